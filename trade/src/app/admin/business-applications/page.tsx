@@ -9,6 +9,10 @@ import { useRouter } from "next/navigation";
 import { applicationDataSchema, applicationStatusSchema, applicationRejectionReasons, opportunitySchema, type ApplicationData, type OpportunityFormData } from "@/schemas";
 import { validateOrThrow } from "@/utils/validation";
 import { validateStatusTransition, type ApplicationStatus } from "@/utils/application-status-machine";
+import { calculateChecklistScore } from "@/utils/due-diligence";
+import { normalizeContractType, type ContractType } from "@/utils/contract-type";
+import { opportunityIndex } from "@/utils/doc-index";
+import { getValidationMessage } from "@/utils/validation-error";
 import toast from "react-hot-toast";
 import { ErrorBoundary } from "@/components/error-boundary";
 import { usePagination } from "@/hooks/usePagination";
@@ -815,7 +819,7 @@ function AdminApplicationsPageContent() {
           dueDiligence,
           dueDiligenceNotes,
           riskRating,
-          dueDiligenceScore: calculateDueDiligenceScore(),
+          dueDiligenceScore: calculateChecklistScore(app.data.dueDiligenceChecklist),
           reviewedBy: user?.key || 'admin',
           reviewedAt: new Date().toISOString(),
           approvedAt: new Date().toISOString(),
@@ -867,7 +871,7 @@ function AdminApplicationsPageContent() {
               dueDiligence,
               dueDiligenceNotes,
               riskRating,
-              dueDiligenceScore: calculateDueDiligenceScore(),
+              dueDiligenceScore: calculateChecklistScore(latestDoc.data.dueDiligenceChecklist),
               reviewedBy: user?.key || 'admin',
               reviewedAt: new Date().toISOString()
             }
@@ -893,19 +897,8 @@ function AdminApplicationsPageContent() {
         return `${day}-${month}-${year}`;
       };
       
-      // Map business application contract types to opportunity contract types
-      const contractTypeMap: Record<string, "musharakah" | "mudarabah" | "murabaha" | "ijarah"> = {
-        musharaka: "musharakah",
-        mudaraba: "mudarabah",
-        murabaha: "murabaha",
-        ijara: "ijarah",
-        istisna: "murabaha",
-        musharakah: "musharakah",
-        mudarabah: "mudarabah",
-        ijarah: "ijarah"
-      };
-      
-      const mappedContractType = contractTypeMap[approvedAppData.contractType] || "musharakah";
+      // Opportunities keep the application's contract type (legacy spellings are normalised)
+      const mappedContractType = normalizeContractType(approvedAppData.contractType) as ContractType;
       
       // Use manual terms from state instead of hardcoded values
       const opportunityData: OpportunityFormData = {
@@ -940,7 +933,8 @@ function AdminApplicationsPageContent() {
         collection: "opportunities",
         doc: {
           key: `opp_${app.key}`,
-          data: validatedOpportunity
+          data: validatedOpportunity,
+          description: opportunityIndex(validatedOpportunity)
         }
       });
       
@@ -949,7 +943,7 @@ function AdminApplicationsPageContent() {
       alert(`Application approved! Investment opportunity created for ${approvedAppData.businessName}`);
     } catch (error) {
       console.error('Error approving application:', error);
-      alert(error instanceof Error ? error.message : 'Failed to approve application. Please try again.');
+      alert(error instanceof Error ? getValidationMessage(error) : 'Failed to approve application. Please try again.');
     } finally {
       setProcessing(false);
     }
@@ -1022,7 +1016,7 @@ function AdminApplicationsPageContent() {
       alert('Application rejected successfully');
     } catch (error) {
       console.error('Error rejecting application:', error);
-      alert(error instanceof Error ? error.message : 'Failed to reject application. Please try again.');
+      alert(error instanceof Error ? getValidationMessage(error) : 'Failed to reject application. Please try again.');
     } finally {
       setProcessing(false);
     }
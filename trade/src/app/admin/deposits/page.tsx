@@ -106,7 +106,7 @@ export default function AdminDepositsPage() {
 
     try {
       setProcessing(true);
-      const userId = deposit.key.split('_')[0];
+      const userId = deposit.data.userId;
 
       // 1. Update deposit request status
       await setDoc({
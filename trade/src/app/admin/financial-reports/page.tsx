@@ -6,7 +6,8 @@ import { initSatellite, onAuthStateChange, listDocs, setDoc, type Doc } from "@j
 import { useEffect, useState } from "react";
 import Link from "next/link";
 import { useRouter } from "next/navigation";
-import type { RevenueReport, ApplicationData } from "@/schemas";
+import type { RevenueReport, RevenueReportRevision, ApplicationData } from "@/schemas";
+import { revenueReportIndex } from "@/utils/doc-index";
 import { MurabahaReportFields } from "@/components/reporting/murabaha-report-fields";
 import { MudarabaReportFields } from "@/components/reporting/mudaraba-report-fields";
 import { MusharakahReportFields } from "@/components/reporting/musharakah-report-fields";
@@ -27,6 +28,7 @@ export default function AdminFinancialReportsPage() {
   const [revisionNotes, setRevisionNotes] = useState("");
   const [updating, setUpdating] = useState(false);
   const [filterStatus, setFilterStatus] = useState<string>("all");
+  const [revisions, setRevisions] = useState<Doc<RevenueReportRevision>[]>([]);
   const router = useRouter();
 
   useEffect(() => {
@@ -79,6 +81,26 @@ export default function AdminFinancialReportsPage() {
     }
   };
 
+  useEffect(() => {
+    if (!selectedReport) {
+      setRevisions([]);
+      return;
+    }
+
+    (async () => {
+      try {
+        const result = await listDocs<RevenueReportRevision>({
+          collection: "revenue_report_revisions",
+          filter: { matcher: { key: `^${selectedReport.key}_v` } },
+        });
+        setRevisions(result.items.sort((a, b) => b.data.revision - a.data.revision));
+      } catch (error) {
+        console.error("Error loading report revisions:", error);
+        setRevisions([]);
+      }
+    })();
+  }, [selectedReport]);
+
   const handleUpdateStatus = async (status: "approved" | "revision_requested" | "under_review") => {
     if (!selectedReport || !user) return;
 
@@ -91,8 +113,9 @@ export default function AdminFinancialReportsPage() {
         reviewedAt: new Date().toISOString(),
       };
 
+      // Review notes are required by the satellite when approving
       if (adminNotes.trim()) {
-        updatedData.adminNotes = adminNotes;
+        updatedData.reviewNotes = adminNotes;
       }
 
       if (status === "revision_requested" && revisionNotes.trim()) {
@@ -104,6 +127,7 @@ export default function AdminFinancialReportsPage() {
         doc: {
           key: selectedReport.key,
           data: updatedData,
+          description: revenueReportIndex(updatedData),
           version: selectedReport.version,
         },
       });
@@ -300,6 +324,51 @@ export default function AdminFinancialReportsPage() {
                     </div>
                   </div>
 
+                  {/* Anomaly Flags */}
+                  {selectedReport.data.anomalyScore !== undefined && (
+                    <div className="mb-6 p-4 bg-amber-50 dark:bg-amber-900/20 rounded-lg border border-amber-200 dark:border-amber-800">
+                      <h4 className="text-sm font-semibold text-neutral-700 dark:text-neutral-300 mb-2">
+                        Anomaly Score: {selectedReport.data.anomalyScore}/100
+                      </h4>
+                      {selectedReport.data.anomalyFlags && selectedReport.data.anomalyFlags.length > 0 ? (
+                        <ul className="space-y-1 text-sm text-amber-800 dark:text-amber-300">
+                          {selectedReport.data.anomalyFlags.map((flag) => (
+                            <li key={flag.code}>{flag.message}</li>
+                          ))}
+                        </ul>
+                      ) : (
+                        <p className="text-sm text-neutral-600 dark:text-neutral-400">No anomalies against the approved history</p>
+                      )}
+                    </div>
+                  )}
+
+                  {/* Revision History */}
+                  {revisions.length > 1 && (
+                    <div className="mb-6">
+                      <h4 className="text-sm font-semibold text-neutral-700 dark:text-neutral-300 mb-3">Revision History</h4>
+                      <div className="space-y-3">
+                        {revisions.filter((rev) => rev.data.changes).map((rev) => (
+                          <div key={rev.key} className="p-3 bg-neutral-50 dark:bg-neutral-800 rounded-lg text-sm">
+                            <p className="font-semibold text-neutral-900 dark:text-white mb-1">
+                              Revision {rev.data.revision} • {new Date(rev.data.submittedAt).toLocaleString()}
+                            </p>
+                            {Object.keys(rev.data.changes ?? {}).length === 0 ? (
+                              <p className="text-neutral-600 dark:text-neutral-400">Resubmitted without changes</p>
+                            ) : (
+                              <ul className="space-y-1 text-neutral-700 dark:text-neutral-300">
+                                {Object.entries(rev.data.changes ?? {}).map(([field, change]) => (
+                                  <li key={field}>
+                                    <span className="font-mono">{field}</span>: {JSON.stringify(change.before)} → {JSON.stringify(change.after)}
+                                  </li>
+                                ))}
+                              </ul>
+                            )}
+                          </div>
+                        ))}
+                      </div>
+                    </div>
+                  )}
+
                   {/* Documents */}
                   {selectedReport.data.documents && selectedReport.data.documents.length > 0 && (
                     <div className="mb-6">
@@ -605,7 +674,7 @@ export default function AdminFinancialReportsPage() {
                   <div className="space-y-4">
                     <div>
                       <label className="block text-sm font-semibold text-neutral-700 dark:text-neutral-300 mb-2">
-                        Admin Notes (Optional)
+                        Review Notes (Required for approval)
                       </label>
                       <textarea
                         value={adminNotes}
@@ -632,7 +701,7 @@ export default function AdminFinancialReportsPage() {
                     <div className="flex gap-3">
                       <button
                         onClick={() => handleUpdateStatus("approved")}
-                        disabled={updating}
+                        disabled={updating || !adminNotes.trim()}
                         className="flex-1 px-6 py-3 bg-gradient-to-r from-green-600 to-green-700 hover:from-green-700 hover:to-green-800 text-white font-semibold rounded-lg transition-all shadow-lg disabled:opacity-50"
                       >
                         {updating ? "Processing..." : "Approve"}
@@ -21,6 +21,7 @@ export default function ProfitDistributionPage() {
   const [selectedOpportunity, setSelectedOpportunity] = useState<OpportunityDoc | null>(null);
   const [showDistributionModal, setShowDistributionModal] = useState(false);
   const [processing, setProcessing] = useState(false);
+  const [confirmingDraft, setConfirmingDraft] = useState<Doc<ProfitDistribution> | null>(null);
   
   // Distribution form state
   const [distributionPeriod, setDistributionPeriod] = useState("");
@@ -115,13 +116,15 @@ export default function ProfitDistributionPage() {
 
       const totalInvested = opportunityInvestments.reduce((sum, inv) => sum + inv.data.amount, 0);
 
-      // 2. Create main distribution record
-      const distributionId = `dist_${selectedOpportunity.key}_${Date.now()}`;
-      await setDoc({
+      // 2. Create main distribution record (or confirm the generated draft)
+      const distributionId = confirmingDraft?.key ?? `dist_${selectedOpportunity.key}_${Date.now()}`;
+      const distributionDoc = await setDoc({
         collection: "profit_distributions",
         doc: {
           key: distributionId,
+          version: confirmingDraft?.version,
           data: {
+            ...confirmingDraft?.data,
             opportunityId: selectedOpportunity.key,
             businessName: selectedOpportunity.data.businessName,
             contractType: selectedOpportunity.data.contractType,
@@ -255,7 +258,9 @@ export default function ProfitDistributionPage() {
         collection: "profit_distributions",
         doc: {
           key: distributionId,
+          version: distributionDoc.version,
           data: {
+            ...confirmingDraft?.data,
             opportunityId: selectedOpportunity.key,
             businessName: selectedOpportunity.data.businessName,
             contractType: selectedOpportunity.data.contractType,
@@ -276,6 +281,7 @@ export default function ProfitDistributionPage() {
       await fetchData();
       setShowDistributionModal(false);
       setSelectedOpportunity(null);
+      setConfirmingDraft(null);
       setDistributionPeriod("");
       setTotalProfitAmount("");
       setNotes("");
@@ -289,8 +295,25 @@ export default function ProfitDistributionPage() {
     }
   };
 
+  const handleReviewDraft = (draft: Doc<ProfitDistribution>) => {
+    const opportunity = opportunities.find(opp => opp.key === draft.data.opportunityId);
+    if (!opportunity) {
+      alert("The opportunity for this draft is no longer active");
+      return;
+    }
+
+    setConfirmingDraft(draft);
+    setSelectedOpportunity(opportunity);
+    setDistributionPeriod(draft.data.distributionPeriod);
+    setTotalProfitAmount(String(draft.data.totalProfitAmount));
+    setNotes(draft.data.notes || "");
+    setShowDistributionModal(true);
+  };
+
   const getStatusColor = (status: string) => {
     switch (status) {
+      case 'draft':
+        return 'bg-primary-100 text-primary-800 dark:bg-primary-900/30 dark:text-primary-400';
       case 'completed':
         return 'bg-success-100 text-success-800 dark:bg-success-900/30 dark:text-success-400';
       case 'processing':
@@ -549,6 +572,14 @@ export default function ProfitDistributionPage() {
                         <span className={`px-3 py-1 rounded-full text-xs font-semibold ${getStatusColor(dist.data.status)}`}>
                           {dist.data.status}
                         </span>
+                        {dist.data.status === 'draft' && (
+                          <button
+                            onClick={() => handleReviewDraft(dist)}
+                            className="ml-3 text-xs font-semibold text-primary-600 hover:text-primary-700 dark:text-primary-400"
+                          >
+                            Review &amp; confirm
+                          </button>
+                        )}
                       </td>
                     </tr>
                   ))
@@ -572,6 +603,7 @@ export default function ProfitDistributionPage() {
                   onClick={() => {
                     setShowDistributionModal(false);
                     setSelectedOpportunity(null);
+                    setConfirmingDraft(null);
                   }}
                   className="p-2 hover:bg-neutral-100 dark:hover:bg-neutral-800 rounded-lg transition-colors"
                 >
@@ -667,6 +699,7 @@ export default function ProfitDistributionPage() {
                   onClick={() => {
                     setShowDistributionModal(false);
                     setSelectedOpportunity(null);
+                    setConfirmingDraft(null);
                   }}
                   className="flex-1 px-4 py-3 bg-neutral-100 dark:bg-neutral-800 hover:bg-neutral-200 dark:hover:bg-neutral-700 text-neutral-700 dark:text-neutral-300 rounded-lg font-medium transition-colors"
                 >
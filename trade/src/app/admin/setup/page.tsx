@@ -4,6 +4,7 @@ import { useEffect, useState } from "react";
 import { useRouter } from "next/navigation";
 import { initSatellite, onAuthStateChange, setDoc, getDoc } from "@junobuild/core";
 import type { User } from "@junobuild/core";
+import { getValidationMessage } from "@/utils/validation-error";
 
 export default function AdminSetupPage() {
   const router = useRouter();
@@ -102,7 +103,7 @@ export default function AdminSetupPage() {
       router.push("/admin/dashboard");
     } catch (err: any) {
       console.error("Error creating admin profile:", err);
-      setError(err.message || "Failed to create admin profile");
+      setError(getValidationMessage(err) || "Failed to create admin profile");
       setSaving(false);
     }
   };
@@ -11,6 +11,7 @@ import {
 } from "@/schemas";
 import { useRouter } from "next/navigation";
 import { ErrorBoundary } from "@/components/error-boundary";
+import { getValidationMessage } from "@/utils/validation-error";
 
 interface AdminWithPermissions extends AdminProfile {
   permissions: AdminPermissions;
@@ -168,7 +169,7 @@ function AdminTeamPageContent() {
       alert(`${admin.data.displayName} has been ${action}d successfully.`);
     } catch (error: any) {
       console.error(`Error ${action}ing admin:`, error);
-      alert(`Failed to ${action} admin: ${error.message || "Unknown error"}`);
+      alert(`Failed to ${action} admin: ${getValidationMessage(error) || "Unknown error"}`);
     }
   };
 
@@ -195,7 +196,7 @@ function AdminTeamPageContent() {
       alert(`${admin.data.displayName} has been deleted (deactivated).`);
     } catch (error: any) {
       console.error("Error deleting admin:", error);
-      alert(`Failed to delete admin: ${error.message || "Unknown error"}`);
+      alert(`Failed to delete admin: ${getValidationMessage(error) || "Unknown error"}`);
     }
   };
 
@@ -256,26 +257,7 @@ function AdminTeamPageContent() {
         doc: docToSave,
       });
 
-      // Log the action
-      if (currentAdminProfile) {
-        await setDoc({
-          collection: "admin_audit_logs",
-          doc: {
-            key: `${selectedAdmin.key}_${Date.now()}`,
-            data: {
-              adminId: currentAdminProfile.key,
-              adminName: currentAdminProfile.data.displayName,
-              adminRole: currentAdminProfile.data.role,
-              action: selectedAdmin.version ? "update_admin" : "create_admin",
-              targetCollection: "admin_profiles",
-              targetId: selectedAdmin.key,
-              targetName: selectedAdmin.data.displayName,
-              timestamp: Date.now(),
-              success: true,
-            },
-          },
-        });
-      }
+      // Audit entry is written by the satellite (admin_audit_logs is satellite-only)
 
       // Reload to get fresh versions
       await loadAdmins();
@@ -292,7 +274,7 @@ function AdminTeamPageContent() {
         setShowEditModal(false);
         setSelectedAdmin(null);
       } else {
-        alert(`Failed to save admin profile: ${error.message || "Unknown error"}`);
+        alert(`Failed to save admin profile: ${getValidationMessage(error) || "Unknown error"}`);
       }
     } finally {
       setSaving(false);
@@ -6,6 +6,7 @@ import { useEffect, useState } from "react";
 import Link from "next/link";
 import { useRouter } from "next/navigation";
 import type { WithdrawalRequest, Wallet, InvestorProfile } from "@/schemas";
+import { withdrawalRequestIndex } from "@/utils/doc-index";
 
 type User = {
   key: string;
@@ -106,7 +107,7 @@ export default function AdminWithdrawalsPage() {
 
     try {
       setProcessing(true);
-      const userId = withdrawal.key.split('_')[0];
+      const userId = withdrawal.data.userId;
 
       // 1. Check wallet balance
       const walletDoc = await getDoc<Wallet>({
@@ -137,6 +138,7 @@ export default function AdminWithdrawalsPage() {
             processedAt: Date.now(),
             processedBy: user?.key || '',
           },
+          description: withdrawalRequestIndex(withdrawal.data),
           version: withdrawal.version,
         }
       });
@@ -211,6 +213,7 @@ export default function AdminWithdrawalsPage() {
             processedAt: Date.now(),
             processedBy: user?.key || '',
           },
+          description: withdrawalRequestIndex(withdrawal.data),
           version: withdrawal.version,
         }
       });
@@ -11,6 +11,7 @@ import { validateData } from "@/utils/validation";
 import { applicationDataSchema } from "@/schemas";
 import toast from "react-hot-toast";
 import { logger } from "@/utils/logger";
+import { getValidationMessage } from "@/utils/validation-error";
 import { validateStatusTransition, type ApplicationStatus } from "@/utils/application-status-machine";
 import { ErrorBoundary } from "@/components/error-boundary";
 import { StepIndicator } from "@/components/step-indicator";
@@ -432,6 +433,8 @@ function ApplyForFinancingPageContent() {
             submittedAt: new Date().toISOString(),
             updatedAt: new Date().toISOString(),
             resubmittedAt: isResubmission ? new Date().toISOString() : undefined,
+            // Required by the satellite for rejected -> pending
+            isResubmission: isResubmission || undefined,
             // Clear rejection fields when resubmitting
             rejectionReason: undefined,
             rejectionAllowsResubmit: undefined,
@@ -452,7 +455,7 @@ function ApplyForFinancingPageContent() {
       router.push("/business/dashboard");
     } catch (err: any) {
       logger.error("Application error:", err);
-      setError(err.message || "Failed to submit application");
+      setError(getValidationMessage(err) || "Failed to submit application");
       setSubmitting(false);
     }
   };
@@ -8,6 +8,7 @@ import Link from "next/link";
 import { useRouter } from "next/navigation";
 import { revenueReportSchema, calculateFinancialMetrics, type RevenueReport, type FinancialMetrics, type ApplicationData } from "@/schemas";
 import { validateData } from "@/utils/validation";
+import { revenueReportIndex } from "@/utils/doc-index";
 import { AreaChart, Area, XAxis, YAxis, CartesianGrid, Tooltip, ResponsiveContainer, LineChart, Line } from "recharts";
 import toast from "react-hot-toast";
 import { logger } from "@/utils/logger";
@@ -31,6 +32,8 @@ export default function BusinessReportingPage() {
   const [contractType, setContractType] = useState<string | null>(null);
   const [reports, setReports] = useState<Doc<RevenueReport>[]>([]);
   const [showForm, setShowForm] = useState(false);
+  // Report sent back for revision - resubmitted under the same key
+  const [revisingReport, setRevisingReport] = useState<Doc<RevenueReport> | null>(null);
   const [uploadingDoc, setUploadingDoc] = useState(false);
   const router = useRouter();
 
@@ -222,13 +225,16 @@ export default function BusinessReportingPage() {
       await setDoc({
         collection: "revenue_reports",
         doc: {
-          key: `report_${Date.now()}`,
+          key: revisingReport?.key ?? `report_${Date.now()}`,
           data: validation.data as RevenueReport,
+          description: revenueReportIndex(validation.data as RevenueReport),
+          version: revisingReport?.version,
         },
       });
 
       toast.success("Revenue report submitted successfully!");
       setShowForm(false);
+      setRevisingReport(null);
       setFormData({
         reportingPeriod: "monthly",
         periodStart: "",
@@ -936,6 +942,7 @@ export default function BusinessReportingPage() {
                                     ...report.data,
                                     status: "draft",
                                   });
+                                  setRevisingReport(report);
                                   setShowForm(true);
                                 }}
                                 className="mt-2 text-xs px-3 py-1 bg-amber-600 hover:bg-amber-700 text-white rounded-lg transition-colors"
@@ -16,6 +16,7 @@ import { downloadInvestmentCertificate, type CertificateData } from "@/utils/pdf
 import toast from "react-hot-toast";
 import { useRateLimit } from "@/hooks/useRateLimit";
 import { batchGetDocs, documentCache } from "@/utils/batch-fetch";
+import { normalizeContractType, type ContractType } from "@/utils/contract-type";
 import { InvestmentCardSkeleton, OpportunityCardSkeleton, PortfolioStatsSkeleton } from "@/components/skeletons";
 import { OfflineBanner } from "@/components/offline-banner";
 import { trackPageView, trackInvestment, trackInteraction } from "@/utils/analytics";
@@ -1591,13 +1592,14 @@ export default function MemberDashboardPage() {
                     const daysRemaining = Math.ceil((new Date(opportunity.data.campaignDeadline.split('-').reverse().join('-')).getTime() - Date.now()) / (1000 * 60 * 60 * 24));
                     
                     const contractColors = {
-                      musharakah: { border: "business", gradient: "from-business-600 via-business-500 to-business-700", hover: "hover:border-business-400 dark:hover:border-business-600" },
-                      mudarabah: { border: "primary", gradient: "from-primary-600 via-primary-500 to-primary-700", hover: "hover:border-primary-400 dark:hover:border-primary-600" },
+                      musharaka: { border: "business", gradient: "from-business-600 via-business-500 to-business-700", hover: "hover:border-business-400 dark:hover:border-business-600" },
+                      mudaraba: { border: "primary", gradient: "from-primary-600 via-primary-500 to-primary-700", hover: "hover:border-primary-400 dark:hover:border-primary-600" },
                       murabaha: { border: "success", gradient: "from-success-600 via-success-500 to-success-700", hover: "hover:border-success-400 dark:hover:border-success-600" },
-                      ijarah: { border: "violet", gradient: "from-violet-600 via-violet-500 to-violet-700", hover: "hover:border-violet-400 dark:hover:border-violet-600" }
+                      ijara: { border: "violet", gradient: "from-violet-600 via-violet-500 to-violet-700", hover: "hover:border-violet-400 dark:hover:border-violet-600" },
+                      istisna: { border: "success", gradient: "from-success-600 via-success-500 to-success-700", hover: "hover:border-success-400 dark:hover:border-success-600" }
                     };
                     
-                    const colors = contractColors[opportunity.data.contractType] || contractColors.musharakah;
+                    const colors = contractColors[normalizeContractType(opportunity.data.contractType) as ContractType] || contractColors.musharaka;
 
                     return (
                       <div
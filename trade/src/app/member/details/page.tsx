@@ -7,6 +7,7 @@ import { useEffect, useState, Suspense } from "react";
 import Link from "next/link";
 import { useRouter, useSearchParams } from "next/navigation";
 import type { Investment, Transaction } from "@/schemas";
+import { cancelInvestment, isWithinCoolingOff } from "@/utils/investment-actions";
 
 type User = {
   key: string;
@@ -18,6 +19,7 @@ function InvestmentDetailsPageContent() {
   const [contractType, setContractType] = useState<string>("Musharakah");
   const [transactions, setTransactions] = useState<Doc<Transaction>[]>([]);
   const [loading, setLoading] = useState(true);
+  const [cancelling, setCancelling] = useState(false);
   const router = useRouter();
   const searchParams = useSearchParams();
   const investmentId = searchParams.get('id');
@@ -92,6 +94,20 @@ function InvestmentDetailsPageContent() {
     }
   };
 
+  const handleCancel = async () => {
+    if (!investment) return;
+    if (!confirm("Cancel this investment? The amount will be refunded to your wallet.")) return;
+
+    setCancelling(true);
+    const result = await cancelInvestment(investment);
+    setCancelling(false);
+    alert(result.message);
+
+    if (result.success) {
+      await fetchInvestmentData();
+    }
+  };
+
   const formatCurrency = (amount: number): string => {
     return new Intl.NumberFormat("en-NG", {
       style: "currency",
@@ -167,6 +183,15 @@ function InvestmentDetailsPageContent() {
               >
                 View Performance
               </Link>
+              {investment.data.status === "pending" && isWithinCoolingOff(investment.created_at) && (
+                <button
+                  onClick={handleCancel}
+                  disabled={cancelling}
+                  className="px-4 py-2 bg-error-600 hover:bg-error-700 disabled:opacity-50 text-white rounded-lg text-sm font-semibold transition-colors"
+                >
+                  {cancelling ? "Cancelling..." : "Cancel Investment"}
+                </button>
+              )}
               <span className={`px-3 py-2 rounded-lg text-sm font-semibold ${
                 investment.data.status === "active"
                   ? "bg-success-100 dark:bg-success-900/30 text-success-700 dark:text-success-300"
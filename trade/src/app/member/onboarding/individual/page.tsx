@@ -7,6 +7,7 @@ import Link from "next/link";
 import { useRouter } from "next/navigation";
 import { individualInvestorSchema, type IndividualInvestor } from "@/schemas";
 import { validateData, formatZodErrors } from "@/utils/validation";
+import { keepReviewedFields } from "@/utils/investor-profile";
 import { setDoc, uploadFile, initSatellite, onAuthStateChange, getDoc } from "@junobuild/core";
 
 type FormErrors = Record<string, string>;
@@ -185,7 +186,9 @@ export default function IndividualOnboardingPage() {
             memberType: 'individual',
             submittedAt: new Date().toISOString(),
             status: 'pending',
-            kycStatus: 'pending'
+            kycStatus: 'pending',
+            // Self-declared; reviewers set `accredited` once it is verified
+            accreditationRequested: result.data.accredited
           };
           
           console.log("Profile data to save:", profileData);
@@ -207,7 +210,7 @@ export default function IndividualOnboardingPage() {
             collection: "individual_investor_profiles",
             doc: {
               key: user.key,
-              data: profileData,
+              data: keepReviewedFields(profileData, existingProfile?.data as Record<string, unknown> | undefined),
               // Include version if updating existing document
               ...(existingProfile ? { version: existingProfile.version } : {})
             }
@@ -7,6 +7,7 @@ import { useEffect, useState } from "react";
 import Link from "next/link";
 import { useRouter } from "next/navigation";
 import type { Wallet, DepositRequest, WithdrawalRequest } from "@/schemas";
+import { withdrawalRequestIndex } from "@/utils/doc-index";
 import { initiatePaystackPayment, initiateStripePayment, verifyPaystackPayment, verifyStripePayment } from "@/utils/payment-providers";
 
 type User = {
@@ -151,13 +152,14 @@ export default function WalletPage() {
             const verification = await verifyPaystackPayment(reference);
             
             if (verification.success) {
+              // The wallet is credited when an admin approves the deposit - clients never move balances
               const depositRequest: DepositRequest = {
                 userId: user.key,
                 amount,
                 currency: "NGN",
                 paymentMethod: "card",
                 paymentReference: reference,
-                status: "approved", // Auto-approve card payments after verification
+                status: "pending",
               };
 
               await setDoc({
@@ -168,30 +170,7 @@ export default function WalletPage() {
                 }
               });
 
-              // Update wallet balance
-              const currentWallet = await getDoc<Wallet>({
-                collection: "wallets",
-                key: `wallet_${user.key}`
-              });
-
-              const newBalance = (currentWallet?.data.availableBalance || 0) + amount;
-              
-              await setDoc({
-                collection: "wallets",
-                doc: {
-                  key: `wallet_${user.key}`,
-                  data: {
-                    ...currentWallet?.data,
-                    userId: user.key,
-                    availableBalance: newBalance,
-                    totalBalance: newBalance,
-                    currency: "NGN"
-                  } as Wallet,
-                  version: currentWallet?.version
-                }
-              });
-
-              alert(`Payment successful! ₦${amount.toLocaleString()} has been added to your wallet.`);
+              alert(`Payment successful! ₦${amount.toLocaleString()} will be added to your wallet once the deposit is confirmed.`);
               setShowDepositModal(false);
               setDepositAmount("");
               await fetchWalletData();
@@ -223,13 +202,14 @@ export default function WalletPage() {
             const verification = await verifyStripePayment(reference);
             
             if (verification.success) {
+              // The wallet is credited when an admin approves the deposit - clients never move balances
               const depositRequest: DepositRequest = {
                 userId: user.key,
                 amount,
                 currency: "NGN",
                 paymentMethod: "card",
                 paymentReference: reference,
-                status: "approved", // Auto-approve card payments after verification
+                status: "pending",
               };
 
               await setDoc({
@@ -240,30 +220,7 @@ export default function WalletPage() {
                 }
               });
 
-              // Update wallet balance
-              const currentWallet = await getDoc<Wallet>({
-                collection: "wallets",
-                key: `wallet_${user.key}`
-              });
-
-              const newBalance = (currentWallet?.data.availableBalance || 0) + amount;
-              
-              await setDoc({
-                collection: "wallets",
-                doc: {
-                  key: `wallet_${user.key}`,
-                  data: {
-                    ...currentWallet?.data,
-                    userId: user.key,
-                    availableBalance: newBalance,
-                    totalBalance: newBalance,
-                    currency: "NGN"
-                  } as Wallet,
-                  version: currentWallet?.version
-                }
-              });
-
-              alert(`Payment successful! ₦${amount.toLocaleString()} has been added to your wallet.`);
+              alert(`Payment successful! ₦${amount.toLocaleString()} will be added to your wallet once the deposit is confirmed.`);
               setShowDepositModal(false);
               setDepositAmount("");
               await fetchWalletData();
@@ -363,6 +320,7 @@ export default function WalletPage() {
         doc: {
           key: `withdrawal_${user.key}_${Date.now()}`,
           data: withdrawalRequest,
+          description: withdrawalRequestIndex(withdrawalRequest),
         }
       });
 
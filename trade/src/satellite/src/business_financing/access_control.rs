@@ -1,5 +1,6 @@
-use junobuild_satellite::AssertSetDocContext;
 use serde_json::Value;
+use super::dispatch::SetDocCall;
+use super::validation_error::ValidationError;
 
 /// Role-based access control
 #[allow(dead_code)]
@@ -63,46 +64,64 @@ pub fn validate_user_permission(
     resource: &str,
 ) -> Result<(), String> {
     match action {
-        "approve_application" | "reject_application" => {
-            if !user_role.can_approve() {
-                return Err(format!(
+        "approve_application" | "reject_application" if !user_role.can_approve() => {
+            return Err(ValidationError::new(
+                "INSUFFICIENT_ROLE",
+                format!(
                     "❌ Access Denied: Only admin staff (Reviewer or above) can {} on {}",
                     action, resource
-                ));
-            }
+                ),
+            )
+            .param("action", action)
+            .param("resource", resource)
+            .param("requiredRole", "reviewer")
+            .into());
         }
-        "review_kyc" => {
-            if !user_role.is_admin() {
-                return Err(format!(
-                    "❌ Access Denied: Only admin staff can {} on {}",
-                    action, resource
-                ));
-            }
+        "review_kyc" if !user_role.is_admin() => {
+            return Err(ValidationError::new(
+                "INSUFFICIENT_ROLE",
+                format!("❌ Access Denied: Only admin staff can {} on {}", action, resource),
+            )
+            .param("action", action)
+            .param("resource", resource)
+            .param("requiredRole", "viewer")
+            .into());
         }
-        "manage_team" | "distribute_profits" => {
-            if !user_role.is_manager_or_above() {
-                return Err(format!(
+        "manage_team" | "distribute_profits" if !user_role.is_manager_or_above() => {
+            return Err(ValidationError::new(
+                "INSUFFICIENT_ROLE",
+                format!(
                     "❌ Access Denied: Only Managers or Super Admins can {} on {}",
                     action, resource
-                ));
-            }
+                ),
+            )
+            .param("action", action)
+            .param("resource", resource)
+            .param("requiredRole", "manager")
+            .into());
         }
-        "submit_application" | "upload_documents" => {
-            if user_role != UserRole::Business && !user_role.is_admin() {
-                return Err(format!(
-                    "❌ Access Denied: Only businesses can {} for {}",
-                    action, resource
-                ));
-            }
+        "submit_application" | "upload_documents" if user_role != UserRole::Business && !user_role.is_admin() => {
+            return Err(ValidationError::new(
+                "INSUFFICIENT_ROLE",
+                format!("❌ Access Denied: Only businesses can {} for {}", action, resource),
+            )
+            .param("action", action)
+            .param("resource", resource)
+            .param("requiredRole", "business")
+            .into());
         }
         "invest" | "view_opportunities" => {
             // All authenticated users (Member or Business) can invest/view
             // Admin authentication is handled by Juno/Internet Identity
         }
-        "create_opportunity" => {
-            if !user_role.is_manager_or_above() {
-                return Err("❌ Access Denied: Only Managers can create opportunities".to_string());
-            }
+        "create_opportunity" if !user_role.is_manager_or_above() => {
+            return Err(ValidationError::new(
+                "INSUFFICIENT_ROLE",
+                "❌ Access Denied: Only Managers can create opportunities",
+            )
+            .param("action", action)
+            .param("requiredRole", "manager")
+            .into());
         }
         _ => {
             // Default: allow
@@ -126,68 +145,69 @@ pub fn validate_data_ownership(
     
     // Users can only access their own data
     if user_id != resource_owner_id {
-        return Err("❌ Access Denied: You can only access your own data".to_string());
+        return Err(ValidationError::new(
+            "NOT_DOCUMENT_OWNER",
+            "❌ Access Denied: You can only access your own data",
+        )
+        .critical()
+        .into());
     }
     
     Ok(())
 }
 
 /// Validates admin-only operations
-pub fn assert_admin_only_operation(context: &AssertSetDocContext) -> Result<(), String> {
-    let collection = &context.data.collection;
-    
-    // SKIP admin_profiles - bootstrap logic handled in assert_can_manage_admins
-    if collection == "admin_profiles" {
-        return Ok(());
-    }
-    
-    // Parse the proposed data from JSON string
-    let data_str = std::str::from_utf8(&context.data.data.proposed.data)
-        .map_err(|e| format!("Failed to decode data as UTF-8: {}", e))?;
-    
-    let data: Value = serde_json::from_str(data_str)
-        .map_err(|e| format!("Failed to parse data: {}", e))?;
-    
-    let data = data.as_object()
-        .ok_or("Invalid data format")?;
+/// (admin_profiles go through the bootstrap and hierarchy checks in admin_permissions.rs)
+pub fn assert_admin_only_operation<T>(call: &SetDocCall<T>) -> Result<(), String> {
+    let collection = call.collection();
+    let data = call.proposed_json()?
+        .as_object()
+        .ok_or_else(|| ValidationError::new("INVALID_FORMAT", "❌ Invalid data format").critical())?;
     
     // Operations that require admin privileges
-    let admin_only_collections = vec![
+    // (admin_audit_logs are written by the satellite only - see audit_log.rs)
+    let admin_only_collections = [
         "opportunities",           // Only admins create opportunities
         "profit_distributions",    // Only admins distribute profits
-        "admin_audit_logs",        // Only admins can write audit logs
     ];
     
-    if admin_only_collections.contains(&collection.as_str()) {
+    if admin_only_collections.contains(&collection) {
         // Check if user has admin role (in production, this would check JWT or session)
-        // For audit logs, check adminId field instead of createdBy
-        let admin_field = if collection == "admin_audit_logs" {
-            data.get("adminId").and_then(|v| v.as_str())
-        } else {
-            data.get("createdBy").and_then(|v| v.as_str())
-        };
+        let admin_field = data.get("createdBy").and_then(|v| v.as_str());
         
         if let Some(admin_user) = admin_field {
             // In production, verify admin_user is actually an admin
             if !is_admin_user(admin_user) {
-                return Err(format!(
-                    "❌ Access Denied: Only admins can create {} records",
-                    collection
-                ));
+                return Err(ValidationError::new(
+                    "INSUFFICIENT_ROLE",
+                    format!("❌ Access Denied: Only admins can create {} records", collection),
+                )
+                .field("createdBy")
+                .param("collection", collection)
+                .critical()
+                .into());
             }
         } else {
-            let required_field = if collection == "admin_audit_logs" { "adminId" } else { "createdBy" };
-            return Err(format!("❌ Admin operations must include {} field", required_field));
+            return Err(ValidationError::new("MISSING_FIELD", "❌ Admin operations must include createdBy field")
+                .field("createdBy")
+                .param("collection", collection)
+                .into());
         }
     }
     
     // Prevent non-admins from changing status to 'approved'
-    if collection == "business_applications" || collection == "revenue_reports" {
+    // (revenue_reports are covered by revenue_report_workflow.rs)
+    if collection == "business_applications" {
         if let Some(status) = data.get("status").and_then(|v| v.as_str()) {
             if status == "approved" {
                 // Verify this is being done by an admin
                 if !data.contains_key("approvedBy") && !data.contains_key("reviewedBy") {
-                    return Err("❌ Approval must be done by an admin with proper authorization".to_string());
+                    return Err(ValidationError::new(
+                        "MISSING_FIELD",
+                        "❌ Approval must be done by an admin with proper authorization",
+                    )
+                    .field("reviewedBy")
+                    .into());
                 }
             }
         }
@@ -215,10 +235,16 @@ impl RateLimiter {
         request_count: u32,
     ) -> Result<(), String> {
         if request_count > self.max_requests_per_hour {
-            return Err(format!(
-                "❌ Rate Limit Exceeded: User {} has made {} requests (max: {})",
-                user_id, request_count, self.max_requests_per_hour
-            ));
+            return Err(ValidationError::new(
+                "RATE_LIMIT_EXCEEDED",
+                format!(
+                    "❌ Rate Limit Exceeded: User {} has made {} requests (max: {})",
+                    user_id, request_count, self.max_requests_per_hour
+                ),
+            )
+            .param("max", self.max_requests_per_hour)
+            .param("actual", request_count)
+            .into());
         }
         Ok(())
     }
@@ -251,10 +277,13 @@ pub fn validate_ip_access(ip_address: &str, _allowed_countries: Vec<&str>) -> Re
     // For now, just a placeholder implementation
     
     // Block known malicious IPs (example)
-    let blocked_ips = vec!["0.0.0.0", "127.0.0.1"];
+    let blocked_ips = ["0.0.0.0", "127.0.0.1"];
     
     if blocked_ips.contains(&ip_address) {
-        return Err(format!("❌ Access Denied: IP {} is blocked", ip_address));
+        return Err(ValidationError::new("IP_BLOCKED", format!("❌ Access Denied: IP {} is blocked", ip_address))
+            .param("ip", ip_address)
+            .critical()
+            .into());
     }
     
     Ok(())
@@ -266,7 +295,7 @@ pub fn validate_single_active_application(
     business_id: &str,
     existing_applications: Vec<Value>,
 ) -> Result<(), String> {
-    let active_statuses = vec!["pending", "under_review", "in-review"];
+    let active_statuses = ["pending", "under_review", "in-review"];
     
     for app in existing_applications {
         let app_business_id = app.get("businessId")
@@ -279,10 +308,15 @@ pub fn validate_single_active_application(
             .unwrap_or("unknown");
         
         if app_business_id == business_id && active_statuses.contains(&status) {
-            return Err(format!(
-                "❌ Access Denied: You already have an active application (status: {}). Wait for it to be processed.",
-                status
-            ));
+            return Err(ValidationError::new(
+                "ACTIVE_APPLICATION_EXISTS",
+                format!(
+                    "❌ Access Denied: You already have an active application (status: {}). Wait for it to be processed.",
+                    status
+                ),
+            )
+            .param("status", status)
+            .into());
         }
     }
     
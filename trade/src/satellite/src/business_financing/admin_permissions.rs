@@ -1,129 +1,146 @@
-use junobuild_satellite::{AssertSetDocContext, AssertDeleteDocContext, get_doc};
-use serde_json::Value;
+use junobuild_satellite::{
+    AssertDeleteDocContext, count_collection_docs_store, get_controllers,
+    get_doc, get_doc_store, id,
+};
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use super::audit_log::{
+    AUDIT_LOG_COLLECTION, append_audit_entry, build_audit_entry, diff_fields, parse_doc_json,
+};
+use super::dispatch::SetDocCall;
+use super::models::{AdminProfileDoc, BusinessApplication, decode_doc};
+use super::validation_error::ValidationError;
+
+/// Key of the audit entry recording the first super_admin bootstrap.
+/// Its presence closes the bootstrap path even if every admin profile is later removed.
+const BOOTSTRAP_RECORD_KEY: &str = "super_admin_bootstrap";
 
 /// Fetches admin profile from datastore (SECURITY-CRITICAL)
 /// Always use this function to verify admin permissions - never trust frontend data
 pub fn get_admin_profile(user_id: &str) -> Result<AdminProfile, String> {
     // Fetch from admin_profiles collection
     let doc = get_doc("admin_profiles".to_string(), user_id.to_string())
-        .ok_or_else(|| format!("❌ Admin profile not found for user: {}", user_id))?;
-    
-    // Parse document data
-    let data_bytes = doc.data;
-    let data_str = std::str::from_utf8(&data_bytes)
-        .map_err(|e| format!("❌ Failed to decode admin profile as UTF-8: {}", e))?;
-    let data: Value = serde_json::from_str(data_str)
-        .map_err(|e| format!("❌ Invalid JSON in admin profile: {}", e))?;
+        .ok_or_else(|| {
+            ValidationError::new(
+                "ADMIN_PROFILE_NOT_FOUND",
+                format!("❌ Admin profile not found for user: {}", user_id),
+            )
+            .param("userId", user_id)
+        })?;
     
-    // Extract and validate fields
-    let role = data["role"].as_str().unwrap_or("viewer").to_string();
-    let approval_limit = data["approvalLimit"].as_f64().unwrap_or(0.0);
-    let is_active = data["isActive"].as_bool().unwrap_or(false);
+    let profile: AdminProfileDoc = decode_doc("admin_profiles", &doc.data)?;
     
     // Validate admin is active
-    if !is_active {
-        return Err(format!("❌ Admin account '{}' is inactive", user_id));
+    if !profile.is_active {
+        return Err(ValidationError::new(
+            "ADMIN_INACTIVE",
+            format!("❌ Admin account '{}' is inactive", user_id),
+        )
+        .param("userId", user_id)
+        .into());
     }
     
     Ok(AdminProfile {
-        user_id: data["userId"].as_str().unwrap_or(user_id).to_string(),
-        display_name: data["displayName"].as_str().unwrap_or("Unknown").to_string(),
-        role,
-        approval_limit,
-        is_active,
+        role: profile.role,
+        approval_limit: profile.approval_limit,
     })
 }
 
-/// Enhanced admin role validation with datastore lookup
-/// SECURITY: Never trust role data from request - always fetch from datastore
-#[allow(dead_code)]
-pub fn validate_admin_role_from_datastore(
-    context: &AssertSetDocContext,
-    required_role: &str,
-) -> Result<AdminProfile, String> {
-    let caller = &context.caller.to_text();
-    
-    // Fetch admin profile from datastore
-    let admin_profile = get_admin_profile(caller)?;
-    
-    // Validate role level
-    if !has_sufficient_role(&admin_profile.role, required_role) {
-        return Err(format!(
-            "❌ Access Denied: Your role '{}' is insufficient. Required: '{}' or higher",
-            admin_profile.role, required_role
-        ));
-    }
-    
-    Ok(admin_profile)
-}
-
-/// Admin Profile structure matching frontend schema
-#[allow(dead_code)]
-#[derive(Debug)]
+/// Permissions of an active admin, as stored in admin_profiles
+#[derive(Debug, Clone)]
 pub struct AdminProfile {
-    pub user_id: String,
-    pub display_name: String,
     pub role: String,
     pub approval_limit: f64,
-    pub is_active: bool,
+}
+
+/// Admin profile lookups memoized for the duration of one hook call
+/// Lookup failures are cached too so a missing profile is only read once
+#[derive(Default)]
+pub struct AdminProfileCache {
+    profiles: RefCell<BTreeMap<String, Result<AdminProfile, String>>>,
+}
+
+impl AdminProfileCache {
+    pub fn get(&self, user_id: &str) -> Result<AdminProfile, String> {
+        if let Some(cached) = self.profiles.borrow().get(user_id) {
+            return cached.clone();
+        }
+        
+        let profile = get_admin_profile(user_id);
+        self.profiles.borrow_mut().insert(user_id.to_string(), profile.clone());
+        profile
+    }
 }
 
 /// Validates admin approval with amount-based limits
 /// Enforces role hierarchy and approval limits from admin profile
 pub fn assert_admin_approval_with_limits(
-    context: &AssertSetDocContext,
+    call: &SetDocCall<BusinessApplication>,
 ) -> Result<(), String> {
-    let collection = &context.data.collection;
-    
-    // Only validate business_applications collection
-    if collection != "business_applications" {
-        return Ok(());
-    }
-    
-    let doc_data = &context.data.data.proposed.data;
-    let data_str = std::str::from_utf8(doc_data)
-        .map_err(|e| format!("❌ Failed to decode data as UTF-8: {}", e))?;
-    let data: Value = serde_json::from_str(data_str)
-        .map_err(|e| format!("❌ Invalid JSON data: {}", e))?;
+    let application = &call.proposed;
     
     // Check if status is being set to "approved"
-    let status = data["status"]
-        .as_str()
-        .ok_or_else(|| "❌ Missing status field".to_string())?;
-    
-    if status != "approved" {
+    if application.status != "approved" {
         return Ok(()); // Only validate approvals
     }
     
+    // Satellite writes (e.g. the derived due diligence score) re-save approved documents
+    if call.is_satellite() {
+        return Ok(());
+    }
+    
     // Get requested amount
-    let requested_amount = data["requestedAmount"]
-        .as_f64()
-        .ok_or_else(|| "❌ Missing or invalid requestedAmount".to_string())?;
+    let requested_amount = application
+        .requested_amount
+        .ok_or_else(|| {
+            ValidationError::new("MISSING_FIELD", "❌ Missing or invalid requestedAmount")
+                .field("requestedAmount")
+        })?;
     
     // Get approver ID from context.caller (tamper-proof)
-    let approver_id = context.caller.to_text();
+    let approver_id = call.caller();
     
     // SECURITY: Fetch admin profile from datastore (never trust frontend data)
-    let admin_profile = get_admin_profile(&approver_id)?;
+    let admin_profile = call.admin_profile(&approver_id)?;
     
     // Validate role-based approval limit
     if requested_amount > admin_profile.approval_limit {
-        return Err(format!(
-            "❌ Approval Denied: Amount ₦{:.2} exceeds your approval limit of ₦{:.2} ({} role)",
-            requested_amount, admin_profile.approval_limit, admin_profile.role
-        ));
+        return Err(ValidationError::new(
+            "APPROVAL_LIMIT_EXCEEDED",
+            format!(
+                "❌ Approval Denied: Amount ₦{:.2} exceeds your approval limit of ₦{:.2} ({} role)",
+                requested_amount, admin_profile.approval_limit, admin_profile.role
+            ),
+        )
+        .field("requestedAmount")
+        .param("limit", admin_profile.approval_limit)
+        .param("actual", requested_amount)
+        .param("role", admin_profile.role.as_str())
+        .into());
     }
     
     // Validate approvedBy field matches caller (consistency check)
-    let approved_by = data["approvedBy"]
-        .as_str()
-        .ok_or_else(|| "❌ Missing approvedBy field".to_string())?;
+    let approved_by = application
+        .approved_by
+        .as_deref()
+        .ok_or_else(|| {
+            ValidationError::new("MISSING_FIELD", "❌ Missing approvedBy field").field("approvedBy")
+        })?;
     
     if approved_by != approver_id {
-        return Err(format!(
-            "❌ Security violation: approvedBy field ('{}') does not match authenticated caller ('{}')",
-            approved_by, approver_id
-        ));
+        return Err(ValidationError::new(
+            "CALLER_MISMATCH",
+            format!(
+                "❌ Security violation: approvedBy field ('{}') does not match authenticated caller ('{}')",
+                approved_by, approver_id
+            ),
+        )
+        .field("approvedBy")
+        .param("expected", approver_id.as_str())
+        .param("actual", approved_by)
+        .critical()
+        .into());
     }
     
     // Validate time-based restrictions for high-value approvals
@@ -143,104 +160,128 @@ pub fn assert_admin_approval_with_limits(
         
         // Check if weekend (Saturday=5, Sunday=6)
         if day_of_week >= 5 {
-            return Err(format!(
-                "❌ Time Restriction: Approvals above ₦10M cannot be processed on weekends. Amount: ₦{:.2}",
-                requested_amount
-            ));
+            return Err(ValidationError::new(
+                "APPROVAL_TIME_RESTRICTED",
+                format!(
+                    "❌ Time Restriction: Approvals above ₦10M cannot be processed on weekends. Amount: ₦{:.2}",
+                    requested_amount
+                ),
+            )
+            .param("reason", "weekend")
+            .param("actual", requested_amount)
+            .into());
         }
         
         // Check if outside business hours (6 AM - 10 PM UTC)
-        if hours < 6 || hours >= 22 {
-            return Err(format!(
-                "❌ Time Restriction: Approvals above ₦10M must be processed between 6 AM - 10 PM (UTC). Current hour: {}:00. Amount: ₦{:.2}",
-                hours, requested_amount
-            ));
+        if !(6..22).contains(&hours) {
+            return Err(ValidationError::new(
+                "APPROVAL_TIME_RESTRICTED",
+                format!(
+                    "❌ Time Restriction: Approvals above ₦10M must be processed between 6 AM - 10 PM (UTC). Current hour: {}:00. Amount: ₦{:.2}",
+                    hours, requested_amount
+                ),
+            )
+            .param("reason", "outside_business_hours")
+            .param("hour", hours)
+            .param("actual", requested_amount)
+            .into());
         }
     }
     
     // Validate dual authorization requirement
     if requested_amount > 50_000_000.0 {
-        let secondary_approver = data["secondaryApprover"].as_str();
+        let secondary_approver = application.secondary_approver.as_deref();
         
-        if secondary_approver.is_none() || secondary_approver == Some("") {
-            return Err(format!(
-                "❌ Dual authorization required: Amount ₦{:.2} exceeds ₦50M threshold. Secondary approver must be assigned.",
-                requested_amount
-            ));
+        if secondary_approver.is_none_or(str::is_empty) {
+            return Err(ValidationError::new(
+                "DUAL_AUTHORIZATION_REQUIRED",
+                format!(
+                    "❌ Dual authorization required: Amount ₦{:.2} exceeds ₦50M threshold. Secondary approver must be assigned.",
+                    requested_amount
+                ),
+            )
+            .field("secondaryApprover")
+            .param("threshold", 50_000_000.0)
+            .param("actual", requested_amount)
+            .into());
         }
     }
     
+    // Log the approval (only the transition into "approved")
+    let was_approved = call
+        .current
+        .as_ref()
+        .is_some_and(|current| current.status == "approved");
+    
+    if !was_approved {
+        log_admin_action(
+            &approver_id,
+            "approve_application",
+            call.collection(),
+            call.key(),
+            call.current_json()?,
+            Some(call.proposed_json()?),
+        )?;
+    }
+    
     Ok(())
 }
 
 /// Validates separation of duties
 /// Ensures reviewer and approver are different people
 pub fn validate_separation_of_duties(
-    context: &AssertSetDocContext,
+    call: &SetDocCall<BusinessApplication>,
 ) -> Result<(), String> {
-    let collection = &context.data.collection;
+    let application = &call.proposed;
     
-    if collection != "business_applications" {
-        return Ok(());
-    }
-    
-    let doc_data = &context.data.data.proposed.data;
-    let data_str = std::str::from_utf8(doc_data)
-        .map_err(|e| format!("❌ Failed to decode data as UTF-8: {}", e))?;
-    let data: Value = serde_json::from_str(data_str)
-        .map_err(|e| format!("❌ Invalid JSON data: {}", e))?;
-    
-    let status = data["status"].as_str();
-    
-    if status != Some("approved") {
+    if application.status != "approved" {
         return Ok(());
     }
     
     // Get reviewer ID and approver ID
-    let reviewed_by = data["reviewedBy"].as_str().unwrap_or("");
-    let approved_by = data["approvedBy"].as_str().unwrap_or("");
+    let reviewed_by = application.reviewed_by.as_deref().unwrap_or("");
+    let approved_by = application.approved_by.as_deref().unwrap_or("");
     
     if !reviewed_by.is_empty() && reviewed_by == approved_by {
-        return Err(
-            "❌ Separation of duties violation: Reviewer cannot approve their own review".to_string()
-        );
+        return Err(ValidationError::new(
+            "SEPARATION_OF_DUTIES",
+            "❌ Separation of duties violation: Reviewer cannot approve their own review",
+        )
+        .field("approvedBy")
+        .critical()
+        .into());
     }
     
     Ok(())
 }
 
 /// Logs admin action to audit trail
-/// Records all sensitive operations for compliance
+/// Records all sensitive operations for compliance as an immutable admin_audit_logs entry
+/// carrying the caller, a before/after diff of the target document and the IC time
 pub fn log_admin_action(
     admin_id: &str,
     action: &str,
     target_collection: &str,
     target_id: &str,
-    metadata: Option<&Value>,
+    before: Option<&Value>,
+    after: Option<&Value>,
 ) -> Result<(), String> {
-    // Generate timestamp
-    let timestamp = ic_cdk::api::time();
-    
-    // Create audit log entry
-    let _log_entry = format!(
-        "{{\"adminId\":\"{}\",\"action\":\"{}\",\"targetCollection\":\"{}\",\"targetId\":\"{}\",\"timestamp\":{},\"metadata\":{}}}",
+    let entry = build_audit_entry(
         admin_id,
         action,
         target_collection,
         target_id,
-        timestamp,
-        metadata.map(|m| m.to_string()).unwrap_or_else(|| "null".to_string())
+        ic_cdk::api::time(),
+        before,
+        after,
     );
     
-    // TODO: Store in admin_audit_logs collection
-    // For now, just return success
-    // set_doc("admin_audit_logs", &format!("{}_{}", admin_id, timestamp), &log_entry)?;
+    append_audit_entry(None, &entry)?;
     
     Ok(())
 }
 
 /// Role hierarchy for permission checks
-#[allow(dead_code)]
 pub fn get_role_level(role: &str) -> i32 {
     match role {
         "super_admin" => 5,
@@ -253,74 +294,162 @@ pub fn get_role_level(role: &str) -> i32 {
 }
 
 /// Check if role has sufficient permission level
-#[allow(dead_code)]
 pub fn has_sufficient_role(user_role: &str, required_role: &str) -> bool {
     get_role_level(user_role) >= get_role_level(required_role)
 }
 
-/// Validates admin can manage other admins
-pub fn assert_can_manage_admins(
-    context: &AssertSetDocContext,
+/// Rejection for an admin whose role is below the one an operation requires
+pub fn insufficient_role(message: String, role: &str, required_role: &str) -> ValidationError {
+    ValidationError::new("INSUFFICIENT_ROLE", message)
+        .param("role", role)
+        .param("requiredRole", required_role)
+}
+
+/// Handles the one-time super_admin bootstrap for admin_profiles writes
+/// Returns `Ok(true)` when the write is an authorised bootstrap (the regular admin
+/// management checks must then be skipped) and `Ok(false)` for every other write.
+///
+/// Self-creation of a super_admin is only open while the admin_profiles collection is
+/// empty and no bootstrap has ever been recorded. Satellite controllers keep the ability
+/// to bootstrap themselves as a recovery path. Every bootstrap is recorded in admin_audit_logs.
+pub fn assert_admin_bootstrap(
+    call: &SetDocCall<AdminProfileDoc>,
+) -> Result<bool, String> {
+    let caller = call.caller();
+    
+    let profile = &call.proposed;
+    let target_user_id = profile.user_id.as_deref().unwrap_or("");
+    
+    if !is_self_super_admin_request(&caller, call.key(), target_user_id, &profile.role, profile.is_active) {
+        return Ok(false);
+    }
+    
+    // Existing admins go through the regular hierarchy checks
+    if call.admin_profile(&caller).is_ok() {
+        return Ok(false);
+    }
+    
+    let is_controller = get_controllers().contains_key(&call.context.caller);
+    let admin_count = count_collection_docs_store(&call.context.data.collection)?;
+    let bootstrap_recorded = get_doc_store(
+        id(),
+        AUDIT_LOG_COLLECTION.to_string(),
+        BOOTSTRAP_RECORD_KEY.to_string(),
+    )?
+    .is_some();
+    
+    if !is_bootstrap_open(is_controller, admin_count, bootstrap_recorded) {
+        return Err(ValidationError::new(
+            "BOOTSTRAP_CLOSED",
+            "❌ Bootstrap Closed: A super admin has already been created. Ask an existing super admin to create your profile.",
+        )
+        .into());
+    }
+    
+    record_bootstrap(&caller, call.proposed_json()?, is_controller, bootstrap_recorded)?;
+    
+    Ok(true)
+}
+
+/// A bootstrap request is an active super_admin profile the caller creates for themselves
+fn is_self_super_admin_request(
+    caller: &str,
+    key: &str,
+    target_user_id: &str,
+    new_role: &str,
+    is_active: bool,
+) -> bool {
+    key == caller && target_user_id == caller && new_role == "super_admin" && is_active
+}
+
+/// Bootstrap is open to anyone only before the first admin exists; controllers can always use it
+fn is_bootstrap_open(is_controller: bool, admin_count: usize, bootstrap_recorded: bool) -> bool {
+    is_controller || (admin_count == 0 && !bootstrap_recorded)
+}
+
+/// Writes the bootstrap event to admin_audit_logs with the satellite as author
+fn record_bootstrap(
+    caller: &str,
+    profile: &Value,
+    via_controller: bool,
+    bootstrap_recorded: bool,
 ) -> Result<(), String> {
-    let collection = &context.data.collection;
+    let timestamp = ic_cdk::api::time();
     
-    if collection != "admin_profiles" {
-        return Ok(());
-    }
+    // The first bootstrap owns the well-known key; later controller recoveries get their own entry
+    let key = if bootstrap_recorded {
+        None
+    } else {
+        Some(BOOTSTRAP_RECORD_KEY.to_string())
+    };
     
-    let caller = context.caller.to_text();
+    let mut entry = build_audit_entry(
+        caller,
+        "bootstrap_super_admin",
+        "admin_profiles",
+        caller,
+        timestamp,
+        None,
+        Some(profile),
+    );
+    entry["metadata"] = json!({ "viaController": via_controller });
     
-    // Parse target admin data first to check if this is initial setup
-    let doc_data = &context.data.data.proposed.data;
-    let data_str = std::str::from_utf8(doc_data)
-        .map_err(|e| format!("❌ Failed to decode data as UTF-8: {}", e))?;
-    let data: Value = serde_json::from_str(data_str)
-        .map_err(|e| format!("❌ Invalid JSON data: {}", e))?;
-    
-    let target_user_id = data["userId"].as_str().unwrap_or("");
-    let new_role = data["role"].as_str().unwrap_or("");
-    
-    // BOOTSTRAP: Allow first super_admin creation without existing profile
-    // If caller is creating their own super_admin profile and no profile exists, allow it
-    let caller_profile_result = get_admin_profile(&caller);
-    
-    if caller_profile_result.is_err() {
-        // No caller profile exists - check if this is bootstrap scenario
-        if target_user_id == caller && new_role == "super_admin" {
-            // Allow first super_admin to self-create
-            return Ok(());
-        }
-        
-        // Not bootstrap - return the original error
-        return Err(format!(
-            "❌ Admin profile not found. First user must create a super_admin profile for themselves. Your ID: {}",
-            caller
-        ));
-    }
+    append_audit_entry(key, &entry)?;
     
-    // Normal flow: validate existing admin
-    let caller_profile = caller_profile_result.unwrap();
+    Ok(())
+}
+
+/// Validates admin can manage other admins
+pub fn assert_can_manage_admins(
+    call: &SetDocCall<AdminProfileDoc>,
+) -> Result<(), String> {
+    let caller = call.caller();
+    
+    let profile = &call.proposed;
+    let target_user_id = profile.user_id.as_deref().unwrap_or("");
+    let new_role = profile.role.as_str();
+    
+    // Bootstrap writes are handled by assert_admin_bootstrap - every other write needs an existing admin
+    let caller_profile = call.admin_profile(&caller).map_err(|_| {
+        ValidationError::new(
+            "ADMIN_PROFILE_NOT_FOUND",
+            format!(
+                "❌ Admin profile not found. Ask an existing super admin to create your profile. Your ID: {}",
+                caller
+            ),
+        )
+        .param("userId", caller.as_str())
+    })?;
     
     // Enforce manager-level access
     if !has_sufficient_role(&caller_profile.role, "manager") {
-        return Err(format!(
-            "❌ Access Denied: Only managers can manage admin profiles. Your role: {}",
-            caller_profile.role
-        ));
+        return Err(insufficient_role(
+            format!("❌ Access Denied: Only managers can manage admin profiles. Your role: {}", caller_profile.role),
+            &caller_profile.role,
+            "manager",
+        )
+        .into());
     }
     
     // Prevent super_admin from demoting themselves (unless another super_admin exists)
     if target_user_id == caller && caller_profile.role == "super_admin" && new_role != "super_admin" {
-        return Err(
-            "❌ Security Policy: Super admins cannot demote themselves. Have another super admin change your role.".to_string()
-        );
+        return Err(ValidationError::new(
+            "SELF_DEMOTION_FORBIDDEN",
+            "❌ Security Policy: Super admins cannot demote themselves. Have another super admin change your role.",
+        )
+        .field("role")
+        .into());
     }
     
     // Prevent managers from creating/promoting to super_admin (only super_admins can)
     if new_role == "super_admin" && caller_profile.role != "super_admin" {
-        return Err(
-            "❌ Access Denied: Only super admins can create or promote to super_admin role".to_string()
-        );
+        return Err(insufficient_role(
+            "❌ Access Denied: Only super admins can create or promote to super_admin role".to_string(),
+            &caller_profile.role,
+            "super_admin",
+        )
+        .field("role")
+        .into());
     }
     
     Ok(())
@@ -328,40 +457,31 @@ pub fn assert_can_manage_admins(
 
 /// Validates profit distribution permissions
 pub fn assert_can_distribute_profits(
-    context: &AssertSetDocContext,
+    call: &SetDocCall<Value>,
 ) -> Result<(), String> {
-    let collection = &context.data.collection;
-    
-    if collection != "profit_distributions" {
-        return Ok(());
-    }
-    
     // Only managers and super_admins can distribute profits
-    let caller = context.caller.to_text();
+    let caller = call.caller();
     
     // SECURITY: Fetch admin profile and validate role
-    let admin_profile = get_admin_profile(&caller)?;
+    let admin_profile = call.admin_profile(&caller)?;
     
     if !has_sufficient_role(&admin_profile.role, "manager") {
-        return Err(format!(
-            "❌ Access Denied: Only managers can distribute profits. Your role: {}",
-            admin_profile.role
-        ));
+        return Err(insufficient_role(
+            format!("❌ Access Denied: Only managers can distribute profits. Your role: {}", admin_profile.role),
+            &admin_profile.role,
+            "manager",
+        )
+        .into());
     }
     
     // Log the action
-    let doc_data = &context.data.data.proposed.data;
-    let data_str = std::str::from_utf8(doc_data)
-        .map_err(|e| format!("❌ Failed to decode data as UTF-8: {}", e))?;
-    let data: Value = serde_json::from_str(data_str)
-        .map_err(|e| format!("❌ Invalid JSON data: {}", e))?;
-    
     log_admin_action(
         &caller,
         "distribute_profit",
-        collection,
-        &context.data.key,
-        Some(&data),
+        call.collection(),
+        call.key(),
+        call.current.as_ref(),
+        Some(&call.proposed),
     )?;
     
     Ok(())
@@ -370,72 +490,58 @@ pub fn assert_can_distribute_profits(
 /// Validates admin deactivation (soft delete)
 /// Allows managers to deactivate admin accounts without removing them
 pub fn assert_can_deactivate_admin(
-    context: &AssertSetDocContext,
+    call: &SetDocCall<AdminProfileDoc>,
 ) -> Result<(), String> {
-    let collection = &context.data.collection;
+    let caller = call.caller();
     
-    if collection != "admin_profiles" {
-        return Ok(());
-    }
-    
-    let caller = context.caller.to_text();
-    
-    // Parse target admin data to check for bootstrap scenario
-    let doc_data = &context.data.data.proposed.data;
-    let data_str = std::str::from_utf8(doc_data)
-        .map_err(|e| format!("❌ Failed to decode data as UTF-8: {}", e))?;
-    let data: Value = serde_json::from_str(data_str)
-        .map_err(|e| format!("❌ Invalid JSON data: {}", e))?;
-    
-    let target_user_id = data["userId"].as_str().unwrap_or("");
-    let new_role = data["role"].as_str().unwrap_or("");
-    let is_active = data["isActive"].as_bool().unwrap_or(true);
-    
-    // BOOTSTRAP: Allow first super_admin creation (skip this validator)
-    let caller_profile_result = get_admin_profile(&caller);
-    if caller_profile_result.is_err() {
-        if target_user_id == caller && new_role == "super_admin" && is_active {
-            // Allow bootstrap - first super_admin self-creation
-            return Ok(());
-        }
-        // Not bootstrap - return error
-        return Err(format!("❌ Admin profile not found for user: {}", caller));
-    }
+    // Target admin data
+    let profile = &call.proposed;
+    let target_user_id = profile.user_id.as_deref().unwrap_or("");
+    let is_active = profile.is_active;
     
     // SECURITY: Fetch caller's admin profile from datastore
-    let caller_profile = caller_profile_result.unwrap();
+    let caller_profile = call.admin_profile(&caller)?;
     
     // Enforce manager-level access for deactivation
     if !has_sufficient_role(&caller_profile.role, "manager") {
-        return Err(format!(
-            "❌ Access Denied: Only managers can deactivate admin accounts. Your role: {}",
-            caller_profile.role
-        ));
+        return Err(insufficient_role(
+            format!("❌ Access Denied: Only managers can deactivate admin accounts. Your role: {}", caller_profile.role),
+            &caller_profile.role,
+            "manager",
+        )
+        .into());
     }
     
     // Prevent self-deactivation
     if target_user_id == caller && !is_active {
-        return Err(
-            "❌ Security Policy: You cannot deactivate your own admin account".to_string()
-        );
+        return Err(ValidationError::new(
+            "SELF_DEACTIVATION_FORBIDDEN",
+            "❌ Security Policy: You cannot deactivate your own admin account",
+        )
+        .field("isActive")
+        .into());
     }
     
     // Prevent deactivating the last super_admin
-    let target_role = data["role"].as_str().unwrap_or("");
-    if target_role == "super_admin" && !is_active {
+    if profile.role == "super_admin" && !is_active {
         // TODO: Check if other active super_admins exist
         // For now, allow but log warning
     }
     
-    // Log the action
-    let action = if is_active { "reactivate_admin" } else { "deactivate_admin" };
-    log_admin_action(
-        &caller,
-        action,
-        collection,
-        target_user_id,
-        Some(&data),
-    )?;
+    // Log the action when the active flag actually changes
+    if let Some(current) = &call.current {
+        if current.is_active != is_active {
+            let action = if is_active { "reactivate_admin" } else { "deactivate_admin" };
+            log_admin_action(
+                &caller,
+                action,
+                call.collection(),
+                target_user_id,
+                call.current_json()?,
+                Some(call.proposed_json()?),
+            )?;
+        }
+    }
     
     Ok(())
 }
@@ -458,27 +564,40 @@ pub fn assert_can_delete_admin(
     
     // Only super_admins can permanently delete admin profiles
     if caller_profile.role != "super_admin" {
-        return Err(format!(
-            "❌ Access Denied: Only super admins can permanently delete admin profiles. Your role: {}. Use deactivation instead.",
-            caller_profile.role
-        ));
+        return Err(insufficient_role(
+            format!(
+                "❌ Access Denied: Only super admins can permanently delete admin profiles. Your role: {}. Use deactivation instead.",
+                caller_profile.role
+            ),
+            &caller_profile.role,
+            "super_admin",
+        )
+        .into());
     }
     
     let target_user_id = &context.data.key;
     
     // Prevent self-deletion
     if target_user_id == &caller {
-        return Err(
-            "❌ Security Policy: You cannot delete your own admin account".to_string()
-        );
+        return Err(ValidationError::new(
+            "SELF_DELETION_FORBIDDEN",
+            "❌ Security Policy: You cannot delete your own admin account",
+        )
+        .into());
     }
     
     // Log the action
+    let current_data = match &context.data.data.current {
+        Some(current) => Some(parse_doc_json(&current.data)?),
+        None => None,
+    };
+    
     log_admin_action(
         &caller,
         "delete_admin",
         collection,
         target_user_id,
+        current_data.as_ref(),
         None,
     )?;
     
@@ -488,60 +607,51 @@ pub fn assert_can_delete_admin(
 /// Validates permission updates for admin roles
 /// Ensures proper authorization hierarchy when changing approval limits or roles
 pub fn assert_can_update_permissions(
-    context: &AssertSetDocContext,
+    call: &SetDocCall<AdminProfileDoc>,
 ) -> Result<(), String> {
-    let collection = &context.data.collection;
+    let caller = call.caller();
     
-    if collection != "admin_profiles" {
-        return Ok(());
-    }
-    
-    let caller = context.caller.to_text();
-    
-    // Parse target admin data to check for bootstrap scenario
-    let doc_data = &context.data.data.proposed.data;
-    let data_str = std::str::from_utf8(doc_data)
-        .map_err(|e| format!("❌ Failed to decode data as UTF-8: {}", e))?;
-    let data: Value = serde_json::from_str(data_str)
-        .map_err(|e| format!("❌ Invalid JSON data: {}", e))?;
-    
-    let target_user_id = data["userId"].as_str().unwrap_or("");
-    let new_role = data["role"].as_str().unwrap_or("");
-    
-    // BOOTSTRAP: Allow first super_admin creation (skip this validator)
-    let caller_profile_result = get_admin_profile(&caller);
-    if caller_profile_result.is_err() {
-        if target_user_id == caller && new_role == "super_admin" {
-            // Allow bootstrap - first super_admin self-creation
-            return Ok(());
-        }
-        // Not bootstrap - return error
-        return Err(format!("❌ Admin profile not found for user: {}", caller));
-    }
+    // Target admin data
+    let profile = &call.proposed;
+    let target_user_id = profile.user_id.as_deref().unwrap_or("");
+    let new_role = profile.role.as_str();
     
     // SECURITY: Fetch caller's admin profile from datastore
-    let caller_profile = caller_profile_result.unwrap();();
+    let caller_profile = call.admin_profile(&caller)?;
     
-    let new_approval_limit = data["approvalLimit"].as_f64().unwrap_or(0.0);
+    let new_approval_limit = profile.approval_limit;
     
     // Get existing profile if it exists
-    if let Ok(existing_profile) = get_admin_profile(target_user_id) {
+    if let Ok(existing_profile) = call.admin_profile(target_user_id) {
         // Check if approval limit is being increased
         if new_approval_limit > existing_profile.approval_limit {
             // Only managers and super_admins can increase approval limits
             if !has_sufficient_role(&caller_profile.role, "manager") {
-                return Err(format!(
-                    "❌ Access Denied: Only managers can increase approval limits. Your role: {}",
-                    caller_profile.role
-                ));
+                return Err(insufficient_role(
+                    format!(
+                        "❌ Access Denied: Only managers can increase approval limits. Your role: {}",
+                        caller_profile.role
+                    ),
+                    &caller_profile.role,
+                    "manager",
+                )
+                .field("approvalLimit")
+                .into());
             }
             
             // Managers cannot set limits above their own
             if caller_profile.role == "manager" && new_approval_limit > caller_profile.approval_limit {
-                return Err(format!(
-                    "❌ Access Denied: Cannot set approval limit (₦{:.2}) higher than your own (₦{:.2})",
-                    new_approval_limit, caller_profile.approval_limit
-                ));
+                return Err(ValidationError::new(
+                    "APPROVAL_LIMIT_EXCEEDED",
+                    format!(
+                        "❌ Access Denied: Cannot set approval limit (₦{:.2}) higher than your own (₦{:.2})",
+                        new_approval_limit, caller_profile.approval_limit
+                    ),
+                )
+                .field("approvalLimit")
+                .param("limit", caller_profile.approval_limit)
+                .param("actual", new_approval_limit)
+                .into());
             }
         }
         
@@ -549,28 +659,52 @@ pub fn assert_can_update_permissions(
         if get_role_level(new_role) > get_role_level(&existing_profile.role) {
             // Only super_admins can promote to their own level or above
             if get_role_level(new_role) >= get_role_level(&caller_profile.role) && caller_profile.role != "super_admin" {
-                return Err(format!(
-                    "❌ Access Denied: Cannot promote admin to role '{}' (level {}) when your role is '{}' (level {})",
-                    new_role, get_role_level(new_role), caller_profile.role, get_role_level(&caller_profile.role)
-                ));
+                return Err(insufficient_role(
+                    format!(
+                        "❌ Access Denied: Cannot promote admin to role '{}' (level {}) when your role is '{}' (level {})",
+                        new_role, get_role_level(new_role), caller_profile.role, get_role_level(&caller_profile.role)
+                    ),
+                    &caller_profile.role,
+                    "super_admin",
+                )
+                .field("role")
+                .param("targetRole", new_role)
+                .into());
             }
         }
     }
     
-    // Log the action
-    log_admin_action(
-        &caller,
-        "update_admin_permissions",
-        collection,
-        target_user_id,
-        Some(&data),
-    )?;
+    // Log creations and changes to role, limit or permission set
+    let data = call.proposed_json()?;
+    let current_data = call.current_json()?;
+    let changes = diff_fields(current_data, Some(data));
+    let permissions_changed = ["role", "approvalLimit", "permissions"]
+        .iter()
+        .any(|field| changes.get(*field).is_some());
+    
+    let action = match current_data {
+        None => Some("create_admin"),
+        Some(_) if permissions_changed => Some("update_admin_permissions"),
+        Some(_) => None,
+    };
+    
+    if let Some(action) = action {
+        log_admin_action(
+            &caller,
+            action,
+            call.collection(),
+            target_user_id,
+            current_data,
+            Some(data),
+        )?;
+    }
     
     Ok(())
 }
 
 /// Validates bulk admin operations
 /// Prevents mass deactivation or role changes without proper authorization
+#[allow(dead_code)]
 pub fn assert_bulk_admin_operation(
     caller_role: &str,
     operation: &str,
@@ -625,6 +759,23 @@ mod tests {
         assert!(!has_sufficient_role("reviewer", "manager"));
     }
     
+    #[test]
+    fn test_bootstrap_request_must_target_caller() {
+        assert!(is_self_super_admin_request("alice", "alice", "alice", "super_admin", true));
+        assert!(!is_self_super_admin_request("alice", "bob", "alice", "super_admin", true));
+        assert!(!is_self_super_admin_request("alice", "alice", "bob", "super_admin", true));
+        assert!(!is_self_super_admin_request("alice", "alice", "alice", "manager", true));
+        assert!(!is_self_super_admin_request("alice", "alice", "alice", "super_admin", false));
+    }
+
+    #[test]
+    fn test_bootstrap_closed_after_first_admin() {
+        assert!(is_bootstrap_open(false, 0, false));
+        assert!(!is_bootstrap_open(false, 1, false));
+        assert!(!is_bootstrap_open(false, 0, true));
+        assert!(is_bootstrap_open(true, 3, true));
+    }
+    
     #[test]
     fn test_bulk_operation_limits() {
         assert!(assert_bulk_admin_operation("super_admin", "deactivate", 5).is_ok());
@@ -0,0 +1,293 @@
+use super::admin_permissions::{has_sufficient_role, insufficient_role};
+use super::dispatch::SetDocCall;
+use super::models::BusinessApplication;
+use super::validation_error::ValidationError;
+
+/// Business application status, mirroring `src/utils/application-status-machine.ts`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApplicationStatus {
+    Pending,
+    New,
+    Review,
+    Approved,
+    Rejected,
+    MoreInfo,
+}
+
+impl ApplicationStatus {
+    pub fn parse(status: &str) -> Result<Self, String> {
+        match status {
+            "pending" => Ok(Self::Pending),
+            "new" => Ok(Self::New),
+            "review" => Ok(Self::Review),
+            "approved" => Ok(Self::Approved),
+            "rejected" => Ok(Self::Rejected),
+            "more-info" => Ok(Self::MoreInfo),
+            _ => Err(ValidationError::new(
+                "INVALID_STATUS",
+                format!("❌ Invalid application status: '{}'", status),
+            )
+            .field("status")
+            .param("actual", status)
+            .into()),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::New => "new",
+            Self::Review => "review",
+            Self::Approved => "approved",
+            Self::Rejected => "rejected",
+            Self::MoreInfo => "more-info",
+        }
+    }
+
+    /// Statuses reachable from this one (approved is terminal)
+    pub fn allowed_transitions(&self) -> &'static [ApplicationStatus] {
+        use ApplicationStatus::*;
+        match self {
+            Pending | New => &[Review, Rejected, MoreInfo],
+            Review => &[Approved, Rejected, MoreInfo],
+            Approved => &[],
+            Rejected => &[Pending],
+            MoreInfo => &[Pending, Review],
+        }
+    }
+}
+
+/// Who is allowed to perform a given transition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionActor {
+    /// The business that owns the application (resubmission)
+    Owner,
+    /// An active admin with at least the given role
+    Admin(&'static str),
+}
+
+/// Returns the actor required for `from -> to`, or an error if the transition is illegal
+pub fn required_actor(
+    from: ApplicationStatus,
+    to: ApplicationStatus,
+) -> Result<TransitionActor, String> {
+    use ApplicationStatus::*;
+
+    let invalid_transition = || -> String {
+        ValidationError::new(
+            "INVALID_STATUS_TRANSITION",
+            format!(
+                "❌ Invalid status transition: '{}' → '{}' is not allowed",
+                from.as_str(),
+                to.as_str()
+            ),
+        )
+        .field("status")
+        .param("from", from.as_str())
+        .param("to", to.as_str())
+        .into()
+    };
+
+    if !from.allowed_transitions().contains(&to) {
+        return Err(invalid_transition());
+    }
+
+    match to {
+        Pending => Ok(TransitionActor::Owner),
+        Review | MoreInfo => Ok(TransitionActor::Admin("reviewer")),
+        Approved | Rejected => Ok(TransitionActor::Admin("approver")),
+        // No transition leads back to 'new'
+        New => Err(invalid_transition()),
+    }
+}
+
+/// A rejected application goes back to pending only as a flagged resubmission,
+/// and only when the rejection allowed it (as in the TS state machine)
+pub fn validate_resubmission(current: &BusinessApplication, proposed: &BusinessApplication) -> Result<(), String> {
+    if current.rejection_allows_resubmit == Some(false) {
+        return Err(ValidationError::new(
+            "RESUBMISSION_NOT_ALLOWED",
+            "❌ This application cannot be resubmitted due to permanent rejection",
+        )
+        .field("status")
+        .into());
+    }
+
+    if proposed.is_resubmission != Some(true) {
+        return Err(ValidationError::new(
+            "MISSING_FIELD",
+            "❌ Resubmission flag must be set when transitioning from rejected to pending",
+        )
+        .field("isResubmission")
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Enforces the application status state machine on business_applications writes
+/// Compares the stored status with the proposed one so a crafted client cannot
+/// skip review, un-approve an application or resubmit a permanently rejected one
+pub fn assert_application_status_transition(
+    call: &SetDocCall<BusinessApplication>,
+) -> Result<(), String> {
+    let proposed = &call.proposed;
+    let to = ApplicationStatus::parse(&proposed.status)?;
+
+    let (Some(current), Some(current_doc)) = (&call.current, &call.context.data.data.current) else {
+        // New applications always enter the workflow as pending
+        if !matches!(to, ApplicationStatus::Pending | ApplicationStatus::New) {
+            return Err(ValidationError::new(
+                "INVALID_STATUS_TRANSITION",
+                format!(
+                    "❌ Invalid status: new applications must be submitted as 'pending', not '{}'",
+                    to.as_str()
+                ),
+            )
+            .field("status")
+            .param("to", to.as_str())
+            .into());
+        }
+        return Ok(());
+    };
+    let from = ApplicationStatus::parse(&current.status)?;
+
+    // Same status is a no-op for the state machine
+    if from == to {
+        return Ok(());
+    }
+
+    let actor = required_actor(from, to)?;
+
+    // Writes issued by the satellite itself are trusted
+    if call.is_satellite() {
+        return Ok(());
+    }
+
+    match actor {
+        TransitionActor::Owner => {
+            if current_doc.owner != call.context.caller {
+                return Err(ValidationError::new(
+                    "NOT_DOCUMENT_OWNER",
+                    "❌ Access Denied: Only the business that owns this application can resubmit it",
+                )
+                .critical()
+                .into());
+            }
+            if from == ApplicationStatus::Rejected {
+                validate_resubmission(current, proposed)?;
+            }
+        }
+        TransitionActor::Admin(required_role) => {
+            let admin_profile = call.caller_admin_profile()?;
+            if !has_sufficient_role(&admin_profile.role, required_role) {
+                return Err(insufficient_role(
+                    format!(
+                        "❌ Access Denied: Role '{}' cannot move an application to '{}'. Required: '{}' or higher",
+                        admin_profile.role,
+                        to.as_str(),
+                        required_role
+                    ),
+                    &admin_profile.role,
+                    required_role,
+                )
+                .field("status")
+                .into());
+            }
+        }
+    }
+
+    let has_reason = proposed
+        .rejection_reason
+        .as_deref()
+        .is_some_and(|reason| !reason.trim().is_empty());
+    if to == ApplicationStatus::Rejected && !has_reason {
+        return Err(ValidationError::new(
+            "MISSING_FIELD",
+            "❌ Rejection reason is required when rejecting an application",
+        )
+        .field("rejectionReason")
+        .into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ApplicationStatus::*;
+
+    #[test]
+    fn test_review_cannot_be_skipped() {
+        assert!(required_actor(Pending, Approved).is_err());
+        assert!(required_actor(New, Approved).is_err());
+        assert_eq!(
+            required_actor(Review, Approved),
+            Ok(TransitionActor::Admin("approver"))
+        );
+    }
+
+    #[test]
+    fn test_approved_is_terminal() {
+        for to in [Pending, New, Review, Rejected, MoreInfo] {
+            assert!(required_actor(Approved, to).is_err());
+        }
+    }
+
+    #[test]
+    fn test_transition_roles() {
+        assert_eq!(required_actor(Pending, Review), Ok(TransitionActor::Admin("reviewer")));
+        assert_eq!(required_actor(Review, MoreInfo), Ok(TransitionActor::Admin("reviewer")));
+        assert_eq!(required_actor(Review, Rejected), Ok(TransitionActor::Admin("approver")));
+        assert_eq!(required_actor(Rejected, Pending), Ok(TransitionActor::Owner));
+        assert_eq!(required_actor(MoreInfo, Pending), Ok(TransitionActor::Owner));
+        assert!(required_actor(Rejected, Review).is_err());
+    }
+
+    fn application(value: serde_json::Value) -> BusinessApplication {
+        crate::business_financing::models::decode_doc("business_applications", &serde_json::to_vec(&value).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_resubmission_must_be_flagged_and_allowed() {
+        let rejected = application(serde_json::json!({
+            "businessName": "Kano Agro",
+            "contractType": "musharakah",
+            "status": "rejected"
+        }));
+        let permanent = application(serde_json::json!({
+            "businessName": "Kano Agro",
+            "contractType": "musharakah",
+            "status": "rejected",
+            "rejectionAllowsResubmit": false
+        }));
+        let flagged = application(serde_json::json!({
+            "businessName": "Kano Agro",
+            "contractType": "musharakah",
+            "isResubmission": true
+        }));
+        let unflagged = application(serde_json::json!({
+            "businessName": "Kano Agro",
+            "contractType": "musharakah"
+        }));
+
+        assert!(validate_resubmission(&rejected, &flagged).is_ok());
+        assert!(validate_resubmission(&rejected, &unflagged).is_err());
+        assert!(validate_resubmission(&permanent, &flagged).is_err());
+    }
+
+    #[test]
+    fn test_no_transition_back_to_new() {
+        for from in [Pending, New, Review, Approved, Rejected, MoreInfo] {
+            assert!(required_actor(from, New).is_err());
+        }
+    }
+
+    #[test]
+    fn test_parse_status() {
+        assert_eq!(ApplicationStatus::parse("more-info"), Ok(MoreInfo));
+        assert!(ApplicationStatus::parse("archived").is_err());
+    }
+}
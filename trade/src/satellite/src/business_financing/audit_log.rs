@@ -0,0 +1,626 @@
+use candid::CandidType;
+use junobuild_satellite::{
+    AssertDeleteDocContext, AssertSetDocContext, Doc, SetDoc, caller, get_controllers,
+    get_doc_store, id, set_doc_store,
+};
+use junobuild_utils::encode_doc_data;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use super::admin_permissions::get_admin_profile;
+use super::validation_error::ValidationError;
+
+/// Collection holding the immutable admin audit trail
+pub const AUDIT_LOG_COLLECTION: &str = "admin_audit_logs";
+
+/// Collection holding one chain head per day (key `YYYY-MM-DD`) plus the `latest` pointer
+pub const AUDIT_CHAIN_COLLECTION: &str = "admin_audit_chain";
+
+/// Key of the pointer to the most recent day with audit entries
+const LATEST_HEAD_KEY: &str = "latest";
+
+/// Previous hash of the very first entry ever written
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Blocks every client write to admin_audit_logs and admin_audit_chain
+/// Only the satellite itself may append entries, and existing entries can never be edited
+pub fn assert_audit_log_write(context: &AssertSetDocContext) -> Result<(), String> {
+    let collection = &context.data.collection;
+
+    if collection != AUDIT_LOG_COLLECTION && collection != AUDIT_CHAIN_COLLECTION {
+        return Ok(());
+    }
+
+    if context.caller != id() {
+        return Err(ValidationError::new(
+            "SATELLITE_ONLY",
+            "❌ Access Denied: Audit log entries are written by the satellite only",
+        )
+        .param("collection", collection.as_str())
+        .critical()
+        .into());
+    }
+
+    // Chain heads move forward with every entry - only the entries themselves are frozen
+    if collection == AUDIT_LOG_COLLECTION && context.data.data.current.is_some() {
+        return Err(ValidationError::new(
+            "AUDIT_LOG_IMMUTABLE",
+            format!("❌ Security violation: Audit log entry '{}' is immutable", context.data.key),
+        )
+        .param("collection", collection.as_str())
+        .critical()
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Blocks every deletion from admin_audit_logs and admin_audit_chain, including by controllers
+pub fn assert_audit_log_delete(context: &AssertDeleteDocContext) -> Result<(), String> {
+    let collection = &context.data.collection;
+
+    if collection != AUDIT_LOG_COLLECTION && collection != AUDIT_CHAIN_COLLECTION {
+        return Ok(());
+    }
+
+    Err(ValidationError::new(
+        "DELETE_FORBIDDEN",
+        format!("❌ Security violation: Audit log entry '{}' cannot be deleted", context.data.key),
+    )
+    .param("collection", collection.as_str())
+    .critical()
+    .into())
+}
+
+/// Appends an entry to admin_audit_logs with the satellite as author
+/// Uses `key` when given, otherwise derives a unique key from the IC time, action and target.
+///
+/// Every entry is chained to its predecessor: it records the previous entry's key and hash,
+/// its position in the day's chain, and its own SHA-256 hash. The day's chain head is moved
+/// forward in admin_audit_chain so `verify_audit_chain` can re-walk the history.
+pub fn append_audit_entry(key: Option<String>, entry: &Value) -> Result<String, String> {
+    let timestamp = entry["timestamp"].as_u64().unwrap_or_else(ic_cdk::api::time);
+
+    let base_key = match key {
+        Some(key) => key,
+        None => format!(
+            "{}_{}_{}",
+            timestamp,
+            entry["action"].as_str().unwrap_or("action"),
+            entry["targetId"].as_str().unwrap_or("unknown"),
+        ),
+    };
+
+    // Several entries can share the same IC time within one call - never overwrite one
+    let mut entry_key = base_key.clone();
+    let mut suffix = 1;
+    while get_doc_store(id(), AUDIT_LOG_COLLECTION.to_string(), entry_key.clone())?.is_some() {
+        entry_key = format!("{}_{}", base_key, suffix);
+        suffix += 1;
+    }
+
+    let day = chain_day(timestamp);
+    let head_doc = get_chain_doc(&day)?;
+    let head = match &head_doc {
+        Some(doc) => decode_chain_head(doc)?,
+        None => open_chain_day(&day)?,
+    };
+
+    let mut chained_entry = entry.clone();
+    chained_entry["chainDay"] = json!(day);
+    chained_entry["sequence"] = json!(head.length);
+    chained_entry["previousKey"] = json!(head.head_key);
+    chained_entry["previousHash"] = json!(head.head_hash);
+    let hash = compute_entry_hash(&chained_entry);
+    chained_entry["hash"] = json!(hash);
+
+    set_doc_store(
+        id(),
+        AUDIT_LOG_COLLECTION.to_string(),
+        entry_key.clone(),
+        SetDoc {
+            data: encode_doc_data(&chained_entry)?,
+            description: None,
+            version: None,
+        },
+    )?;
+
+    let new_head = AuditChainHead {
+        day: day.clone(),
+        length: head.length + 1,
+        head_key: Some(entry_key.clone()),
+        head_hash: hash,
+        genesis_hash: head.genesis_hash,
+        previous_day: head.previous_day,
+    };
+
+    set_chain_doc(&day, &new_head.to_json(), head_doc.and_then(|doc| doc.version))?;
+    update_latest_pointer(&day)?;
+
+    Ok(entry_key)
+}
+
+/// Chain head of one day, as stored in admin_audit_chain
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditChainHead {
+    pub day: String,
+    pub length: u64,
+    pub head_key: Option<String>,
+    pub head_hash: String,
+    /// Previous hash expected on the day's first entry (previous day's head or genesis)
+    pub genesis_hash: String,
+    pub previous_day: Option<String>,
+}
+
+impl AuditChainHead {
+    fn to_json(&self) -> Value {
+        json!({
+            "day": self.day,
+            "length": self.length,
+            "headKey": self.head_key,
+            "headHash": self.head_hash,
+            "genesisHash": self.genesis_hash,
+            "previousDay": self.previous_day,
+        })
+    }
+
+    fn from_json(data: &Value) -> Result<Self, String> {
+        Ok(AuditChainHead {
+            day: data["day"].as_str().ok_or("❌ Chain head missing day")?.to_string(),
+            length: data["length"].as_u64().ok_or("❌ Chain head missing length")?,
+            head_key: data["headKey"].as_str().map(|s| s.to_string()),
+            head_hash: data["headHash"].as_str().ok_or("❌ Chain head missing headHash")?.to_string(),
+            genesis_hash: data["genesisHash"]
+                .as_str()
+                .ok_or("❌ Chain head missing genesisHash")?
+                .to_string(),
+            previous_day: data["previousDay"].as_str().map(|s| s.to_string()),
+        })
+    }
+}
+
+/// Starts the chain of a new day, linked to the most recent day's head
+fn open_chain_day(day: &str) -> Result<AuditChainHead, String> {
+    let previous = match get_chain_doc(LATEST_HEAD_KEY)? {
+        Some(doc) => {
+            let latest = parse_doc_json(&doc.data)?;
+            let previous_day = latest["day"].as_str().unwrap_or_default().to_string();
+            match get_chain_doc(&previous_day)? {
+                Some(head_doc) => Some(decode_chain_head(&head_doc)?),
+                None => None,
+            }
+        }
+        None => None,
+    };
+
+    Ok(match previous {
+        Some(previous) => AuditChainHead {
+            day: day.to_string(),
+            length: 0,
+            head_key: previous.head_key,
+            head_hash: previous.head_hash.clone(),
+            genesis_hash: previous.head_hash,
+            previous_day: Some(previous.day),
+        },
+        None => AuditChainHead {
+            day: day.to_string(),
+            length: 0,
+            head_key: None,
+            head_hash: GENESIS_HASH.to_string(),
+            genesis_hash: GENESIS_HASH.to_string(),
+            previous_day: None,
+        },
+    })
+}
+
+fn update_latest_pointer(day: &str) -> Result<(), String> {
+    let latest_doc = get_chain_doc(LATEST_HEAD_KEY)?;
+
+    if let Some(doc) = &latest_doc {
+        if parse_doc_json(&doc.data)?["day"].as_str() == Some(day) {
+            return Ok(());
+        }
+    }
+
+    set_chain_doc(
+        LATEST_HEAD_KEY,
+        &json!({ "day": day }),
+        latest_doc.and_then(|doc| doc.version),
+    )
+}
+
+fn get_chain_doc(key: &str) -> Result<Option<Doc>, String> {
+    get_doc_store(id(), AUDIT_CHAIN_COLLECTION.to_string(), key.to_string())
+}
+
+fn set_chain_doc(key: &str, data: &Value, version: Option<u64>) -> Result<(), String> {
+    set_doc_store(
+        id(),
+        AUDIT_CHAIN_COLLECTION.to_string(),
+        key.to_string(),
+        SetDoc {
+            data: encode_doc_data(data)?,
+            description: None,
+            version,
+        },
+    )?;
+
+    Ok(())
+}
+
+fn decode_chain_head(doc: &Doc) -> Result<AuditChainHead, String> {
+    AuditChainHead::from_json(&parse_doc_json(&doc.data)?)
+}
+
+/// UTC calendar day (`YYYY-MM-DD`) of an IC timestamp in nanoseconds
+pub fn chain_day(timestamp_nanos: u64) -> String {
+    chrono::DateTime::from_timestamp_nanos(timestamp_nanos as i64)
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+/// SHA-256 over the canonical JSON of an entry, excluding its own `hash` field
+/// serde_json keeps object keys sorted, which makes the serialization deterministic
+pub fn compute_entry_hash(entry: &Value) -> String {
+    let mut content = entry.clone();
+    if let Some(obj) = content.as_object_mut() {
+        obj.remove("hash");
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(content.to_string().as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// First link of the audit chain that failed verification
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuditChainBrokenLink {
+    pub day: String,
+    pub sequence: u64,
+    pub key: Option<String>,
+    pub reason: String,
+}
+
+/// Result of re-walking the audit chain
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuditChainReport {
+    pub valid: bool,
+    pub days_checked: u32,
+    pub entries_checked: u64,
+    pub first_broken_link: Option<AuditChainBrokenLink>,
+}
+
+/// Only satellite controllers and active admins may verify the audit chain
+pub fn assert_can_verify_audit_chain() -> Result<(), String> {
+    let caller = caller();
+
+    if get_controllers().contains_key(&caller) {
+        return Ok(());
+    }
+
+    get_admin_profile(&caller.to_text()).map(|_| ())
+}
+
+/// Re-walks the audit chain and reports the first broken link
+/// Checks a single day when `day` is given, otherwise every day from the first to the latest
+pub fn verify_audit_chain(day: Option<String>) -> Result<AuditChainReport, String> {
+    let days = match day {
+        Some(day) => vec![day],
+        None => collect_chain_days()?,
+    };
+
+    let mut report = AuditChainReport {
+        valid: true,
+        days_checked: 0,
+        entries_checked: 0,
+        first_broken_link: None,
+    };
+
+    // Expected genesis of the next day, once the previous one has been verified
+    let mut expected_genesis: Option<String> = None;
+
+    for day in days {
+        report.days_checked += 1;
+
+        let head = match get_chain_doc(&day)? {
+            Some(doc) => decode_chain_head(&doc)?,
+            None => {
+                report.valid = false;
+                report.first_broken_link = Some(broken(&day, 0, None, "Chain head missing"));
+                return Ok(report);
+            }
+        };
+
+        if let Some(expected) = &expected_genesis {
+            if &head.genesis_hash != expected {
+                report.valid = false;
+                report.first_broken_link = Some(broken(
+                    &day,
+                    0,
+                    None,
+                    "Day does not link to the previous day's chain head",
+                ));
+                return Ok(report);
+            }
+        }
+
+        let (entries, missing) = load_day_entries(&head)?;
+        report.entries_checked += entries.len() as u64;
+
+        // A missing entry stops the backward walk - nothing older than it can be reached
+        let first_break = match missing {
+            Some(missing) => Some(missing),
+            None => verify_day_entries(&head, &entries),
+        };
+
+        if let Some(link) = first_break {
+            report.valid = false;
+            report.first_broken_link = Some(link);
+            return Ok(report);
+        }
+
+        expected_genesis = Some(head.head_hash.clone());
+    }
+
+    Ok(report)
+}
+
+/// Days with audit entries, oldest first, following the heads back from `latest`
+fn collect_chain_days() -> Result<Vec<String>, String> {
+    let mut days = Vec::new();
+
+    let mut next_day = match get_chain_doc(LATEST_HEAD_KEY)? {
+        Some(doc) => parse_doc_json(&doc.data)?["day"].as_str().map(|s| s.to_string()),
+        None => None,
+    };
+
+    while let Some(day) = next_day {
+        next_day = match get_chain_doc(&day)? {
+            Some(doc) => decode_chain_head(&doc)?.previous_day,
+            None => None,
+        };
+        days.push(day);
+    }
+
+    days.reverse();
+    Ok(days)
+}
+
+/// Audit entries of one day keyed by document key, oldest first
+type DayEntries = Vec<(String, Value)>;
+
+/// Walks a day backwards from its head, returning the entries oldest first
+/// plus the broken link when an entry referenced by the chain no longer exists
+fn load_day_entries(
+    head: &AuditChainHead,
+) -> Result<(DayEntries, Option<AuditChainBrokenLink>), String> {
+    let mut entries = Vec::new();
+    let mut key = head.head_key.clone();
+    let mut remaining = head.length;
+
+    while remaining > 0 {
+        let sequence = remaining - 1;
+        let entry_key = match &key {
+            Some(entry_key) => entry_key.clone(),
+            None => {
+                entries.reverse();
+                return Ok((entries, Some(broken(&head.day, sequence, None, "Link to entry missing"))));
+            }
+        };
+
+        let entry = match get_doc_store(id(), AUDIT_LOG_COLLECTION.to_string(), entry_key.clone())? {
+            Some(doc) => parse_doc_json(&doc.data)?,
+            None => {
+                entries.reverse();
+                return Ok((
+                    entries,
+                    Some(broken(&head.day, sequence, Some(entry_key), "Entry missing")),
+                ));
+            }
+        };
+
+        key = entry["previousKey"].as_str().map(|s| s.to_string());
+        entries.push((entry_key, entry));
+        remaining -= 1;
+    }
+
+    entries.reverse();
+    Ok((entries, None))
+}
+
+/// Verifies one day's entries (oldest first) against its chain head
+pub fn verify_day_entries(
+    head: &AuditChainHead,
+    entries: &[(String, Value)],
+) -> Option<AuditChainBrokenLink> {
+    let mut previous_hash = head.genesis_hash.clone();
+
+    for (index, (key, entry)) in entries.iter().enumerate() {
+        let sequence = index as u64;
+        let key = Some(key.clone());
+
+        if entry["chainDay"].as_str() != Some(head.day.as_str())
+            || entry["sequence"].as_u64() != Some(sequence)
+        {
+            return Some(broken(&head.day, sequence, key, "Sequence out of order"));
+        }
+
+        if entry["previousHash"].as_str() != Some(previous_hash.as_str()) {
+            return Some(broken(&head.day, sequence, key, "Previous hash does not match"));
+        }
+
+        let stored_hash = entry["hash"].as_str().unwrap_or_default();
+        if compute_entry_hash(entry) != stored_hash {
+            return Some(broken(&head.day, sequence, key, "Entry content was modified"));
+        }
+
+        previous_hash = stored_hash.to_string();
+    }
+
+    if entries.len() as u64 != head.length || previous_hash != head.head_hash {
+        return Some(broken(
+            &head.day,
+            entries.len() as u64,
+            head.head_key.clone(),
+            "Chain head does not match the last entry",
+        ));
+    }
+
+    None
+}
+
+fn broken(day: &str, sequence: u64, key: Option<String>, reason: &str) -> AuditChainBrokenLink {
+    AuditChainBrokenLink {
+        day: day.to_string(),
+        sequence,
+        key,
+        reason: reason.to_string(),
+    }
+}
+
+/// Builds an audit entry for an action performed by `admin_id` at IC time `timestamp`
+pub fn build_audit_entry(
+    admin_id: &str,
+    action: &str,
+    target_collection: &str,
+    target_id: &str,
+    timestamp: u64,
+    before: Option<&Value>,
+    after: Option<&Value>,
+) -> Value {
+    json!({
+        "adminId": admin_id,
+        "action": action,
+        "targetCollection": target_collection,
+        "targetId": target_id,
+        "timestamp": timestamp,
+        "changes": diff_fields(before, after),
+    })
+}
+
+/// Field-level diff of two documents: `{ field: { before, after } }` for every changed top-level field
+pub fn diff_fields(before: Option<&Value>, after: Option<&Value>) -> Value {
+    let empty = Map::new();
+    let before = before.and_then(|v| v.as_object()).unwrap_or(&empty);
+    let after = after.and_then(|v| v.as_object()).unwrap_or(&empty);
+
+    let mut changes = Map::new();
+
+    for (field, old_value) in before {
+        let new_value = after.get(field).unwrap_or(&Value::Null);
+        if old_value != new_value {
+            changes.insert(field.clone(), json!({ "before": old_value, "after": new_value }));
+        }
+    }
+
+    for (field, new_value) in after {
+        if !before.contains_key(field) {
+            changes.insert(field.clone(), json!({ "before": Value::Null, "after": new_value }));
+        }
+    }
+
+    Value::Object(changes)
+}
+
+/// Decodes raw document data into JSON for audit purposes
+pub fn parse_doc_json(data: &[u8]) -> Result<Value, String> {
+    serde_json::from_slice(data).map_err(|e| format!("❌ Invalid JSON data: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_reports_changed_fields_only() {
+        let before = json!({ "role": "reviewer", "approvalLimit": 5000000.0, "displayName": "Aisha" });
+        let after = json!({ "role": "approver", "approvalLimit": 5000000.0, "displayName": "Aisha" });
+
+        let diff = diff_fields(Some(&before), Some(&after));
+
+        assert_eq!(diff, json!({ "role": { "before": "reviewer", "after": "approver" } }));
+    }
+
+    #[test]
+    fn test_diff_handles_added_and_removed_fields() {
+        let before = json!({ "secondaryApprover": "abc" });
+        let after = json!({ "status": "approved" });
+
+        let diff = diff_fields(Some(&before), Some(&after));
+
+        assert_eq!(diff["secondaryApprover"]["after"], Value::Null);
+        assert_eq!(diff["status"]["before"], Value::Null);
+        assert_eq!(diff["status"]["after"], "approved");
+    }
+
+    fn chained_day(actions: &[&str]) -> (AuditChainHead, Vec<(String, Value)>) {
+        let mut previous_hash = GENESIS_HASH.to_string();
+        let mut entries = Vec::new();
+
+        for (index, action) in actions.iter().enumerate() {
+            let mut entry = json!({
+                "action": action,
+                "chainDay": "2025-06-02",
+                "sequence": index,
+                "previousHash": previous_hash,
+            });
+            let hash = compute_entry_hash(&entry);
+            entry["hash"] = json!(hash);
+            previous_hash = hash;
+            entries.push((format!("entry_{}", index), entry));
+        }
+
+        let head = AuditChainHead {
+            day: "2025-06-02".to_string(),
+            length: entries.len() as u64,
+            head_key: entries.last().map(|(key, _)| key.clone()),
+            head_hash: previous_hash,
+            genesis_hash: GENESIS_HASH.to_string(),
+            previous_day: None,
+        };
+
+        (head, entries)
+    }
+
+    #[test]
+    fn test_intact_chain_verifies() {
+        let (head, entries) = chained_day(&["create_admin", "approve_application", "delete_admin"]);
+        assert_eq!(verify_day_entries(&head, &entries), None);
+    }
+
+    #[test]
+    fn test_modified_entry_is_first_broken_link() {
+        let (head, mut entries) = chained_day(&["create_admin", "approve_application", "delete_admin"]);
+        entries[1].1["action"] = json!("reject_application");
+
+        let link = verify_day_entries(&head, &entries).unwrap();
+
+        assert_eq!(link.sequence, 1);
+        assert_eq!(link.reason, "Entry content was modified");
+    }
+
+    #[test]
+    fn test_removed_entry_breaks_the_chain() {
+        let (head, mut entries) = chained_day(&["create_admin", "approve_application", "delete_admin"]);
+        entries.remove(1);
+
+        let link = verify_day_entries(&head, &entries).unwrap();
+
+        assert_eq!(link.sequence, 1);
+    }
+
+    #[test]
+    fn test_chain_day_from_ic_time() {
+        // 2025-06-02T10:00:00Z
+        assert_eq!(chain_day(1_748_858_400_000_000_000), "2025-06-02");
+    }
+
+    #[test]
+    fn test_diff_of_deletion_lists_all_fields() {
+        let before = json!({ "role": "viewer", "isActive": true });
+
+        let diff = diff_fields(Some(&before), None);
+
+        assert_eq!(diff.as_object().unwrap().len(), 2);
+    }
+}
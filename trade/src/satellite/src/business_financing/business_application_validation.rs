@@ -1,110 +1,103 @@
-use junobuild_satellite::AssertSetDocContext;
 use serde_json::Value;
+use super::dispatch::SetDocCall;
+use super::due_diligence::assert_due_diligence_complete;
+use super::islamic_contract_validation::{CONTRACT_TYPES, canonical_contract_type};
+use super::models::BusinessApplication;
+use super::validation_error::ValidationError;
 
 /// Validates business application before approval
 /// This is the critical gatekeeper that enforces due diligence completion
 pub fn assert_business_application_approval(
-    context: &AssertSetDocContext,
+    call: &SetDocCall<BusinessApplication>,
 ) -> Result<(), String> {
-    let collection = &context.data.collection;
+    let application = &call.proposed;
     
-    // Only enforce for business_applications collection
-    if collection != "business_applications" {
+    // If status is being changed to 'approved', enforce strict rules
+    if application.status != "approved" {
         return Ok(());
     }
     
-    // Parse the proposed data from JSON string
-    let data_str = std::str::from_utf8(&context.data.data.proposed.data)
-        .map_err(|e| format!("Failed to decode business application data as UTF-8: {}", e))?;
+    // 1. CRITICAL: Every mandatory due diligence item must have passed
+    // The score is derived from the checklist - the client-supplied score is ignored
+    assert_due_diligence_complete(application.due_diligence_checklist.as_ref())?;
+    
+    // 2. Verify all required fields exist
+    let required_fields = [
+        ("businessName", !application.business_name.trim().is_empty()),
+        ("businessEmail", application.business_email.is_some()),
+        ("businessPhone", application.business_phone.is_some()),
+        ("businessAddress", application.business_address.is_some()),
+        ("requestedAmount", application.requested_amount.is_some()),
+        ("industry", application.industry.is_some()),
+        ("yearsInOperation", application.years_in_operation.is_some()),
+    ];
+    
+    for (field, present) in required_fields {
+        if !present {
+            return Err(ValidationError::new("MISSING_FIELD", format!("❌ Missing required field: {}", field))
+                .field(field)
+                .into());
+        }
+    }
+    
+    // 3. Validate requested amount is within platform bounds
+    let amount = application.requested_amount.unwrap_or_default();
+    if amount < 100_000.0 {
+        return Err(ValidationError::new("INVALID_AMOUNT", "❌ Requested amount must be at least ₦100,000")
+            .field("requestedAmount")
+            .param("min", 100_000.0)
+            .param("actual", amount)
+            .into());
+    }
+    if amount > 100_000_000.0 {
+        return Err(ValidationError::new("INVALID_AMOUNT", "❌ Requested amount cannot exceed ₦100,000,000")
+            .field("requestedAmount")
+            .param("max", 100_000_000.0)
+            .param("actual", amount)
+            .into());
+    }
+    
+    // 4. Validate contract type is one of the allowed types
+    if canonical_contract_type(&application.contract_type).is_none() {
+        return Err(ValidationError::new(
+            "INVALID_CONTRACT_TYPE",
+            format!(
+                "❌ Invalid contract type: {}. Must be one of: {:?}",
+                application.contract_type, CONTRACT_TYPES
+            ),
+        )
+        .field("contractType")
+        .param("actual", application.contract_type.as_str())
+        .into());
+    }
     
-    let data: Value = serde_json::from_str(data_str)
-        .map_err(|e| format!("Failed to parse business application data: {}", e))?;
+    // 5. Verify documents are submitted
+    if !application.documents_submitted.unwrap_or(false) {
+        return Err(ValidationError::new(
+            "DOCUMENTS_NOT_SUBMITTED",
+            "❌ Cannot approve: Required documents not submitted",
+        )
+        .field("documentsSubmitted")
+        .into());
+    }
     
-    let data = data.as_object()
-        .ok_or("Invalid business application data format")?;
+    // 6. Verify reviewer information is present
+    if application.reviewed_by.is_none() || application.reviewed_at.is_none() {
+        return Err(ValidationError::new("MISSING_FIELD", "❌ Approval must include reviewer information")
+            .field(if application.reviewed_by.is_none() { "reviewedBy" } else { "reviewedAt" })
+            .into());
+    }
     
-    // If status is being changed to 'approved', enforce strict rules
-    if let Some(status) = data.get("status") {
-        if status.as_str() == Some("approved") {
-            // 1. CRITICAL: Verify due diligence is 100% complete
-            let dd_score = data.get("dueDiligenceScore")
-                .and_then(|v| v.as_i64())
-                .unwrap_or(0);
-            
-            if dd_score < 100 {
-                return Err(format!(
-                    "❌ Cannot approve: Due diligence only {}% complete. Must be 100%",
-                    dd_score
-                ));
-            }
-            
-            // 2. Verify all required fields exist
-            let required_fields = vec![
-                "businessName",
-                "businessEmail",
-                "businessPhone",
-                "businessAddress",
-                "requestedAmount",
-                "contractType",
-                "industry",
-                "yearsInOperation",
-            ];
-            
-            for field in required_fields {
-                if !data.contains_key(field) || data.get(field).unwrap().is_null() {
-                    return Err(format!("❌ Missing required field: {}", field));
-                }
-            }
-            
-            // 3. Validate requested amount is within platform bounds
-            if let Some(amount) = data.get("requestedAmount").and_then(|v| v.as_f64()) {
-                if amount < 100_000.0 {
-                    return Err("❌ Requested amount must be at least ₦100,000".to_string());
-                }
-                if amount > 100_000_000.0 {
-                    return Err("❌ Requested amount cannot exceed ₦100,000,000".to_string());
-                }
-            } else {
-                return Err("❌ Invalid requested amount".to_string());
-            }
-            
-            // 4. Validate contract type is one of the allowed types
-            let contract_type = data.get("contractType")
-                .and_then(|v| v.as_str())
-                .ok_or("❌ Invalid contract type")?;
-            
-            let valid_contracts = vec!["musharaka", "mudaraba", "murabaha", "ijara", "istisna"];
-            if !valid_contracts.contains(&contract_type) {
-                return Err(format!(
-                    "❌ Invalid contract type: {}. Must be one of: {:?}",
-                    contract_type, valid_contracts
-                ));
-            }
-            
-            // 5. Verify documents are submitted
-            let docs_submitted = data.get("documentsSubmitted")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(false);
-            
-            if !docs_submitted {
-                return Err("❌ Cannot approve: Required documents not submitted".to_string());
-            }
-            
-            // 6. Verify reviewer information is present
-            if !data.contains_key("reviewedBy") || !data.contains_key("reviewedAt") {
-                return Err("❌ Approval must include reviewer information".to_string());
-            }
-            
-            // 7. Validate years in operation (prevent impossible values)
-            if let Some(years) = data.get("yearsInOperation").and_then(|v| v.as_i64()) {
-                if years < 0 || years > 200 {
-                    return Err(format!(
-                        "❌ Invalid years in operation: {}. Must be between 0 and 200",
-                        years
-                    ));
-                }
-            }
-        }
+    // 7. Validate years in operation (prevent impossible values)
+    let years = application.years_in_operation.unwrap_or_default();
+    if !(0..=200).contains(&years) {
+        return Err(ValidationError::new(
+            "OUT_OF_RANGE",
+            format!("❌ Invalid years in operation: {}. Must be between 0 and 200", years),
+        )
+        .field("yearsInOperation")
+        .param("actual", years)
+        .into());
     }
     
     Ok(())
@@ -116,28 +109,36 @@ pub fn validate_application_data(data: &Value) -> Result<(), String> {
     // Validate email format
     if let Some(email) = data.get("businessEmail").and_then(|v| v.as_str()) {
         if !email.contains('@') || !email.contains('.') {
-            return Err("Invalid email format".to_string());
+            return Err(ValidationError::new("INVALID_FORMAT", "❌ Invalid email format")
+                .field("businessEmail")
+                .into());
         }
     }
     
     // Validate phone number (basic check)
     if let Some(phone) = data.get("businessPhone").and_then(|v| v.as_str()) {
         if phone.len() < 10 {
-            return Err("Phone number must be at least 10 digits".to_string());
+            return Err(ValidationError::new("INVALID_FORMAT", "❌ Phone number must be at least 10 digits")
+                .field("businessPhone")
+                .into());
         }
     }
     
     // Validate BVN (11 digits)
     if let Some(bvn) = data.get("bvn").and_then(|v| v.as_str()) {
         if bvn.len() != 11 || !bvn.chars().all(|c| c.is_numeric()) {
-            return Err("BVN must be exactly 11 digits".to_string());
+            return Err(ValidationError::new("INVALID_FORMAT", "❌ BVN must be exactly 11 digits")
+                .field("bvn")
+                .into());
         }
     }
     
     // Validate registration number is not empty
     if let Some(reg_num) = data.get("registrationNumber").and_then(|v| v.as_str()) {
         if reg_num.trim().is_empty() {
-            return Err("Registration number cannot be empty".to_string());
+            return Err(ValidationError::new("MISSING_FIELD", "❌ Registration number cannot be empty")
+                .field("registrationNumber")
+                .into());
         }
     }
     
@@ -183,7 +184,7 @@ pub fn calculate_risk_score(data: &Value) -> Result<i32, String> {
     }
     
     // Clamp score between 0 and 100
-    Ok(score.max(0).min(100))
+    Ok(score.clamp(0, 100))
 }
 
 #[cfg(test)]
@@ -0,0 +1,372 @@
+// Investor concentration caps
+//
+// A single investor may hold at most a configurable share of an opportunity's funding
+// goal, and at most a configurable amount across all opportunities of one issuer
+// (`platform_settings/concentration_limits`). Managers can grant an exception for one
+// opportunity, for one investor or for all of them, in concentration_exceptions; every
+// grant and revocation is recorded in the admin audit trail.
+
+use junobuild_satellite::{AssertDeleteDocContext, get_doc_store, id};
+use serde::{Deserialize, Serialize};
+use super::admin_permissions::{get_admin_profile, has_sufficient_role, insufficient_role, log_admin_action};
+use super::audit_log::parse_doc_json;
+use super::dispatch::SetDocCall;
+use super::doc_index::list_indexed;
+use super::member_validation::RELEASED_INVESTMENT_STATUSES;
+use super::models::{Investment, Opportunity, decode_doc, decode_stored};
+use super::reporting_schedule::PLATFORM_SETTINGS_COLLECTION;
+use super::validation_error::ValidationError;
+
+/// Key of the concentration limits in platform_settings
+pub const CONCENTRATION_SETTINGS_KEY: &str = "concentration_limits";
+
+/// Collection holding exceptions, keyed by `exception_key`
+pub const CONCENTRATION_EXCEPTIONS_COLLECTION: &str = "concentration_exceptions";
+
+/// Role required to grant or revoke an exception
+const EXCEPTION_ROLE: &str = "manager";
+
+/// Platform-wide caps per investor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcentrationLimits {
+    /// Largest share (%) of an opportunity's funding goal one investor may hold
+    #[serde(default = "default_max_opportunity_share")]
+    pub max_opportunity_share: f64,
+    /// Largest amount (₦) one investor may hold across one issuer's opportunities
+    #[serde(default = "default_max_issuer_exposure")]
+    pub max_issuer_exposure: f64,
+}
+
+fn default_max_opportunity_share() -> f64 {
+    25.0
+}
+
+fn default_max_issuer_exposure() -> f64 {
+    20_000_000.0
+}
+
+impl Default for ConcentrationLimits {
+    fn default() -> Self {
+        Self {
+            max_opportunity_share: default_max_opportunity_share(),
+            max_issuer_exposure: default_max_issuer_exposure(),
+        }
+    }
+}
+
+/// Manager-granted exception for one opportunity
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcentrationException {
+    pub opportunity_id: String,
+    /// Restricts the exception to one investor (every investor when absent)
+    pub investor_id: Option<String>,
+    pub max_opportunity_share: Option<f64>,
+    pub max_issuer_exposure: Option<f64>,
+    pub reason: String,
+    pub granted_by: String,
+}
+
+impl ConcentrationLimits {
+    /// Limits after applying an exception that covers `investor_id`
+    pub fn with_exception(&self, exception: Option<&ConcentrationException>, investor_id: &str) -> Self {
+        let Some(exception) = exception.filter(|exception| {
+            exception.investor_id.as_deref().is_none_or(|investor| investor == investor_id)
+        }) else {
+            return self.clone();
+        };
+
+        Self {
+            max_opportunity_share: exception.max_opportunity_share.unwrap_or(self.max_opportunity_share),
+            max_issuer_exposure: exception.max_issuer_exposure.unwrap_or(self.max_issuer_exposure),
+        }
+    }
+}
+
+/// Range checks shared by the settings document and exceptions
+pub fn validate_concentration_limits(limits: &ConcentrationLimits) -> Result<(), String> {
+    if !(limits.max_opportunity_share > 0.0 && limits.max_opportunity_share <= 100.0) {
+        return Err(ValidationError::new(
+            "INVALID_SETTING",
+            "❌ maxOpportunityShare must be a percentage above 0 and at most 100",
+        )
+        .field("maxOpportunityShare")
+        .param("actual", limits.max_opportunity_share)
+        .into());
+    }
+
+    if !(limits.max_issuer_exposure > 0.0 && limits.max_issuer_exposure.is_finite()) {
+        return Err(ValidationError::new("INVALID_SETTING", "❌ maxIssuerExposure must be a positive amount")
+            .field("maxIssuerExposure")
+            .param("actual", limits.max_issuer_exposure)
+            .into());
+    }
+
+    Ok(())
+}
+
+/// Checks the investor's holding in the opportunity and with its issuer after `amount`
+pub fn validate_concentration(
+    amount: f64,
+    held_in_opportunity: f64,
+    held_with_issuer: f64,
+    funding_goal: f64,
+    limits: &ConcentrationLimits,
+) -> Result<(), String> {
+    let max_holding = funding_goal * limits.max_opportunity_share / 100.0;
+    if held_in_opportunity + amount > max_holding {
+        return Err(ValidationError::new(
+            "CONCENTRATION_LIMIT_EXCEEDED",
+            format!(
+                "❌ CONCENTRATION LIMIT: One investor can hold at most {}% (₦{:.2}) of this opportunity. You hold ₦{:.2}",
+                limits.max_opportunity_share, max_holding, held_in_opportunity
+            ),
+        )
+        .field("amount")
+        .param("limit", max_holding)
+        .param("maxShare", limits.max_opportunity_share)
+        .param("held", held_in_opportunity)
+        .param("actual", amount)
+        .into());
+    }
+
+    if held_with_issuer + amount > limits.max_issuer_exposure {
+        return Err(ValidationError::new(
+            "ISSUER_LIMIT_EXCEEDED",
+            format!(
+                "❌ CONCENTRATION LIMIT: One investor can hold at most ₦{:.2} across this business's opportunities. You hold ₦{:.2}",
+                limits.max_issuer_exposure, held_with_issuer
+            ),
+        )
+        .field("amount")
+        .param("limit", limits.max_issuer_exposure)
+        .param("held", held_with_issuer)
+        .param("actual", amount)
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Enforces the caps when an investment is created
+pub fn assert_concentration_limits(call: &SetDocCall<Investment>) -> Result<(), String> {
+    let investment = &call.proposed;
+    if call.current.is_some() {
+        return Ok(());
+    }
+    // assert_kyc_verified_for_investment rejects new investments without an opportunity
+    let Some(opportunity_id) = investment.opportunity_id.as_deref() else {
+        return Err(ValidationError::new("MISSING_FIELD", "❌ Investment must have an opportunityId")
+            .field("opportunityId")
+            .into());
+    };
+
+    let Some(opportunity_doc) = get_doc_store(id(), "opportunities".to_string(), opportunity_id.to_string())? else {
+        return Err(ValidationError::new(
+            "OPPORTUNITY_NOT_FOUND",
+            format!("❌ Opportunity '{}' does not exist", opportunity_id),
+        )
+        .field("opportunityId")
+        .into());
+    };
+    let opportunity: Opportunity = decode_doc("opportunities", &opportunity_doc.data)?;
+    let issuer = issuer_of(&opportunity);
+
+    let issuer_opportunities: Vec<String> = list_indexed::<Opportunity>("opportunities", "issuer", issuer)?
+        .into_iter()
+        .map(|(key, _, _)| key)
+        .collect();
+
+    let holdings: Vec<Investment> = list_indexed::<Investment>("investments", "investor", &investment.investor_id)?
+        .into_iter()
+        .filter(|(key, _, _)| key != call.key())
+        .map(|(_, _, other)| other)
+        .filter(|other| !other.status.as_deref().is_some_and(|status| RELEASED_INVESTMENT_STATUSES.contains(&status)))
+        .collect();
+
+    let held_in = |keys: &[String]| -> f64 {
+        holdings
+            .iter()
+            .filter(|holding| holding.opportunity_id.as_ref().is_some_and(|key| keys.contains(key)))
+            .map(|holding| holding.amount)
+            .sum()
+    };
+
+    let exception = load_exception(opportunity_id, &investment.investor_id)?;
+    let limits = load_concentration_limits()?.with_exception(exception.as_ref(), &investment.investor_id);
+
+    validate_concentration(
+        investment.amount,
+        held_in(&[opportunity_id.to_string()]),
+        held_in(&issuer_opportunities),
+        opportunity.funding_goal,
+        &limits,
+    )
+}
+
+/// Key of an exception: the opportunity, followed by the investor it is restricted to
+/// (`{opportunityId}_{investorId}`), so one opportunity can hold an exception per investor
+pub fn exception_key(opportunity_id: &str, investor_id: Option<&str>) -> String {
+    match investor_id {
+        Some(investor_id) => format!("{}_{}", opportunity_id, investor_id),
+        None => opportunity_id.to_string(),
+    }
+}
+
+fn require_exception_role(role: &str, action: &str) -> Result<(), String> {
+    if has_sufficient_role(role, EXCEPTION_ROLE) {
+        return Ok(());
+    }
+
+    Err(insufficient_role(
+        format!(
+            "❌ Access Denied: Only managers can {} concentration exceptions. Your role: {}",
+            action, role
+        ),
+        role,
+        EXCEPTION_ROLE,
+    )
+    .into())
+}
+
+/// Only managers grant exceptions, and each one is written to the audit trail
+pub fn assert_concentration_exception(call: &SetDocCall<ConcentrationException>) -> Result<(), String> {
+    let admin_profile = call.caller_admin_profile()?;
+    require_exception_role(&admin_profile.role, "grant")?;
+
+    let exception = &call.proposed;
+    let expected_key = exception_key(&exception.opportunity_id, exception.investor_id.as_deref());
+    if call.key() != expected_key {
+        return Err(ValidationError::new(
+            "KEY_MISMATCH",
+            format!("❌ This concentration exception must be keyed '{}'", expected_key),
+        )
+        .field("opportunityId")
+        .param("expected", expected_key.as_str())
+        .into());
+    }
+
+    if exception.granted_by != call.caller() {
+        return Err(ValidationError::new(
+            "CALLER_MISMATCH",
+            "❌ grantedBy must be the manager granting the exception",
+        )
+        .field("grantedBy")
+        .critical()
+        .into());
+    }
+
+    if exception.reason.trim().is_empty() {
+        return Err(ValidationError::new("MISSING_FIELD", "❌ A concentration exception must give its reason")
+            .field("reason")
+            .into());
+    }
+
+    let defaults = ConcentrationLimits::default();
+    validate_concentration_limits(&ConcentrationLimits {
+        max_opportunity_share: exception.max_opportunity_share.unwrap_or(defaults.max_opportunity_share),
+        max_issuer_exposure: exception.max_issuer_exposure.unwrap_or(defaults.max_issuer_exposure),
+    })?;
+
+    log_admin_action(
+        &call.caller(),
+        "grant_concentration_exception",
+        call.collection(),
+        call.key(),
+        call.current_json()?,
+        Some(call.proposed_json()?),
+    )
+}
+
+/// Current platform limits (defaults until a manager saves them)
+pub fn load_concentration_limits() -> Result<ConcentrationLimits, String> {
+    let doc = get_doc_store(id(), PLATFORM_SETTINGS_COLLECTION.to_string(), CONCENTRATION_SETTINGS_KEY.to_string())?;
+
+    Ok(decode_stored(PLATFORM_SETTINGS_COLLECTION, doc.as_ref())?.unwrap_or_default())
+}
+
+/// Only managers revoke exceptions, and each revocation is written to the audit trail
+pub fn assert_concentration_exception_delete(context: &AssertDeleteDocContext) -> Result<(), String> {
+    if context.data.collection != CONCENTRATION_EXCEPTIONS_COLLECTION {
+        return Ok(());
+    }
+
+    let caller = context.caller.to_text();
+    let admin_profile = get_admin_profile(&caller)?;
+    require_exception_role(&admin_profile.role, "revoke")?;
+
+    let before = match &context.data.data.current {
+        Some(doc) => Some(parse_doc_json(&doc.data)?),
+        None => None,
+    };
+    log_admin_action(
+        &caller,
+        "revoke_concentration_exception",
+        CONCENTRATION_EXCEPTIONS_COLLECTION,
+        &context.data.key,
+        before.as_ref(),
+        None,
+    )
+}
+
+/// The investor's own exception for the opportunity, else the opportunity-wide one
+fn load_exception(opportunity_id: &str, investor_id: &str) -> Result<Option<ConcentrationException>, String> {
+    for key in [exception_key(opportunity_id, Some(investor_id)), exception_key(opportunity_id, None)] {
+        let doc = get_doc_store(id(), CONCENTRATION_EXCEPTIONS_COLLECTION.to_string(), key)?;
+        if let Some(exception) = decode_stored(CONCENTRATION_EXCEPTIONS_COLLECTION, doc.as_ref())? {
+            return Ok(Some(exception));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Issuer an opportunity raises funds for
+pub fn issuer_of(opportunity: &Opportunity) -> &str {
+    opportunity.business_id.as_deref().unwrap_or(&opportunity.application_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn exception(investor_id: Option<&str>) -> ConcentrationException {
+        decode_doc(CONCENTRATION_EXCEPTIONS_COLLECTION, &serde_json::to_vec(&json!({
+            "opportunityId": "opp_1",
+            "investorId": investor_id,
+            "maxOpportunityShare": 60.0,
+            "reason": "Anchor investor for the pilot farm",
+            "grantedBy": "manager_1"
+        })).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_caps_per_opportunity_and_issuer() {
+        let limits = ConcentrationLimits::default();
+
+        // 25% of a ₦10M goal
+        assert!(validate_concentration(2_000_000.0, 500_000.0, 500_000.0, 10_000_000.0, &limits).is_ok());
+        assert!(validate_concentration(2_000_000.0, 600_000.0, 600_000.0, 10_000_000.0, &limits).is_err());
+        // Other opportunities of the same business count towards the issuer cap
+        assert!(validate_concentration(1_000_000.0, 0.0, 19_500_000.0, 10_000_000.0, &limits).is_err());
+    }
+
+    #[test]
+    fn test_exceptions_are_keyed_per_investor() {
+        assert_eq!(exception_key("opp_app_1", Some("anchor-principal")), "opp_app_1_anchor-principal");
+        assert_eq!(exception_key("opp_app_1", None), "opp_app_1");
+    }
+
+    #[test]
+    fn test_exception_applies_to_its_investor_only() {
+        let limits = ConcentrationLimits::default();
+        let anchor = exception(Some("anchor"));
+
+        assert_eq!(limits.with_exception(Some(&anchor), "anchor").max_opportunity_share, 60.0);
+        assert_eq!(limits.with_exception(Some(&anchor), "someone_else"), limits);
+        assert_eq!(limits.with_exception(Some(&exception(None)), "anyone").max_opportunity_share, 60.0);
+        assert_eq!(limits.with_exception(None, "anyone"), limits);
+    }
+}
@@ -0,0 +1,441 @@
+// Single entry point for assert_set_doc
+//
+// Routes each write by collection, decodes the current and proposed documents once
+// into the collection's typed model and shares one admin profile cache between all
+// the rules that apply to that collection.
+
+use junobuild_satellite::{AssertSetDocContext, id};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::cell::OnceCell;
+use super::access_control::assert_admin_only_operation;
+use super::admin_permissions::{
+    AdminProfile, AdminProfileCache, assert_admin_approval_with_limits, assert_admin_bootstrap,
+    assert_can_deactivate_admin, assert_can_distribute_profits, assert_can_manage_admins,
+    assert_can_update_permissions, validate_separation_of_duties,
+};
+use super::application_status_machine::assert_application_status_transition;
+use super::audit_log::{AUDIT_CHAIN_COLLECTION, AUDIT_LOG_COLLECTION, assert_audit_log_write, parse_doc_json};
+use super::business_application_validation::assert_business_application_approval;
+use super::concentration_limits::{
+    CONCENTRATION_EXCEPTIONS_COLLECTION, ConcentrationException, assert_concentration_exception,
+    assert_concentration_limits,
+};
+use super::financial_data_validation::validate_revenue_report;
+use super::doc_index::{
+    assert_indexed, investment_index, opportunity_index, revenue_report_index, withdrawal_request_index,
+};
+use super::due_diligence::{assert_due_diligence_checklist, assert_due_diligence_score};
+use super::investment_lifecycle::{assert_investment_status_transition, refund_cancelled_investment};
+use super::investment_opportunity_validation::assert_investment_opportunity_creation;
+use super::member_validation::{
+    assert_corporate_ubo_compliance, assert_immutable_fields, assert_investment_limits,
+    assert_kyc_verified_for_investment, assert_member_number_uniqueness, assert_reviewed_profile_fields,
+};
+use super::models::{
+    AdminProfileDoc, BusinessApplication, Investment, InvestorProfile, Opportunity,
+    OpportunityAmendment, PlatformMessage, RevenueReport, WithdrawalRequest, decode_doc, decode_stored,
+};
+use super::opportunity_amendments::{
+    OPPORTUNITY_AMENDMENTS_COLLECTION, assert_opportunity_amendment, assert_terms_frozen,
+};
+use super::opportunity_funding::{assert_funding_fields_untouched, assert_investment_opportunity_open};
+use super::platform_message_validation::validate_platform_message;
+use super::revenue_anomaly::assert_anomaly_fields_untouched;
+use super::reporting_schedule::{
+    PLATFORM_SETTINGS_COLLECTION, REPORTING_SCHEDULES_COLLECTION, assert_platform_settings_update,
+    assert_reporting_schedule_write,
+};
+use super::revenue_report_history::{REVENUE_REPORT_REVISIONS_COLLECTION, assert_revision_history_write};
+use super::revenue_report_validation::validate_revenue_report_submission;
+use super::revenue_report_workflow::assert_revenue_report_transition;
+use super::wallet_ledger::{
+    assert_funds_request_write, assert_transaction_write, assert_wallet_write, settle_investment,
+};
+
+/// One assert_set_doc call with its documents decoded into the collection model
+pub struct SetDocCall<'a, T> {
+    pub context: &'a AssertSetDocContext,
+    pub proposed: T,
+    pub current: Option<T>,
+    admins: &'a AdminProfileCache,
+    proposed_json: OnceCell<Value>,
+    current_json: OnceCell<Option<Value>>,
+}
+
+impl<'a, T: DeserializeOwned> SetDocCall<'a, T> {
+    /// Decodes the proposed and stored documents, rejecting malformed ones
+    pub fn decode(context: &'a AssertSetDocContext, admins: &'a AdminProfileCache) -> Result<Self, String> {
+        let collection = &context.data.collection;
+        let proposed = decode_doc(collection, &context.data.data.proposed.data)?;
+        let current = decode_stored(collection, context.data.data.current.as_ref())?;
+
+        Ok(Self {
+            context,
+            proposed,
+            current,
+            admins,
+            proposed_json: OnceCell::new(),
+            current_json: OnceCell::new(),
+        })
+    }
+}
+
+impl<T> SetDocCall<'_, T> {
+    pub fn collection(&self) -> &str {
+        &self.context.data.collection
+    }
+
+    pub fn key(&self) -> &str {
+        &self.context.data.key
+    }
+
+    pub fn caller(&self) -> String {
+        self.context.caller.to_text()
+    }
+
+    /// Writes issued by the satellite itself (derived fields, timers)
+    pub fn is_satellite(&self) -> bool {
+        self.context.caller == id()
+    }
+
+    /// Active admin profile of `user_id`, fetched at most once per call
+    pub fn admin_profile(&self, user_id: &str) -> Result<AdminProfile, String> {
+        self.admins.get(user_id)
+    }
+
+    /// Active admin profile of the caller
+    pub fn caller_admin_profile(&self) -> Result<AdminProfile, String> {
+        self.admin_profile(&self.caller())
+    }
+
+    /// Untyped proposed document, parsed lazily for audit diffs and loose rules
+    pub fn proposed_json(&self) -> Result<&Value, String> {
+        if let Some(value) = self.proposed_json.get() {
+            return Ok(value);
+        }
+        let value = parse_doc_json(&self.context.data.data.proposed.data)?;
+        Ok(self.proposed_json.get_or_init(|| value))
+    }
+
+    /// Untyped stored document, parsed lazily for audit diffs
+    pub fn current_json(&self) -> Result<Option<&Value>, String> {
+        if let Some(value) = self.current_json.get() {
+            return Ok(value.as_ref());
+        }
+        let value = match &self.context.data.data.current {
+            Some(doc) => Some(parse_doc_json(&doc.data)?),
+            None => None,
+        };
+        Ok(self.current_json.get_or_init(|| value).as_ref())
+    }
+}
+
+/// Runs every rule that applies to the written collection
+/// CRITICAL GATEKEEPERS - Enforce business rules before data is stored
+pub fn assert_set_doc_rules(context: &AssertSetDocContext) -> Result<(), String> {
+    let admins = AdminProfileCache::default();
+
+    match context.data.collection.as_str() {
+        // Audit Trail - Only the satellite appends to admin_audit_logs, entries are immutable
+        AUDIT_LOG_COLLECTION | AUDIT_CHAIN_COLLECTION => assert_audit_log_write(context),
+
+        "business_applications" => {
+            let call = SetDocCall::<BusinessApplication>::decode(context, &admins)?;
+
+            // Application Status - Enforce the status state machine and who may move it
+            assert_application_status_transition(&call)?;
+
+            // Due Diligence Checklist - Only reviewers record items, attributed to themselves
+            assert_due_diligence_checklist(&call)?;
+
+            // Due Diligence Score - Must match the score derived from the checklist
+            assert_due_diligence_score(&call)?;
+
+            // Business Application Approval - All mandatory due diligence items passed
+            assert_business_application_approval(&call)?;
+
+            // Admin Approval Limits - Validate amount-based authorization
+            assert_admin_approval_with_limits(&call)?;
+
+            // Separation of Duties - Reviewer cannot approve own review
+            validate_separation_of_duties(&call)?;
+
+            // Admin-Only Operations - Approval must carry the approving admin
+            assert_admin_only_operation(&call)
+        }
+
+        "opportunities" => {
+            let call = SetDocCall::<Opportunity>::decode(context, &admins)?;
+
+            // Funding totals and the funded transition written by the satellite
+            if call.is_satellite() {
+                return Ok(());
+            }
+
+            // Opportunity Funding - currentFunding/investorCount are derived, clients only cancel active ones
+            assert_funding_fields_untouched(&call)?;
+
+            // Term Freeze - Terms of funded-into opportunities change only through amendments
+            assert_terms_frozen(&call)?;
+
+            // Investment Opportunity Creation - Only from approved applications
+            assert_investment_opportunity_creation(&call)?;
+
+            // Admin-Only Operations - Only admins create opportunities
+            assert_admin_only_operation(&call)?;
+
+            // Description Index - Opportunities are looked up by application and issuer
+            assert_indexed(&call, &opportunity_index(&call.proposed))
+        }
+
+        OPPORTUNITY_AMENDMENTS_COLLECTION => {
+            let call = SetDocCall::<OpportunityAmendment>::decode(context, &admins)?;
+
+            // Opportunity Amendments - Raised by admins, decided by another manager, applied on approval
+            assert_opportunity_amendment(&call)
+        }
+
+        "profit_distributions" => {
+            let call = SetDocCall::<Value>::decode(context, &admins)?;
+
+            // Drafts generated from approved revenue reports (see profit_distribution.rs)
+            if call.is_satellite() {
+                return Ok(());
+            }
+
+            // Admin-Only Operations - Only admins distribute profits
+            assert_admin_only_operation(&call)?;
+
+            // Profit Distribution - Only managers can distribute profits
+            assert_can_distribute_profits(&call)
+        }
+
+        "revenue_reports" => {
+            let call = SetDocCall::<RevenueReport>::decode(context, &admins)?;
+
+            // Revenue Report Workflow - Owner files, reviewers decide, approved is final
+            assert_revenue_report_transition(&call)?;
+
+            // Revenue Report Financials - Net/gross profit must reconcile, amounts non-negative
+            validate_revenue_report(&call)?;
+
+            // Revenue Report Submission - Period, documents, status and contract details
+            validate_revenue_report_submission(&call)?;
+
+            // Revenue Anomalies - anomalyFlags/anomalyScore are written by the satellite only
+            assert_anomaly_fields_untouched(&call)?;
+
+            // Description Index - Reports are looked up by application
+            assert_indexed(&call, &revenue_report_index(&call.proposed))
+        }
+
+        // Reporting Schedules - Maintained by the satellite from funding and filed reports
+        REPORTING_SCHEDULES_COLLECTION => assert_reporting_schedule_write(context),
+
+        PLATFORM_SETTINGS_COLLECTION => {
+            let call = SetDocCall::<Value>::decode(context, &admins)?;
+
+            // Platform Settings - Only managers tune grace periods and other settings
+            assert_platform_settings_update(&call)
+        }
+
+        // Revision History - Satellite-written, immutable snapshots of submitted reports
+        REVENUE_REPORT_REVISIONS_COLLECTION => assert_revision_history_write(context),
+
+        "platform_messages" => {
+            let call = SetDocCall::<PlatformMessage>::decode(context, &admins)?;
+
+            // Platform Message Validation - Ensure message integrity and prevent abuse
+            validate_platform_message(&call)
+        }
+
+        "admin_profiles" => {
+            let call = SetDocCall::<AdminProfileDoc>::decode(context, &admins)?;
+
+            // Admin Bootstrap - First super_admin only while no admin exists (or by a controller)
+            if assert_admin_bootstrap(&call)? {
+                return Ok(());
+            }
+
+            // Admin Profile Management - Only managers can manage admins
+            assert_can_manage_admins(&call)?;
+
+            // Admin Deactivation/Reactivation - Managers can toggle active status
+            assert_can_deactivate_admin(&call)?;
+
+            // Admin Permission Updates - Enforce hierarchy when changing limits/roles
+            assert_can_update_permissions(&call)
+        }
+
+        // === MEMBER/INVESTOR CORE VALIDATIONS ===
+        "individual_investor_profiles" | "corporate_investor_profiles" => {
+            let call = SetDocCall::<InvestorProfile>::decode(context, &admins)?;
+
+            // Member Number Uniqueness - Prevent duplicate membership numbers
+            assert_member_number_uniqueness(&call)?;
+
+            // Profile Update Restrictions - Enforce immutable fields
+            assert_immutable_fields(&call)?;
+
+            // Reviewed Fields - KYC status, suspension, review date and limit tier are set by reviewers
+            assert_reviewed_profile_fields(&call)?;
+
+            // Corporate UBO Validation - Beneficial ownership compliance
+            assert_corporate_ubo_compliance(&call)
+        }
+
+        "investments" => {
+            let call = SetDocCall::<Investment>::decode(context, &admins)?;
+
+            // Confirmations and refunds written by the satellite's timers
+            if call.is_satellite() {
+                return Ok(());
+            }
+
+            // Investment Authorization - Only verified KYC can invest
+            assert_kyc_verified_for_investment(&call)?;
+
+            // Investment Lifecycle - pending -> confirmed, cancellable only during cooling-off
+            assert_investment_status_transition(&call)?;
+
+            // Description Index - Investments are looked up by investor and opportunity
+            assert_indexed(&call, &investment_index(&call.proposed))?;
+
+            // Investment Limits - Accredited vs non-accredited caps
+            assert_investment_limits(&call)?;
+
+            // Concentration Caps - Share of one opportunity and exposure to one issuer
+            assert_concentration_limits(&call)?;
+
+            // Open Opportunity - Active, before its deadline, within minimum and remaining goal
+            assert_investment_opportunity_open(&call)?;
+
+            // Settlement - Debit the wallet on creation, refund it on cancellation (must stay last)
+            settle_investment(&call)?;
+            refund_cancelled_investment(&call)
+        }
+
+        "wallets" => {
+            let call = SetDocCall::<Value>::decode(context, &admins)?;
+
+            // Settlements and refunds written by the satellite
+            if call.is_satellite() {
+                return Ok(());
+            }
+
+            // Wallet Ledger - Keyed by owner, balances moved by the satellite or approving admins
+            assert_wallet_write(&call)
+        }
+
+        "transactions" => {
+            let call = SetDocCall::<Value>::decode(context, &admins)?;
+
+            if call.is_satellite() {
+                return Ok(());
+            }
+
+            // Wallet Ledger - Transactions are recorded by the satellite or approving admins
+            assert_transaction_write(&call)
+        }
+
+        "deposit_requests" => {
+            let call = SetDocCall::<Value>::decode(context, &admins)?;
+
+            // Funds Requests - Members file pending requests, approving admins decide them
+            assert_funds_request_write(&call)
+        }
+
+        "withdrawal_requests" => {
+            let call = SetDocCall::<WithdrawalRequest>::decode(context, &admins)?;
+
+            // Funds Requests - Members file pending requests, approving admins decide them
+            assert_funds_request_write(&call)?;
+
+            // Description Index - Held withdrawals are looked up by member
+            assert_indexed(&call, &withdrawal_request_index(&call.proposed))
+        }
+
+        CONCENTRATION_EXCEPTIONS_COLLECTION => {
+            let call = SetDocCall::<ConcentrationException>::decode(context, &admins)?;
+
+            // Concentration Exceptions - Granted by managers, always audited
+            assert_concentration_exception(&call)
+        }
+
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+    use crate::business_financing::validation_error::ValidationError;
+    use junobuild_satellite::{Doc, DocAssertSet, DocContext, HookContext, SetDoc};
+    use serde_json::json;
+
+    fn context(collection: &str, current: Option<Value>, proposed: Value) -> AssertSetDocContext {
+        HookContext {
+            caller: Principal::anonymous(),
+            data: DocContext {
+                collection: collection.to_string(),
+                key: "doc_1".to_string(),
+                data: DocAssertSet {
+                    current: current.map(|data| Doc {
+                        owner: Principal::anonymous(),
+                        data: serde_json::to_vec(&data).unwrap(),
+                        description: None,
+                        created_at: 0,
+                        updated_at: 0,
+                        version: Some(1),
+                    }),
+                    proposed: SetDoc {
+                        data: serde_json::to_vec(&proposed).unwrap(),
+                        description: None,
+                        version: None,
+                    },
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn test_decodes_current_and_proposed_once() {
+        let admins = AdminProfileCache::default();
+        let ctx = context(
+            "investments",
+            Some(json!({ "investorId": "inv_1", "amount": 1000 })),
+            json!({ "investorId": "inv_1", "amount": 2500, "status": "pending" }),
+        );
+        let call = SetDocCall::<Investment>::decode(&ctx, &admins).unwrap();
+
+        assert_eq!(call.proposed.amount, 2500.0);
+        assert_eq!(call.current.as_ref().map(|c| c.amount), Some(1000.0));
+        assert_eq!(call.proposed_json().unwrap()["status"], "pending");
+        assert!(std::ptr::eq(call.proposed_json().unwrap(), call.proposed_json().unwrap()));
+        assert_eq!(call.current_json().unwrap().unwrap()["amount"], 1000);
+    }
+
+    #[test]
+    fn test_malformed_stored_document_is_rejected() {
+        let admins = AdminProfileCache::default();
+        let ctx = context(
+            "investments",
+            Some(json!({ "amount": 1000 })),
+            json!({ "investorId": "inv_1", "amount": 2500 }),
+        );
+        let err = SetDocCall::<Investment>::decode(&ctx, &admins).err().unwrap();
+        let err = ValidationError::parse(&err).unwrap();
+
+        assert_eq!(err.code, "MALFORMED_DOCUMENT");
+        assert!(err.message.starts_with("❌ Malformed investments document"));
+    }
+
+    #[test]
+    fn test_unguarded_collections_are_not_parsed() {
+        let ctx = context("users", None, json!("not an object"));
+
+        assert!(assert_set_doc_rules(&ctx).is_ok());
+    }
+}
@@ -0,0 +1,190 @@
+// Description indexes
+//
+// Juno can only filter list_docs by key, owner, timestamps and description, so the
+// collections the satellite looks up by reference carry those references in their
+// description as `name:value;` entries (e.g. "application:app_1;"). The asserts
+// require the description to match the document on every write, which makes an
+// index lookup as reliable as comparing the data itself, and on_post_upgrade fills
+// in the index of documents stored before it existed.
+
+use junobuild_satellite::{Doc, SetDoc, id, list_docs_store, set_doc_store};
+use junobuild_shared::types::list::{ListMatcher, ListParams};
+use serde::de::DeserializeOwned;
+use super::concentration_limits::issuer_of;
+use super::dispatch::SetDocCall;
+use super::models::{Investment, Opportunity, RevenueReport, WithdrawalRequest, decode_doc};
+use super::validation_error::ValidationError;
+
+/// Characters with a meaning in Juno's (Rust) regex matcher
+const PATTERN_METACHARACTERS: &str = "\\.+*?()|[]{}^$#&-~";
+
+/// One `name:value;` entry per reference, in the given order
+pub fn index_description(entries: &[(&str, &str)]) -> String {
+    entries.iter().map(|(name, value)| format!("{}:{};", name, value)).collect()
+}
+
+/// Escapes a reference so the matcher compares it literally
+pub fn escape_pattern(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if PATTERN_METACHARACTERS.contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Description pattern of the documents indexed under `name:value`
+pub fn index_pattern(name: &str, value: &str) -> String {
+    format!("(^|;){}:{};", name, escape_pattern(value))
+}
+
+/// Documents of `collection` indexed under `name:value`, decoded
+/// A matching document that no longer decodes is an error, not a silent omission
+pub fn list_indexed<T: DeserializeOwned>(
+    collection: &str,
+    name: &str,
+    value: &str,
+) -> Result<Vec<(String, Doc, T)>, String> {
+    list_matching(
+        collection,
+        ListMatcher {
+            description: Some(index_pattern(name, value)),
+            ..Default::default()
+        },
+    )
+}
+
+/// Documents of `collection` whose key starts with `prefix`, decoded
+/// For collections keyed by reference, e.g. `assignment_{applicationId}_{timestamp}`
+pub fn list_key_prefixed<T: DeserializeOwned>(collection: &str, prefix: &str) -> Result<Vec<(String, Doc, T)>, String> {
+    list_matching(
+        collection,
+        ListMatcher {
+            key: Some(format!("^{}", escape_pattern(prefix))),
+            ..Default::default()
+        },
+    )
+}
+
+fn list_matching<T: DeserializeOwned>(collection: &str, matcher: ListMatcher) -> Result<Vec<(String, Doc, T)>, String> {
+    let params = ListParams {
+        matcher: Some(matcher),
+        ..Default::default()
+    };
+
+    list_docs_store(id(), collection.to_string(), &params)?
+        .items
+        .into_iter()
+        .map(|(key, doc)| {
+            let data = decode_doc(collection, &doc.data)?;
+            Ok((key, doc, data))
+        })
+        .collect()
+}
+
+/// The written description must be exactly the document's index
+pub fn assert_indexed<T>(call: &SetDocCall<T>, expected: &str) -> Result<(), String> {
+    let description = call.context.data.data.proposed.description.as_deref();
+    if description == Some(expected) {
+        return Ok(());
+    }
+
+    Err(ValidationError::new(
+        "INDEX_MISMATCH",
+        format!("❌ The {} description must be '{}'", call.collection(), expected),
+    )
+    .field("description")
+    .param("expected", expected)
+    .param("actual", description.unwrap_or_default())
+    .into())
+}
+
+/// Index of a revenue report: the application it reports on
+pub fn revenue_report_index(report: &RevenueReport) -> String {
+    index_description(&[("application", &report.application_id)])
+}
+
+/// Index of an opportunity: the application it was created from and its issuer
+pub fn opportunity_index(opportunity: &Opportunity) -> String {
+    index_description(&[
+        ("application", &opportunity.application_id),
+        ("issuer", issuer_of(opportunity)),
+    ])
+}
+
+/// Index of an investment: its investor and the opportunity it funds
+pub fn investment_index(investment: &Investment) -> String {
+    index_description(&[
+        ("investor", &investment.investor_id),
+        ("opportunity", investment.opportunity_id.as_deref().unwrap_or_default()),
+    ])
+}
+
+/// Index of a withdrawal request: the member whose wallet it draws on
+pub fn withdrawal_request_index(request: &WithdrawalRequest) -> String {
+    index_description(&[("user", &request.user_id)])
+}
+
+/// Writes the index onto stored documents that predate it
+/// Documents that cannot be indexed are logged and left as they are
+fn backfill_index<T: DeserializeOwned>(collection: &str, index: fn(&T) -> String) -> Result<(), String> {
+    let docs = list_docs_store(id(), collection.to_string(), &Default::default())?;
+
+    for (key, doc) in docs.items {
+        let expected = match decode_doc::<T>(collection, &doc.data) {
+            Ok(data) => index(&data),
+            Err(error) => {
+                ic_cdk::println!("Cannot index {} '{}': {}", collection, key, error);
+                continue;
+            }
+        };
+        if doc.description.as_deref() == Some(expected.as_str()) {
+            continue;
+        }
+
+        let indexed = SetDoc {
+            data: doc.data,
+            description: Some(expected),
+            version: doc.version,
+        };
+        if let Err(error) = set_doc_store(id(), collection.to_string(), key.clone(), indexed) {
+            ic_cdk::println!("Cannot index {} '{}': {}", collection, key, error);
+        }
+    }
+
+    Ok(())
+}
+
+/// Brings every indexed collection up to date after an upgrade
+pub fn backfill_indexes() {
+    let results = [
+        backfill_index("revenue_reports", revenue_report_index),
+        backfill_index("opportunities", opportunity_index),
+        backfill_index("investments", investment_index),
+        backfill_index("withdrawal_requests", withdrawal_request_index),
+    ];
+    for error in results.into_iter().filter_map(Result::err) {
+        ic_cdk::println!("Index backfill failed: {}", error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_description_lists_every_reference() {
+        assert_eq!(
+            index_description(&[("investor", "abc-123"), ("opportunity", "opp_app_1")]),
+            "investor:abc-123;opportunity:opp_app_1;"
+        );
+    }
+
+    #[test]
+    fn test_index_pattern_escapes_references() {
+        assert_eq!(index_pattern("application", "app_1"), "(^|;)application:app_1;");
+        assert_eq!(index_pattern("investor", "a.b-c(d)"), "(^|;)investor:a\\.b\\-c\\(d\\);");
+    }
+}
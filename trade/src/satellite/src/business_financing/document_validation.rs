@@ -1,4 +1,5 @@
 use junobuild_satellite::AssertUploadAssetContext;
+use super::validation_error::ValidationError;
 
 /// Validates document uploads for security and compliance
 pub fn assert_document_upload(context: &AssertUploadAssetContext) -> Result<(), String> {
@@ -12,26 +13,44 @@ pub fn assert_document_upload(context: &AssertUploadAssetContext) -> Result<(),
     
     // 1. Validate file extension
     let allowed_extensions = vec!["pdf", "jpg", "jpeg", "png", "doc", "docx", "xls", "xlsx"];
-    let extension = filename.split('.').last().unwrap_or("").to_lowercase();
+    let extension = filename.split('.').next_back().unwrap_or("").to_lowercase();
     
     if !allowed_extensions.contains(&extension.as_str()) {
-        return Err(format!(
-            "❌ File type not allowed: .{}. Allowed types: {:?}",
-            extension, allowed_extensions
-        ));
+        return Err(ValidationError::new(
+            "FILE_TYPE_NOT_ALLOWED",
+            format!(
+                "❌ File type not allowed: .{}. Allowed types: {:?}",
+                extension, allowed_extensions
+            ),
+        )
+        .field("filename")
+        .param("actual", extension.as_str())
+        .param("allowed", allowed_extensions)
+        .into());
     }
     
     // 2. Validate filename doesn't contain dangerous characters
     let dangerous_chars = vec!["../", "..\\", "<", ">", "|", "&", ";", "$"];
     for dangerous in dangerous_chars {
         if filename.contains(dangerous) {
-            return Err(format!("❌ Filename contains invalid characters: {}", dangerous));
+            return Err(ValidationError::new(
+                "INVALID_FORMAT",
+                format!("❌ Filename contains invalid characters: {}", dangerous),
+            )
+            .field("filename")
+            .param("actual", dangerous)
+            .critical()
+            .into());
         }
     }
     
     // 3. Validate filename is not too long
     if filename.len() > 255 {
-        return Err("❌ Filename too long. Maximum 255 characters".to_string());
+        return Err(ValidationError::new("INVALID_LENGTH", "❌ Filename too long. Maximum 255 characters")
+            .field("filename")
+            .param("max", 255)
+            .param("actual", filename.len())
+            .into());
     }
     
     // Note: File size and signature validation would require accessing chunks
@@ -65,7 +84,10 @@ impl DocumentType {
             "business_plan" => Ok(DocumentType::BusinessPlan),
             "collateral_document" => Ok(DocumentType::CollateralDocument),
             "audit_report" => Ok(DocumentType::AuditReport),
-            _ => Err(format!("Invalid document type: {}", doc_type)),
+            _ => Err(ValidationError::new("INVALID_DOCUMENT_TYPE", format!("❌ Invalid document type: {}", doc_type))
+                .field("documentType")
+                .param("actual", doc_type)
+                .into()),
         }
     }
     
@@ -92,7 +114,10 @@ pub fn validate_document_completeness(uploaded_doc_types: Vec<&str>) -> Result<(
     
     for required in &required_types {
         if !uploaded_doc_types.contains(required) {
-            return Err(format!("❌ Missing required document: {}", required));
+            return Err(ValidationError::new("MISSING_DOCUMENTS", format!("❌ Missing required document: {}", required))
+                .field("documents")
+                .param("missing", *required)
+                .into());
         }
     }
     
@@ -0,0 +1,427 @@
+use std::collections::BTreeMap;
+use super::admin_permissions::{has_sufficient_role, insufficient_role};
+use super::dispatch::SetDocCall;
+use super::models::{BusinessApplication, ChecklistEntry};
+use super::validation_error::ValidationError;
+
+/// Reviewer timestamps may run ahead of IC time by at most this much (clock skew)
+const MAX_CLOCK_SKEW_NANOS: i64 = 5 * 60 * 1_000_000_000;
+
+/// A due diligence checklist item and whether approval depends on it
+pub struct ChecklistItem {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub mandatory: bool,
+}
+
+/// Checklist items reviewers record on every business application
+pub const CHECKLIST_ITEMS: &[ChecklistItem] = &[
+    ChecklistItem { key: "siteVisit", label: "Site visit", mandatory: true },
+    ChecklistItem { key: "bankVerification", label: "Bank account verification", mandatory: true },
+    ChecklistItem { key: "cacSearch", label: "CAC registry search", mandatory: true },
+    ChecklistItem { key: "references", label: "Trade/customer references", mandatory: true },
+    ChecklistItem { key: "financialStatements", label: "Financial statements review", mandatory: true },
+    ChecklistItem { key: "directorsKyc", label: "Directors' identity (BVN/NIN) verification", mandatory: true },
+    ChecklistItem { key: "taxCompliance", label: "Tax clearance certificate", mandatory: true },
+    ChecklistItem { key: "shariahScreening", label: "Shariah screening", mandatory: true },
+    ChecklistItem { key: "creditBureauCheck", label: "Credit bureau check", mandatory: false },
+    ChecklistItem { key: "collateralValuation", label: "Collateral valuation", mandatory: false },
+    ChecklistItem { key: "insuranceCoverage", label: "Insurance coverage", mandatory: false },
+];
+
+/// Outcome of a single checklist item (same vocabulary as the admin UI)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemStatus {
+    Pass,
+    Fail,
+    NotApplicable,
+    Unknown,
+}
+
+impl ItemStatus {
+    pub fn parse(status: &str) -> Result<Self, String> {
+        match status {
+            "pass" => Ok(Self::Pass),
+            "fail" => Ok(Self::Fail),
+            "na" => Ok(Self::NotApplicable),
+            "unknown" => Ok(Self::Unknown),
+            _ => Err(ValidationError::new(
+                "INVALID_STATUS",
+                format!(
+                    "❌ Invalid due diligence status '{}'. Must be one of: pass, fail, na, unknown",
+                    status
+                ),
+            )
+            .field("status")
+            .param("actual", status)
+            .into()),
+        }
+    }
+}
+
+/// Score and blocking items derived from a checklist
+#[derive(Debug, PartialEq)]
+pub struct ChecklistEvaluation {
+    /// Percentage of applicable items that passed (0-100)
+    pub score: u32,
+    /// Mandatory items that have not passed
+    pub missing_mandatory: Vec<&'static str>,
+}
+
+/// Checklist as stored on the application, keyed by item
+pub type Checklist = BTreeMap<String, ChecklistEntry>;
+
+fn item_status(checklist: Option<&Checklist>, key: &str) -> Result<ItemStatus, String> {
+    match checklist.and_then(|c| c.get(key)) {
+        Some(entry) => ItemStatus::parse(&entry.status),
+        None => Ok(ItemStatus::Unknown),
+    }
+}
+
+/// Derives the due diligence score from the checklist
+/// Missing mandatory items count as not done; missing optional items are not applicable
+pub fn evaluate_checklist(checklist: Option<&Checklist>) -> Result<ChecklistEvaluation, String> {
+    let mut applicable = 0u32;
+    let mut passed = 0u32;
+    let mut missing_mandatory = Vec::new();
+
+    for item in CHECKLIST_ITEMS {
+        let present = checklist.is_some_and(|c| c.contains_key(item.key));
+        let status = item_status(checklist, item.key)?;
+
+        if item.mandatory && status != ItemStatus::Pass {
+            missing_mandatory.push(item.key);
+        }
+        if status == ItemStatus::NotApplicable || (!item.mandatory && !present) {
+            continue;
+        }
+
+        applicable += 1;
+        if status == ItemStatus::Pass {
+            passed += 1;
+        }
+    }
+
+    let score = if applicable == 0 {
+        0
+    } else {
+        ((passed as f64 / applicable as f64) * 100.0).round() as u32
+    };
+
+    Ok(ChecklistEvaluation { score, missing_mandatory })
+}
+
+/// Validates each recorded item against the known checklist
+/// Every decided item (pass/fail/na) must name its reviewer and an RFC 3339 review time
+pub fn validate_checklist_items(checklist: &Checklist, now_nanos: i64) -> Result<(), String> {
+    for (key, entry) in checklist {
+        let Some(definition) = CHECKLIST_ITEMS.iter().find(|i| i.key == key) else {
+            return Err(ValidationError::new(
+                "INVALID_FIELD",
+                format!("❌ Unknown due diligence checklist item: '{}'", key),
+            )
+            .field("dueDiligenceChecklist")
+            .param("actual", key.as_str())
+            .into());
+        };
+
+        // Point the rejection at the item rather than a top-level status
+        let status = ItemStatus::parse(&entry.status).map_err(|error| match ValidationError::parse(&error) {
+            Some(parsed) => parsed.field(&item_field(key, "status")).into(),
+            None => error,
+        })?;
+        if status == ItemStatus::Unknown {
+            continue;
+        }
+
+        if definition.mandatory && status == ItemStatus::NotApplicable {
+            return Err(ValidationError::new(
+                "INVALID_STATUS",
+                format!(
+                    "❌ '{}' is a mandatory due diligence item and cannot be marked not applicable",
+                    definition.label
+                ),
+            )
+            .field(&item_field(key, "status"))
+            .param("actual", entry.status.as_str())
+            .into());
+        }
+
+        if entry.reviewed_by.as_deref().is_none_or(|s| s.trim().is_empty()) {
+            return Err(ValidationError::new(
+                "MISSING_FIELD",
+                format!("❌ Due diligence item '{}' is missing reviewedBy", definition.label),
+            )
+            .field(&item_field(key, "reviewedBy"))
+            .into());
+        }
+
+        let Some(reviewed_at) = entry.reviewed_at.as_deref() else {
+            return Err(ValidationError::new(
+                "MISSING_FIELD",
+                format!("❌ Due diligence item '{}' is missing reviewedAt", definition.label),
+            )
+            .field(&item_field(key, "reviewedAt"))
+            .into());
+        };
+        let Ok(reviewed_at_time) = chrono::DateTime::parse_from_rfc3339(reviewed_at) else {
+            return Err(ValidationError::new(
+                "INVALID_DATE",
+                format!(
+                    "❌ Due diligence item '{}' has an invalid reviewedAt: '{}'",
+                    definition.label, reviewed_at
+                ),
+            )
+            .field(&item_field(key, "reviewedAt"))
+            .param("actual", reviewed_at)
+            .into());
+        };
+
+        if reviewed_at_time.timestamp_nanos_opt().unwrap_or(i64::MAX) > now_nanos + MAX_CLOCK_SKEW_NANOS {
+            return Err(ValidationError::new(
+                "INVALID_DATE",
+                format!("❌ Due diligence item '{}' cannot be reviewed in the future", definition.label),
+            )
+            .field(&item_field(key, "reviewedAt"))
+            .param("actual", reviewed_at)
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Path of one field of a checklist item, e.g. `dueDiligenceChecklist.kyc.reviewedAt`
+fn item_field(key: &str, field: &str) -> String {
+    format!("dueDiligenceChecklist.{}.{}", key, field)
+}
+
+/// Returns the checklist items that differ between the stored and proposed checklist
+pub fn changed_items<'a>(
+    before: Option<&Checklist>,
+    after: &'a Checklist,
+) -> Vec<(&'a String, &'a ChecklistEntry)> {
+    after
+        .iter()
+        .filter(|(key, entry)| before.and_then(|b| b.get(key.as_str())) != Some(*entry))
+        .collect()
+}
+
+/// Guards writes to the due diligence checklist on business_applications
+/// Only reviewer+ admins may record items, and each item they touch is attributed to them
+pub fn assert_due_diligence_checklist(call: &SetDocCall<BusinessApplication>) -> Result<(), String> {
+    let proposed_checklist = call.proposed.due_diligence_checklist.as_ref();
+    let current_checklist = call.current.as_ref().and_then(|c| c.due_diligence_checklist.as_ref());
+
+    if proposed_checklist == current_checklist {
+        return Ok(());
+    }
+
+    // Writes issued by the satellite itself are trusted
+    if call.is_satellite() {
+        return Ok(());
+    }
+
+    let Some(proposed_checklist) = proposed_checklist else {
+        return Err(ValidationError::new(
+            "DUE_DILIGENCE_CHECKLIST_REMOVED",
+            "❌ The due diligence checklist cannot be removed from an application",
+        )
+        .field("dueDiligenceChecklist")
+        .into());
+    };
+
+    let caller = call.caller();
+    let admin_profile = call.admin_profile(&caller)?;
+    if !has_sufficient_role(&admin_profile.role, "reviewer") {
+        return Err(insufficient_role(
+            format!(
+                "❌ Access Denied: Role '{}' cannot record due diligence. Required: 'reviewer' or higher",
+                admin_profile.role
+            ),
+            &admin_profile.role,
+            "reviewer",
+        )
+        .field("dueDiligenceChecklist")
+        .into());
+    }
+
+    validate_checklist_items(proposed_checklist, ic_cdk::api::time() as i64)?;
+
+    for (key, entry) in changed_items(current_checklist, proposed_checklist) {
+        if entry.status == "unknown" {
+            continue;
+        }
+        if entry.reviewed_by.as_deref() != Some(caller.as_str()) {
+            return Err(ValidationError::new(
+                "CALLER_MISMATCH",
+                format!(
+                    "❌ Security violation: due diligence item '{}' must be attributed to the authenticated reviewer ('{}')",
+                    key, caller
+                ),
+            )
+            .field(&item_field(key, "reviewedBy"))
+            .param("expected", caller.as_str())
+            .critical()
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Rejects approval unless every mandatory checklist item has passed
+/// The client-supplied dueDiligenceScore is ignored
+pub fn assert_due_diligence_complete(checklist: Option<&Checklist>) -> Result<(), String> {
+    let evaluation = evaluate_checklist(checklist)?;
+
+    if !evaluation.missing_mandatory.is_empty() {
+        return Err(ValidationError::new(
+            "DUE_DILIGENCE_INCOMPLETE",
+            format!(
+                "❌ Cannot approve: mandatory due diligence items not passed: {}",
+                evaluation.missing_mandatory.join(", ")
+            ),
+        )
+        .field("dueDiligenceChecklist")
+        .param("missingItems", evaluation.missing_mandatory)
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Rejects a dueDiligenceScore that does not match the checklist it is stored with
+/// The score is derived, so approval rules never read a client-chosen value
+pub fn assert_due_diligence_score(call: &SetDocCall<BusinessApplication>) -> Result<(), String> {
+    validate_due_diligence_score(
+        call.proposed.due_diligence_checklist.as_ref(),
+        call.proposed.due_diligence_score,
+    )
+}
+
+/// A claimed score must equal the checklist's score (an absent score claims nothing)
+pub fn validate_due_diligence_score(checklist: Option<&Checklist>, claimed: Option<f64>) -> Result<(), String> {
+    let Some(claimed) = claimed else {
+        return Ok(());
+    };
+
+    let expected = evaluate_checklist(checklist)?.score;
+    if claimed != expected as f64 {
+        return Err(ValidationError::new(
+            "DERIVED_FIELD",
+            format!(
+                "❌ dueDiligenceScore must be derived from the due diligence checklist: expected {}, got {}",
+                expected, claimed
+            ),
+        )
+        .field("dueDiligenceScore")
+        .param("expected", expected)
+        .param("actual", claimed)
+        .critical()
+        .into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_760_000_000_000_000_000; // 2025-10-09T08:53:20Z
+
+    fn entry(status: &str, reviewer: Option<&str>, reviewed_at: Option<&str>) -> ChecklistEntry {
+        ChecklistEntry {
+            status: status.to_string(),
+            reviewed_by: reviewer.map(str::to_string),
+            reviewed_at: reviewed_at.map(str::to_string),
+            notes: None,
+        }
+    }
+
+    fn passed(reviewer: &str) -> ChecklistEntry {
+        entry("pass", Some(reviewer), Some("2025-10-01T10:00:00Z"))
+    }
+
+    fn single(key: &str, item: ChecklistEntry) -> Checklist {
+        Checklist::from([(key.to_string(), item)])
+    }
+
+    fn full_mandatory_checklist() -> Checklist {
+        CHECKLIST_ITEMS
+            .iter()
+            .filter(|i| i.mandatory)
+            .map(|i| (i.key.to_string(), passed("reviewer-1")))
+            .collect()
+    }
+
+    #[test]
+    fn test_score_ignores_missing_optional_items() {
+        let evaluation = evaluate_checklist(Some(&full_mandatory_checklist())).unwrap();
+        assert_eq!(evaluation.score, 100);
+        assert!(evaluation.missing_mandatory.is_empty());
+    }
+
+    #[test]
+    fn test_missing_mandatory_item_blocks_approval() {
+        let mut checklist = full_mandatory_checklist();
+        checklist.remove("siteVisit");
+        checklist.get_mut("cacSearch").unwrap().status = "fail".to_string();
+
+        let evaluation = evaluate_checklist(Some(&checklist)).unwrap();
+        assert_eq!(evaluation.missing_mandatory, vec!["siteVisit", "cacSearch"]);
+        assert_eq!(evaluation.score, 75);
+
+        // Whatever score the client claims, approval depends on the checklist alone
+        assert!(assert_due_diligence_complete(Some(&checklist)).is_err());
+        assert!(assert_due_diligence_complete(None).is_err());
+        assert!(assert_due_diligence_complete(Some(&full_mandatory_checklist())).is_ok());
+    }
+
+    #[test]
+    fn test_claimed_score_must_match_checklist() {
+        let checklist = full_mandatory_checklist();
+
+        assert!(validate_due_diligence_score(Some(&checklist), Some(100.0)).is_ok());
+        assert!(validate_due_diligence_score(Some(&checklist), None).is_ok());
+        assert!(validate_due_diligence_score(Some(&checklist), Some(90.0)).is_err());
+        assert!(validate_due_diligence_score(None, Some(100.0)).is_err());
+    }
+
+    #[test]
+    fn test_item_validation() {
+        assert!(validate_checklist_items(&full_mandatory_checklist(), NOW).is_ok());
+
+        let unknown_item = single("horoscope", passed("reviewer-1"));
+        assert!(validate_checklist_items(&unknown_item, NOW).is_err());
+
+        let no_reviewer = single("siteVisit", entry("pass", None, Some("2025-10-01T10:00:00Z")));
+        let error = ValidationError::parse(&validate_checklist_items(&no_reviewer, NOW).unwrap_err()).unwrap();
+        assert_eq!(error.code, "MISSING_FIELD");
+        assert_eq!(error.field.as_deref(), Some("dueDiligenceChecklist.siteVisit.reviewedBy"));
+
+        let bad_status = single("siteVisit", entry("maybe", Some("r"), Some("2025-10-01T10:00:00Z")));
+        let error = ValidationError::parse(&validate_checklist_items(&bad_status, NOW).unwrap_err()).unwrap();
+        assert_eq!(error.field.as_deref(), Some("dueDiligenceChecklist.siteVisit.status"));
+
+        let future = single("siteVisit", entry("pass", Some("r"), Some("2030-01-01T00:00:00Z")));
+        assert!(validate_checklist_items(&future, NOW).is_err());
+
+        let mandatory_na = single("siteVisit", entry("na", Some("r"), Some("2025-10-01T10:00:00Z")));
+        assert!(validate_checklist_items(&mandatory_na, NOW).is_err());
+
+        let optional_na = single("collateralValuation", entry("na", Some("r"), Some("2025-10-01T10:00:00Z")));
+        assert!(validate_checklist_items(&optional_na, NOW).is_ok());
+    }
+
+    #[test]
+    fn test_changed_items() {
+        let before = single("siteVisit", passed("a"));
+        let mut after = before.clone();
+        after.insert("cacSearch".to_string(), passed("b"));
+        let changed = changed_items(Some(&before), &after);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].0, "cacSearch");
+    }
+}
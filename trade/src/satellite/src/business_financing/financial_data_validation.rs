@@ -1,87 +1,66 @@
-use junobuild_satellite::OnSetDocContext;
 use serde_json::Value;
+use super::dispatch::SetDocCall;
+use super::models::RevenueReport;
+use super::validation_error::ValidationError;
 
 /// Validates financial data integrity for revenue reports
-pub async fn validate_revenue_report(context: &OnSetDocContext) -> Result<(), String> {
-    let collection = &context.data.collection;
-    
-    if collection != "revenue_reports" {
-        return Ok(());
-    }
-    
-    // Parse the after data from JSON string
-    let data_str = std::str::from_utf8(&context.data.data.after.data)
-        .map_err(|e| format!("Failed to decode revenue report data as UTF-8: {}", e))?;
-    
-    let data: Value = serde_json::from_str(data_str)
-        .map_err(|e| format!("Failed to parse revenue report data: {}", e))?;
-    
-    let data = data.as_object()
-        .ok_or("Invalid revenue report data format")?;
+/// Runs in assert_set_doc so an inconsistent report is never stored
+pub fn validate_revenue_report(call: &SetDocCall<RevenueReport>) -> Result<(), String> {
+    let report = &call.proposed;
     
     // Extract financial values
-    let total_revenue = data.get("totalRevenue")
-        .and_then(|v| v.as_f64())
-        .ok_or("Missing or invalid totalRevenue")?;
-    
-    let total_expenses = data.get("totalExpenses")
-        .and_then(|v| v.as_f64())
-        .ok_or("Missing or invalid totalExpenses")?;
-    
-    let gross_profit = data.get("grossProfit")
-        .and_then(|v| v.as_f64())
-        .ok_or("Missing or invalid grossProfit")?;
-    
-    let operating_expenses = data.get("operatingExpenses")
-        .and_then(|v| v.as_f64())
-        .ok_or("Missing or invalid operatingExpenses")?;
-    
-    let net_profit = data.get("netProfit")
-        .and_then(|v| v.as_f64())
-        .ok_or("Missing or invalid netProfit")?;
+    let total_revenue = report.total_revenue;
+    let total_expenses = report.total_expenses;
+    let gross_profit = report.gross_profit;
+    let operating_expenses = report.operating_expenses;
+    let net_profit = report.net_profit;
     
     // Validate calculations
     let calculated_net_profit = total_revenue - total_expenses;
     let tolerance = 0.01; // Allow 1 cent tolerance for floating point
     
     if (net_profit - calculated_net_profit).abs() > tolerance {
-        return Err(format!(
-            "Net profit calculation mismatch. Submitted: {}, Expected: {}",
-            net_profit, calculated_net_profit
-        ));
+        return Err(ValidationError::new(
+            "DERIVED_FIELD",
+            format!(
+                "Net profit calculation mismatch. Submitted: {}, Expected: {}",
+                net_profit, calculated_net_profit
+            ),
+        )
+        .field("netProfit")
+        .param("expected", calculated_net_profit)
+        .param("actual", net_profit)
+        .into());
     }
     
     // Validate gross profit
     if (gross_profit - (total_revenue - (total_expenses - operating_expenses))).abs() > tolerance {
-        return Err("Gross profit calculation is incorrect".to_string());
+        return Err(ValidationError::new("DERIVED_FIELD", "Gross profit calculation is incorrect")
+            .field("grossProfit")
+            .param("expected", total_revenue - (total_expenses - operating_expenses))
+            .param("actual", gross_profit)
+            .into());
     }
     
     // Validate amounts are non-negative
-    if total_revenue < 0.0 || total_expenses < 0.0 || operating_expenses < 0.0 {
-        return Err("Financial amounts cannot be negative".to_string());
-    }
-    
-    // Validate period dates
-    let period_start = data.get("periodStart")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing periodStart")?;
-    
-    let period_end = data.get("periodEnd")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing periodEnd")?;
-    
-    // Basic date validation (start < end)
-    if period_start >= period_end {
-        return Err("Period start date must be before end date".to_string());
+    for (field, amount) in [
+        ("totalRevenue", total_revenue),
+        ("totalExpenses", total_expenses),
+        ("operatingExpenses", operating_expenses),
+    ] {
+        if amount < 0.0 {
+            return Err(ValidationError::new("INVALID_AMOUNT", "Financial amounts cannot be negative")
+                .field(field)
+                .param("actual", amount)
+                .into());
+        }
     }
     
     // Validate document requirement
-    let documents = data.get("documents")
-        .and_then(|v| v.as_array())
-        .ok_or("Missing required documents")?;
-    
-    if documents.is_empty() {
-        return Err("At least one supporting document is required".to_string());
+    if report.documents.is_empty() {
+        return Err(ValidationError::new("MISSING_FIELD", "At least one supporting document is required")
+            .field("documents")
+            .into());
     }
     
     Ok(())
@@ -93,12 +72,18 @@ pub fn validate_profit_distribution(
     total_profit: f64,
     investor_share_percentage: f64,
 ) -> Result<f64, String> {
-    if investor_share_percentage < 0.0 || investor_share_percentage > 100.0 {
-        return Err("Investor share percentage must be between 0 and 100".to_string());
+    if !(0.0..=100.0).contains(&investor_share_percentage) {
+        return Err(ValidationError::new("OUT_OF_RANGE", "Investor share percentage must be between 0 and 100")
+            .field("investorSharePercentage")
+            .param("actual", investor_share_percentage)
+            .into());
     }
     
     if total_profit < 0.0 {
-        return Err("Total profit cannot be negative".to_string());
+        return Err(ValidationError::new("INVALID_AMOUNT", "Total profit cannot be negative")
+            .field("totalProfit")
+            .param("actual", total_profit)
+            .into());
     }
     
     Ok(total_profit * (investor_share_percentage / 100.0))
@@ -116,7 +101,14 @@ pub fn validate_financial_ratios(data: &Value) -> Result<(), String> {
     if current_liabilities > 0.0 {
         let current_ratio = current_assets / current_liabilities;
         if current_ratio < 0.5 {
-            return Err("Current ratio is critically low (< 0.5). Business may have liquidity issues.".to_string());
+            return Err(ValidationError::new(
+                "OUT_OF_RANGE",
+                "Current ratio is critically low (< 0.5). Business may have liquidity issues.",
+            )
+            .field("currentAssets")
+            .param("min", 0.5)
+            .param("actual", current_ratio)
+            .into());
         }
     }
     
@@ -124,7 +116,14 @@ pub fn validate_financial_ratios(data: &Value) -> Result<(), String> {
     if total_equity > 0.0 {
         let debt_to_equity = total_debt / total_equity;
         if debt_to_equity > 5.0 {
-            return Err("Debt-to-equity ratio is too high (> 5.0). Business is over-leveraged.".to_string());
+            return Err(ValidationError::new(
+                "OUT_OF_RANGE",
+                "Debt-to-equity ratio is too high (> 5.0). Business is over-leveraged.",
+            )
+            .field("totalLiabilities")
+            .param("max", 5.0)
+            .param("actual", debt_to_equity)
+            .into());
         }
     }
     
@@ -145,6 +144,8 @@ mod tests {
     #[test]
     fn test_invalid_percentage() {
         let result = validate_profit_distribution(1000000.0, 150.0);
-        assert!(result.is_err());
+        let err = ValidationError::parse(&result.unwrap_err()).unwrap();
+        assert_eq!(err.code, "OUT_OF_RANGE");
+        assert_eq!(err.field.as_deref(), Some("investorSharePercentage"));
     }
 }
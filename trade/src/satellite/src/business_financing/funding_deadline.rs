@@ -0,0 +1,384 @@
+// Funding deadline expiry
+//
+// Every opportunity carries a funding window ending on its campaignDeadline
+// (DD-MM-YYYY). A canister timer closes active opportunities whose window has ended:
+// if the funding reached the opportunity's minimumViableFunding it proceeds as partially
+// funded, otherwise it is cancelled and every committed investment is refunded to the
+// investor's wallet with a `refund` transaction. Opportunities an admin cancels are
+// refunded the same way.
+
+use chrono::NaiveDate;
+use ic_cdk_timers::{set_timer, set_timer_interval};
+use junobuild_satellite::{Doc, OnSetDocContext, SetDoc, id, list_docs_store, set_doc_store};
+use junobuild_utils::encode_doc_data;
+use serde_json::{json, Value};
+use std::time::Duration;
+use super::audit_log::parse_doc_json;
+use super::doc_index::list_indexed;
+use super::models::{Investment, Opportunity, decode_doc, decode_stored};
+use super::notifications::write_notification;
+use super::opportunity_funding::is_committed;
+use super::reporting_schedule::create_schedule;
+use super::revenue_report_validation::ic_today;
+use super::validation_error::ValidationError;
+use super::wallet_ledger::{refund_investment_to_wallet, refund_transaction_key};
+
+/// How often the timer looks for expired funding windows
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// What happens to an opportunity at its funding deadline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeadlineOutcome {
+    /// Window still open, or already fully funded/closed
+    Open,
+    /// Minimum viable funding reached - proceeds with what was raised
+    PartiallyFunded,
+    /// Under-funded - cancelled and refunded
+    Cancelled,
+}
+
+/// Parses a `DD-MM-YYYY` campaign deadline
+pub fn parse_campaign_deadline(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%d-%m-%Y").map_err(|_| {
+        ValidationError::new(
+            "INVALID_DATE",
+            format!("❌ campaignDeadline must be a DD-MM-YYYY date, got '{}'", value),
+        )
+        .field("campaignDeadline")
+        .param("actual", value)
+        .into()
+    })
+}
+
+/// Outcome for `opportunity` on `today` (the window includes the deadline day itself)
+pub fn deadline_outcome(opportunity: &Opportunity, today: NaiveDate) -> Result<DeadlineOutcome, String> {
+    if opportunity.status != "active" {
+        return Ok(DeadlineOutcome::Open);
+    }
+
+    let Some(deadline) = opportunity.campaign_deadline.as_deref() else {
+        return Ok(DeadlineOutcome::Open);
+    };
+    if parse_campaign_deadline(deadline)? >= today {
+        return Ok(DeadlineOutcome::Open);
+    }
+
+    let viable = opportunity
+        .minimum_viable_funding
+        .is_some_and(|minimum| opportunity.current_funding > 0.0 && opportunity.current_funding >= minimum);
+
+    Ok(if viable { DeadlineOutcome::PartiallyFunded } else { DeadlineOutcome::Cancelled })
+}
+
+/// Arms the deadline timer (timers do not survive upgrades, so this runs on init and post-upgrade)
+pub fn start_funding_deadline_timer() {
+    // Catch up on deadlines missed during the upgrade, outside the upgrade itself
+    set_timer(Duration::ZERO, async {
+        run_sweep();
+    });
+
+    set_timer_interval(SWEEP_INTERVAL, || async {
+        run_sweep();
+    });
+}
+
+fn run_sweep() {
+    if let Err(error) = sweep_funding_deadlines() {
+        ic_cdk::println!("Funding deadline sweep failed: {}", error);
+    }
+}
+
+/// Closes every active opportunity whose funding window has ended
+pub fn sweep_funding_deadlines() -> Result<(), String> {
+    let today = ic_today();
+    let opportunities = list_docs_store(id(), "opportunities".to_string(), &Default::default())?;
+
+    for (key, doc) in &opportunities.items {
+        // One malformed opportunity must not block the others
+        let result = decode_doc::<Opportunity>("opportunities", &doc.data).and_then(|opportunity| {
+            match deadline_outcome(&opportunity, today)? {
+                DeadlineOutcome::Open => Ok(()),
+                DeadlineOutcome::PartiallyFunded => close_partially_funded(key, doc, &opportunity),
+                DeadlineOutcome::Cancelled => cancel_and_refund(key, doc, &opportunity),
+            }
+        });
+        if let Err(error) = result {
+            ic_cdk::println!("Could not close opportunity {} at its deadline: {}", key, error);
+        }
+    }
+
+    Ok(())
+}
+
+fn close_partially_funded(key: &str, doc: &Doc, opportunity: &Opportunity) -> Result<(), String> {
+    let mut data = parse_doc_json(&doc.data)?;
+    data["status"] = Value::from("funded");
+    data["partiallyFunded"] = Value::from(true);
+    data["closedAt"] = Value::from(now_rfc3339());
+    write_opportunity(key, doc, &data)?;
+
+    // Satellite writes do not run on_set_doc, so start the reporting calendar here
+    create_schedule(key, opportunity, ic_cdk::api::time())?;
+
+    notify_investors(
+        key,
+        opportunity,
+        "Funding closed",
+        &format!(
+            "{} closed its funding window with ₦{:.2} of ₦{:.2} raised and will proceed as partially funded.",
+            business_name(opportunity),
+            opportunity.current_funding,
+            opportunity.funding_goal
+        ),
+    )
+}
+
+/// Why an opportunity was cancelled - recorded as its closureReason and told to investors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cancellation {
+    /// Under-funded at its campaign deadline
+    FundingDeadline,
+    /// Cancelled by an admin
+    Manual,
+}
+
+impl Cancellation {
+    fn closure_reason(self) -> &'static str {
+        match self {
+            Cancellation::FundingDeadline => "funding_deadline",
+            Cancellation::Manual => "cancelled",
+        }
+    }
+
+    fn explanation(self, opportunity: &Opportunity) -> String {
+        match self {
+            Cancellation::FundingDeadline => {
+                format!("{} did not reach its funding goal by its deadline.", business_name(opportunity))
+            }
+            Cancellation::Manual => format!("{} was cancelled.", business_name(opportunity)),
+        }
+    }
+}
+
+fn cancel_and_refund(key: &str, doc: &Doc, opportunity: &Opportunity) -> Result<(), String> {
+    let mut data = parse_doc_json(&doc.data)?;
+    data["status"] = Value::from("cancelled");
+    refund_cancelled(key, doc, &mut data, opportunity, Cancellation::FundingDeadline)
+}
+
+/// Refunds the committed investments of an opportunity an admin moves to cancelled
+/// (satellite writes do not run on_set_doc, so deadline cancellations are not refunded twice)
+pub fn refund_cancelled_opportunity(context: &OnSetDocContext) -> Result<(), String> {
+    if context.data.collection != "opportunities" {
+        return Ok(());
+    }
+
+    let collection = &context.data.collection;
+    let after: Opportunity = decode_doc(collection, &context.data.data.after.data)?;
+    let before: Option<Opportunity> = decode_stored(collection, context.data.data.before.as_ref())?;
+    if after.status != "cancelled" || before.is_some_and(|before| before.status == "cancelled") {
+        return Ok(());
+    }
+
+    let doc = &context.data.data.after;
+    let mut data = parse_doc_json(&doc.data)?;
+    refund_cancelled(&context.data.key, doc, &mut data, &after, Cancellation::Manual)
+}
+
+/// Refunds every committed investment and closes the cancelled opportunity's funding
+fn refund_cancelled(
+    key: &str,
+    doc: &Doc,
+    data: &mut Value,
+    opportunity: &Opportunity,
+    cancellation: Cancellation,
+) -> Result<(), String> {
+    // Decoded up front: a malformed investment leaves the opportunity open rather than unrefunded
+    let investments = list_indexed::<Investment>("investments", "opportunity", key)?;
+
+    let mut refunded = 0.0;
+    for (investment_key, investment_doc, investment) in &investments {
+        if !is_committed(investment) {
+            continue;
+        }
+
+        refund_investment(investment_key, investment_doc, investment, key, opportunity, cancellation)?;
+        refunded += investment.amount;
+    }
+
+    if data.get("closureReason").is_none_or(Value::is_null) {
+        data["closureReason"] = Value::from(cancellation.closure_reason());
+    }
+    data["closedAt"] = Value::from(now_rfc3339());
+    data["refundedAmount"] = Value::from(refunded);
+    data["currentFunding"] = Value::from(0.0);
+    data["investorCount"] = Value::from(0);
+    write_opportunity(key, doc, data)
+}
+
+/// Credits the investment back to the investor's wallet and records the refund
+fn refund_investment(
+    investment_key: &str,
+    investment_doc: &Doc,
+    investment: &Investment,
+    opportunity_key: &str,
+    opportunity: &Opportunity,
+    cancellation: Cancellation,
+) -> Result<(), String> {
+    let investor_id = &investment.investor_id;
+    let explanation = cancellation.explanation(opportunity);
+
+    // An existing refund transaction means an earlier run credited the wallet but stopped
+    // before marking the investment - finish the job rather than leaving it active
+    let transaction_key = refund_investment_to_wallet(
+        investment_key,
+        investment,
+        opportunity_key,
+        &format!("Refund - {}", explanation),
+        json!({
+            "businessName": business_name(opportunity),
+            "contractType": opportunity.contract_type,
+            "reason": cancellation.closure_reason(),
+        }),
+    )?
+    .unwrap_or_else(|| refund_transaction_key(investment_key));
+
+    let mut data = parse_doc_json(&investment_doc.data)?;
+    data["status"] = Value::from("refunded");
+    data["refundTransactionId"] = Value::from(transaction_key.as_str());
+    set_doc_store(
+        id(),
+        "investments".to_string(),
+        investment_key.to_string(),
+        SetDoc {
+            data: encode_doc_data(&data)?,
+            description: investment_doc.description.clone(),
+            version: investment_doc.version,
+        },
+    )?;
+
+    write_notification(
+        investor_id,
+        "investment_refunded",
+        "high",
+        "Investment refunded",
+        &format!(
+            "{} Your investment of ₦{:.2} has been refunded to your wallet.",
+            explanation,
+            investment.amount
+        ),
+        "/member/wallet",
+        &json!({
+            "investmentId": investment_key,
+            "opportunityId": opportunity_key,
+            "amount": investment.amount,
+            "transactionId": transaction_key,
+        }),
+    )
+}
+
+fn notify_investors(key: &str, opportunity: &Opportunity, title: &str, message: &str) -> Result<(), String> {
+    let mut investors: Vec<String> = list_indexed::<Investment>("investments", "opportunity", key)?
+        .into_iter()
+        .map(|(_, _, investment)| investment.investor_id)
+        .collect();
+    investors.sort();
+    investors.dedup();
+
+    for investor_id in investors {
+        write_notification(
+            &investor_id,
+            "investment_milestone",
+            "normal",
+            title,
+            message,
+            "/member/portfolio",
+            &json!({ "opportunityId": key, "amount": opportunity.current_funding }),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn write_opportunity(key: &str, doc: &Doc, data: &Value) -> Result<(), String> {
+    set_doc_store(
+        id(),
+        "opportunities".to_string(),
+        key.to_string(),
+        SetDoc {
+            data: encode_doc_data(data)?,
+            description: doc.description.clone(),
+            version: doc.version,
+        },
+    )?;
+    Ok(())
+}
+
+fn business_name(opportunity: &Opportunity) -> &str {
+    opportunity.business_name.as_deref().unwrap_or("The opportunity")
+}
+
+fn now_rfc3339() -> String {
+    chrono::DateTime::from_timestamp_nanos(ic_cdk::api::time() as i64).to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opportunity(status: &str, current_funding: f64, minimum_viable: Option<f64>) -> Opportunity {
+        decode_doc("opportunities", &serde_json::to_vec(&json!({
+            "applicationId": "app_1",
+            "fundingGoal": 1_000_000.0,
+            "minimumInvestment": 50_000.0,
+            "contractType": "musharaka",
+            "expectedReturnMin": 10.0,
+            "expectedReturnMax": 15.0,
+            "termMonths": 12,
+            "status": status,
+            "currentFunding": current_funding,
+            "campaignDeadline": "30-09-2026",
+            "minimumViableFunding": minimum_viable
+        })).unwrap()).unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_window_includes_deadline_day() {
+        let opp = opportunity("active", 100_000.0, None);
+
+        assert_eq!(deadline_outcome(&opp, date("2026-09-30")), Ok(DeadlineOutcome::Open));
+        assert_eq!(deadline_outcome(&opp, date("2026-10-01")), Ok(DeadlineOutcome::Cancelled));
+        assert_eq!(
+            deadline_outcome(&opportunity("funded", 1_000_000.0, None), date("2026-10-01")),
+            Ok(DeadlineOutcome::Open)
+        );
+    }
+
+    #[test]
+    fn test_minimum_viable_funding_allows_partial_funding() {
+        let after = date("2026-10-01");
+
+        assert_eq!(
+            deadline_outcome(&opportunity("active", 600_000.0, Some(500_000.0)), after),
+            Ok(DeadlineOutcome::PartiallyFunded)
+        );
+        assert_eq!(
+            deadline_outcome(&opportunity("active", 400_000.0, Some(500_000.0)), after),
+            Ok(DeadlineOutcome::Cancelled)
+        );
+        assert_eq!(
+            deadline_outcome(&opportunity("active", 0.0, Some(0.0)), after),
+            Ok(DeadlineOutcome::Cancelled)
+        );
+    }
+
+    #[test]
+    fn test_campaign_deadline_format() {
+        assert_eq!(parse_campaign_deadline("30-09-2026"), Ok(date("2026-09-30")));
+        assert!(parse_campaign_deadline("2026-09-30").is_err());
+    }
+}
@@ -0,0 +1,397 @@
+// Investment cooling-off and lifecycle
+//
+// New investments start `pending`. During the 48h cooling-off period that follows the
+// investor may cancel; the cancelled commitment is refunded to their wallet in the same
+// write and drops out of the opportunity's funding. Once the window has elapsed a canister timer
+// confirms the investment (`active`). Only managers settle active investments.
+
+use ic_cdk_timers::{set_timer, set_timer_interval};
+use junobuild_satellite::{OnSetDocContext, SetDoc, id, list_docs_store, set_doc_store};
+use junobuild_utils::encode_doc_data;
+use serde_json::{json, Value};
+use std::time::Duration;
+use super::admin_permissions::{has_sufficient_role, insufficient_role};
+use super::audit_log::parse_doc_json;
+use super::dispatch::SetDocCall;
+use super::models::{Investment, decode_doc, decode_stored};
+use super::notifications::write_notification;
+use super::validation_error::ValidationError;
+use super::wallet_ledger::{refund_investment_to_wallet, refund_transaction_key};
+
+/// Length of the cooling-off period after an investment is created
+pub const COOLING_OFF_PERIOD: Duration = Duration::from_secs(48 * 60 * 60);
+
+/// How often the timer looks for investments whose cooling-off has elapsed
+const SWEEP_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Investment status, mirroring `investmentTransactionSchema` in `src/schemas/opportunity.schema.ts`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvestmentStatus {
+    Pending,
+    Active,
+    Cancelled,
+    Refunded,
+    Completed,
+    Defaulted,
+}
+
+impl InvestmentStatus {
+    /// Parses a stored status (investments written before the lifecycle have none and are active)
+    pub fn parse(status: Option<&str>) -> Result<Self, String> {
+        match status.unwrap_or("active") {
+            "pending" => Ok(Self::Pending),
+            "active" => Ok(Self::Active),
+            "cancelled" => Ok(Self::Cancelled),
+            "refunded" => Ok(Self::Refunded),
+            "completed" => Ok(Self::Completed),
+            "defaulted" => Ok(Self::Defaulted),
+            other => Err(ValidationError::new(
+                "INVALID_STATUS",
+                format!("❌ Invalid investment status: '{}'", other),
+            )
+            .field("status")
+            .param("actual", other)
+            .into()),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Active => "active",
+            Self::Cancelled => "cancelled",
+            Self::Refunded => "refunded",
+            Self::Completed => "completed",
+            Self::Defaulted => "defaulted",
+        }
+    }
+
+    /// Whether the amount counts towards the opportunity's funding
+    /// (pending investments reserve their amount during the cooling-off period)
+    pub fn is_committed(&self) -> bool {
+        matches!(self, Self::Pending | Self::Active | Self::Completed | Self::Defaulted)
+    }
+
+    /// Statuses reachable from this one (cancelled, refunded, completed and defaulted are terminal)
+    pub fn allowed_transitions(&self) -> &'static [InvestmentStatus] {
+        use InvestmentStatus::*;
+        match self {
+            Pending => &[Active, Cancelled, Refunded],
+            Active => &[Completed, Defaulted, Refunded],
+            Cancelled | Refunded | Completed | Defaulted => &[],
+        }
+    }
+}
+
+/// Who is allowed to perform a given transition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvestmentActor {
+    /// The investor, within the cooling-off period
+    Investor,
+    /// The satellite's timers (confirmation, deadline refunds)
+    Satellite,
+    /// An active admin with at least the given role
+    Admin(&'static str),
+}
+
+/// Returns the actor required for `from -> to`, or an error if the transition is illegal
+pub fn required_actor(from: InvestmentStatus, to: InvestmentStatus) -> Result<InvestmentActor, String> {
+    use InvestmentStatus::*;
+
+    if !from.allowed_transitions().contains(&to) {
+        return Err(ValidationError::new(
+            "INVALID_STATUS_TRANSITION",
+            format!(
+                "❌ Invalid status transition: '{}' → '{}' is not allowed",
+                from.as_str(),
+                to.as_str()
+            ),
+        )
+        .field("status")
+        .param("from", from.as_str())
+        .param("to", to.as_str())
+        .into());
+    }
+
+    Ok(match to {
+        Cancelled => InvestmentActor::Investor,
+        Active | Refunded => InvestmentActor::Satellite,
+        Completed | Defaulted => InvestmentActor::Admin("manager"),
+        Pending => unreachable!("no transition leads back to 'pending'"),
+    })
+}
+
+/// Whether `now` (nanoseconds) still falls inside the cooling-off period of an
+/// investment created at `created_at`
+pub fn within_cooling_off(created_at: u64, now: u64) -> bool {
+    now < created_at.saturating_add(COOLING_OFF_PERIOD.as_nanos() as u64)
+}
+
+/// Enforces the investment lifecycle on client writes to investments
+pub fn assert_investment_status_transition(call: &SetDocCall<Investment>) -> Result<(), String> {
+    let to = InvestmentStatus::parse(call.proposed.status.as_deref())?;
+
+    let (Some(current), Some(current_doc)) = (&call.current, &call.context.data.data.current) else {
+        // New investments always enter their cooling-off period
+        if to != InvestmentStatus::Pending {
+            return Err(ValidationError::new(
+                "INVALID_STATUS_TRANSITION",
+                format!(
+                    "❌ Invalid status: new investments must be created as 'pending', not '{}'",
+                    to.as_str()
+                ),
+            )
+            .field("status")
+            .param("to", to.as_str())
+            .into());
+        }
+        return Ok(());
+    };
+    let from = InvestmentStatus::parse(current.status.as_deref())?;
+
+    // Same status is a no-op for the lifecycle
+    if from == to {
+        return Ok(());
+    }
+
+    match required_actor(from, to)? {
+        InvestmentActor::Investor => {
+            if current.investor_id != call.caller() {
+                return Err(ValidationError::new(
+                    "CALLER_MISMATCH",
+                    "❌ Access Denied: Only the investor can cancel their investment",
+                )
+                .field("status")
+                .critical()
+                .into());
+            }
+            if !within_cooling_off(current_doc.created_at, ic_cdk::api::time()) {
+                return Err(ValidationError::new(
+                    "COOLING_OFF_ELAPSED",
+                    "❌ The 48-hour cooling-off period has ended - this investment can no longer be cancelled",
+                )
+                .field("status")
+                .param("createdAt", current_doc.created_at)
+                .into());
+            }
+        }
+        InvestmentActor::Satellite => {
+            return Err(ValidationError::new(
+                "INVALID_STATUS_TRANSITION",
+                format!(
+                    "❌ Investments are moved to '{}' by the satellite, not by clients",
+                    to.as_str()
+                ),
+            )
+            .field("status")
+            .param("from", from.as_str())
+            .param("to", to.as_str())
+            .critical()
+            .into());
+        }
+        InvestmentActor::Admin(required_role) => {
+            let admin_profile = call.caller_admin_profile()?;
+            if !has_sufficient_role(&admin_profile.role, required_role) {
+                return Err(insufficient_role(
+                    format!(
+                        "❌ Access Denied: Role '{}' cannot move an investment to '{}'. Required: '{}' or higher",
+                        admin_profile.role,
+                        to.as_str(),
+                        required_role
+                    ),
+                    &admin_profile.role,
+                    required_role,
+                )
+                .field("status")
+                .into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the write moves a pending investment to cancelled
+fn is_cancellation(before: Option<&Investment>, after: &Investment) -> bool {
+    before.is_some_and(|before| before.status.as_deref() == Some("pending"))
+        && after.status.as_deref() == Some("cancelled")
+}
+
+/// Refunds an investment the investor cancels during its cooling-off period
+/// Runs in the assert path next to settle_investment, so the wallet credit commits
+/// together with the cancellation or not at all (must stay last)
+pub fn refund_cancelled_investment(call: &SetDocCall<Investment>) -> Result<(), String> {
+    if !is_cancellation(call.current.as_ref(), &call.proposed) {
+        return Ok(());
+    }
+
+    refund_investment_to_wallet(
+        call.key(),
+        &call.proposed,
+        call.proposed.opportunity_id.as_deref().unwrap_or_default(),
+        "Refund - investment cancelled during the cooling-off period",
+        json!({ "reason": "cooling_off_cancellation" }),
+    )?;
+
+    Ok(())
+}
+
+/// Links a cancelled investment to its refund and tells the investor
+pub fn record_investment_cancellation(context: &OnSetDocContext) -> Result<(), String> {
+    if context.data.collection != "investments" {
+        return Ok(());
+    }
+
+    let collection = &context.data.collection;
+    let key = &context.data.key;
+    let after: Investment = decode_doc(collection, &context.data.data.after.data)?;
+    let before: Option<Investment> = decode_stored(collection, context.data.data.before.as_ref())?;
+    if !is_cancellation(before.as_ref(), &after) {
+        return Ok(());
+    }
+    let transaction_key = refund_transaction_key(key);
+
+    let mut data = parse_doc_json(&context.data.data.after.data)?;
+    data["refundTransactionId"] = Value::from(transaction_key.as_str());
+    data["cancelledAt"] = Value::from(now_rfc3339());
+    set_doc_store(
+        id(),
+        collection.clone(),
+        key.clone(),
+        SetDoc {
+            data: encode_doc_data(&data)?,
+            description: context.data.data.after.description.clone(),
+            version: context.data.data.after.version,
+        },
+    )?;
+
+    write_notification(
+        &after.investor_id,
+        "investment_refunded",
+        "high",
+        "Investment cancelled",
+        &format!(
+            "Your investment of ₦{:.2} was cancelled during its cooling-off period and has been refunded to your wallet.",
+            after.amount
+        ),
+        "/member/wallet",
+        &json!({
+            "investmentId": key,
+            "opportunityId": after.opportunity_id,
+            "amount": after.amount,
+            "transactionId": transaction_key,
+        }),
+    )
+}
+
+/// Arms the confirmation timer (timers do not survive upgrades, so this runs on init and post-upgrade)
+pub fn start_cooling_off_timer() {
+    set_timer(Duration::ZERO, async {
+        run_sweep();
+    });
+
+    set_timer_interval(SWEEP_INTERVAL, || async {
+        run_sweep();
+    });
+}
+
+fn run_sweep() {
+    if let Err(error) = confirm_elapsed_investments() {
+        ic_cdk::println!("Cooling-off sweep failed: {}", error);
+    }
+}
+
+/// Confirms every pending investment whose cooling-off period has elapsed
+pub fn confirm_elapsed_investments() -> Result<(), String> {
+    let now = ic_cdk::api::time();
+    let investments = list_docs_store(id(), "investments".to_string(), &Default::default())?;
+
+    for (key, doc) in &investments.items {
+        let investment = match decode_doc::<Investment>("investments", &doc.data) {
+            Ok(investment) => investment,
+            Err(error) => {
+                ic_cdk::println!("Could not confirm investment {}: {}", key, error);
+                continue;
+            }
+        };
+        if investment.status.as_deref() != Some("pending") || within_cooling_off(doc.created_at, now) {
+            continue;
+        }
+
+        // One malformed investment must not block the others
+        let result = parse_doc_json(&doc.data).and_then(|mut data| {
+            data["status"] = Value::from(InvestmentStatus::Active.as_str());
+            data["confirmedAt"] = Value::from(now_rfc3339());
+            set_doc_store(
+                id(),
+                "investments".to_string(),
+                key.clone(),
+                SetDoc {
+                    data: encode_doc_data(&data)?,
+                    description: doc.description.clone(),
+                    version: doc.version,
+                },
+            )?;
+
+            write_notification(
+                &investment.investor_id,
+                "investment_confirmed",
+                "normal",
+                "Investment confirmed",
+                &format!(
+                    "The cooling-off period of your ₦{:.2} investment has ended and it is now confirmed.",
+                    investment.amount
+                ),
+                "/member/portfolio",
+                &json!({
+                    "investmentId": key,
+                    "opportunityId": investment.opportunity_id,
+                    "amount": investment.amount,
+                }),
+            )
+        });
+        if let Err(error) = result {
+            ic_cdk::println!("Could not confirm investment {}: {}", key, error);
+        }
+    }
+
+    Ok(())
+}
+
+fn now_rfc3339() -> String {
+    chrono::DateTime::from_timestamp_nanos(ic_cdk::api::time() as i64).to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use InvestmentStatus::*;
+
+    const HOUR: u64 = 60 * 60 * 1_000_000_000;
+
+    #[test]
+    fn test_cancellation_only_within_cooling_off() {
+        let created_at = 1_000 * HOUR;
+
+        assert!(within_cooling_off(created_at, created_at + HOUR));
+        assert!(within_cooling_off(created_at, created_at + 48 * HOUR - 1));
+        assert!(!within_cooling_off(created_at, created_at + 48 * HOUR));
+    }
+
+    #[test]
+    fn test_transition_actors() {
+        assert_eq!(required_actor(Pending, Cancelled), Ok(InvestmentActor::Investor));
+        assert_eq!(required_actor(Pending, Active), Ok(InvestmentActor::Satellite));
+        assert_eq!(required_actor(Active, Completed), Ok(InvestmentActor::Admin("manager")));
+        // Confirmed investments are past their cooling-off period
+        assert!(required_actor(Active, Cancelled).is_err());
+        assert!(required_actor(Cancelled, Active).is_err());
+    }
+
+    #[test]
+    fn test_legacy_investments_without_status_are_active() {
+        assert_eq!(InvestmentStatus::parse(None), Ok(Active));
+        assert!(InvestmentStatus::parse(None).unwrap().is_committed());
+        assert!(InvestmentStatus::parse(Some("confirmed")).is_err());
+    }
+}
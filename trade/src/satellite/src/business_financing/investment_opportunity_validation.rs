@@ -1,131 +1,313 @@
-use junobuild_satellite::AssertSetDocContext;
-use serde_json::Value;
+use junobuild_satellite::{get_doc_store, id};
+use super::dispatch::SetDocCall;
+use super::doc_index::list_indexed;
+use super::funding_deadline::parse_campaign_deadline;
+use super::islamic_contract_validation::{CONTRACT_TYPES, canonical_contract_type};
+use super::models::{BusinessApplication, Opportunity, decode_doc};
+use super::validation_error::ValidationError;
+
+/// Opportunity statuses that still hold an application's funding round
+const LIVE_OPPORTUNITY_STATUSES: [&str; 2] = ["active", "funded"];
+
+/// Funding goal bounds (₦)
+const MIN_FUNDING_GOAL: f64 = 100_000.0;
+const MAX_FUNDING_GOAL: f64 = 100_000_000.0;
+
+/// Lowest minimum investment an opportunity may set (₦)
+const MIN_INVESTMENT_FLOOR: f64 = 10_000.0;
 
 /// Validates investment opportunity creation
 /// Ensures opportunities are only created from approved applications
 pub fn assert_investment_opportunity_creation(
-    context: &AssertSetDocContext,
+    call: &SetDocCall<Opportunity>,
 ) -> Result<(), String> {
-    let collection = &context.data.collection;
+    let opportunity = &call.proposed;
     
-    if collection != "opportunities" {
-        return Ok(());
+    // 1. Verify opportunity is linked to an approved application
+    if opportunity.application_id.is_empty() {
+        return Err(ValidationError::new("MISSING_FIELD", "❌ Application ID cannot be empty")
+            .field("applicationId")
+            .into());
     }
+    assert_opportunity_application_link(call)?;
     
-    // Parse the proposed data from JSON string
-    let data_str = std::str::from_utf8(&context.data.data.proposed.data)
-        .map_err(|e| format!("Failed to decode opportunity data as UTF-8: {}", e))?;
+    // 2-7. Funding goal, minimum investment, returns, contract type, term and status
+    validate_opportunity_terms(opportunity)?;
+    let funding_goal = opportunity.funding_goal;
     
-    let data: Value = serde_json::from_str(data_str)
-        .map_err(|e| format!("Failed to parse opportunity data: {}", e))?;
-    
-    let data = data.as_object()
-        .ok_or("Invalid opportunity data format")?;
+    // 8. Ensure current funding doesn't exceed goal
+    if opportunity.current_funding > funding_goal {
+        return Err(ValidationError::new("INVALID_AMOUNT", "❌ Current funding cannot exceed funding goal")
+            .field("currentFunding")
+            .param("max", funding_goal)
+            .param("actual", opportunity.current_funding)
+            .into());
+    }
+    if opportunity.current_funding < 0.0 {
+        return Err(ValidationError::new("INVALID_AMOUNT", "❌ Current funding cannot be negative")
+            .field("currentFunding")
+            .param("min", 0.0)
+            .param("actual", opportunity.current_funding)
+            .into());
+    }
     
-    // 1. Verify opportunity is linked to an approved application
-    let application_id = data.get("applicationId")
-        .and_then(|v| v.as_str())
-        .ok_or("❌ Opportunity must be linked to an application")?;
+    // 9. Validate the funding window (closed by the funding deadline timer)
+    let Some(deadline) = opportunity.campaign_deadline.as_deref() else {
+        return Err(ValidationError::new(
+            "MISSING_FIELD",
+            "❌ Opportunity must have a campaignDeadline ending its funding window",
+        )
+        .field("campaignDeadline")
+        .into());
+    };
+    parse_campaign_deadline(deadline)?;
+    
+    if let Some(minimum) = opportunity.minimum_viable_funding {
+        if minimum <= 0.0 || minimum > funding_goal {
+            return Err(ValidationError::new(
+                "INVALID_AMOUNT",
+                "❌ Minimum viable funding must be positive and cannot exceed the funding goal",
+            )
+            .field("minimumViableFunding")
+            .param("max", funding_goal)
+            .param("actual", minimum)
+            .into());
+        }
+    }
     
-    if application_id.is_empty() {
-        return Err("❌ Application ID cannot be empty".to_string());
+    // 10. Verify approver information
+    if opportunity.approved_by.is_none() {
+        return Err(ValidationError::new("MISSING_FIELD", "❌ Opportunity must include approver information")
+            .field("approvedBy")
+            .into());
     }
     
+    Ok(())
+}
+
+/// Validates the opportunity's funding and contract terms
+/// Also applied to the terms an amendment would produce (see opportunity_amendments.rs)
+pub fn validate_opportunity_terms(opportunity: &Opportunity) -> Result<(), String> {
     // 2. Validate funding goal
-    let funding_goal = data.get("fundingGoal")
-        .and_then(|v| v.as_f64())
-        .ok_or("❌ Invalid funding goal")?;
-    
-    if funding_goal < 100_000.0 {
-        return Err("❌ Funding goal must be at least ₦100,000".to_string());
+    let funding_goal = opportunity.funding_goal;
+    
+    if funding_goal < MIN_FUNDING_GOAL {
+        return Err(ValidationError::new("INVALID_AMOUNT", "❌ Funding goal must be at least ₦100,000")
+            .field("fundingGoal")
+            .param("min", MIN_FUNDING_GOAL)
+            .param("actual", funding_goal)
+            .into());
     }
     
-    if funding_goal > 100_000_000.0 {
-        return Err("❌ Funding goal cannot exceed ₦100,000,000".to_string());
+    if funding_goal > MAX_FUNDING_GOAL {
+        return Err(ValidationError::new("INVALID_AMOUNT", "❌ Funding goal cannot exceed ₦100,000,000")
+            .field("fundingGoal")
+            .param("max", MAX_FUNDING_GOAL)
+            .param("actual", funding_goal)
+            .into());
     }
     
     // 3. Validate minimum investment
-    let min_investment = data.get("minimumInvestment")
-        .and_then(|v| v.as_f64())
-        .ok_or("❌ Invalid minimum investment")?;
-    
-    if min_investment < 10_000.0 {
-        return Err("❌ Minimum investment must be at least ₦10,000".to_string());
+    let min_investment = opportunity.minimum_investment;
+    
+    if min_investment < MIN_INVESTMENT_FLOOR {
+        return Err(ValidationError::new("INVALID_AMOUNT", "❌ Minimum investment must be at least ₦10,000")
+            .field("minimumInvestment")
+            .param("min", MIN_INVESTMENT_FLOOR)
+            .param("actual", min_investment)
+            .into());
     }
     
     if min_investment > funding_goal {
-        return Err("❌ Minimum investment cannot exceed funding goal".to_string());
+        return Err(ValidationError::new("INVALID_AMOUNT", "❌ Minimum investment cannot exceed funding goal")
+            .field("minimumInvestment")
+            .param("max", funding_goal)
+            .param("actual", min_investment)
+            .into());
     }
     
     // 4. Validate expected return rates
-    let return_min = data.get("expectedReturnMin")
-        .and_then(|v| v.as_f64())
-        .ok_or("❌ Invalid minimum return rate")?;
-    
-    let return_max = data.get("expectedReturnMax")
-        .and_then(|v| v.as_f64())
-        .ok_or("❌ Invalid maximum return rate")?;
-    
-    if return_min < 0.0 || return_min > 100.0 {
-        return Err("❌ Minimum return rate must be between 0% and 100%".to_string());
+    let return_min = opportunity.expected_return_min;
+    let return_max = opportunity.expected_return_max;
+    
+    if !(0.0..=100.0).contains(&return_min) {
+        return Err(ValidationError::new("OUT_OF_RANGE", "❌ Minimum return rate must be between 0% and 100%")
+            .field("expectedReturnMin")
+            .param("min", 0.0)
+            .param("max", 100.0)
+            .param("actual", return_min)
+            .into());
     }
     
-    if return_max < 0.0 || return_max > 100.0 {
-        return Err("❌ Maximum return rate must be between 0% and 100%".to_string());
+    if !(0.0..=100.0).contains(&return_max) {
+        return Err(ValidationError::new("OUT_OF_RANGE", "❌ Maximum return rate must be between 0% and 100%")
+            .field("expectedReturnMax")
+            .param("min", 0.0)
+            .param("max", 100.0)
+            .param("actual", return_max)
+            .into());
     }
     
     if return_min > return_max {
-        return Err("❌ Minimum return cannot exceed maximum return".to_string());
+        return Err(ValidationError::new("OUT_OF_RANGE", "❌ Minimum return cannot exceed maximum return")
+            .field("expectedReturnMin")
+            .param("max", return_max)
+            .param("actual", return_min)
+            .into());
     }
     
     // 5. Validate contract type (Shariah-compliant instruments)
-    let contract_type = data.get("contractType")
-        .and_then(|v| v.as_str())
-        .ok_or("❌ Invalid contract type")?;
-    
-    // Updated to match application contract types
-    let valid_contracts = vec!["musharaka", "mudaraba", "murabaha", "ijara", "istisna"];
-    if !valid_contracts.contains(&contract_type) {
-        return Err(format!(
-            "❌ Invalid contract type: {}. Must be Shariah-compliant: {:?}",
-            contract_type, valid_contracts
-        ));
+    if canonical_contract_type(&opportunity.contract_type).is_none() {
+        return Err(ValidationError::new(
+            "INVALID_CONTRACT_TYPE",
+            format!(
+                "❌ Invalid contract type: {}. Must be Shariah-compliant: {:?}",
+                opportunity.contract_type, CONTRACT_TYPES
+            ),
+        )
+        .field("contractType")
+        .param("actual", opportunity.contract_type.as_str())
+        .into());
     }
     
     // 6. Validate term months
-    let term_months = data.get("termMonths")
-        .and_then(|v| v.as_i64())
-        .ok_or("❌ Invalid term months")?;
-    
-    if term_months < 3 || term_months > 60 {
-        return Err("❌ Term must be between 3 and 60 months".to_string());
+    if !(3..=60).contains(&opportunity.term_months) {
+        return Err(ValidationError::new("OUT_OF_RANGE", "❌ Term must be between 3 and 60 months")
+            .field("termMonths")
+            .param("min", 3)
+            .param("max", 60)
+            .param("actual", opportunity.term_months)
+            .into());
     }
     
     // 7. Validate status
-    let status = data.get("status")
-        .and_then(|v| v.as_str())
-        .ok_or("❌ Invalid status")?;
-    
-    let valid_statuses = vec!["active", "funded", "closed", "cancelled"];
-    if !valid_statuses.contains(&status) {
-        return Err(format!("❌ Invalid status: {}", status));
+    let valid_statuses = ["active", "funded", "closed", "cancelled"];
+    if !valid_statuses.contains(&opportunity.status.as_str()) {
+        return Err(ValidationError::new("INVALID_STATUS", format!("❌ Invalid status: {}", opportunity.status))
+            .field("status")
+            .param("actual", opportunity.status.as_str())
+            .into());
     }
     
-    // 8. Ensure current funding doesn't exceed goal
-    if let Some(current_funding) = data.get("currentFunding").and_then(|v| v.as_f64()) {
-        if current_funding > funding_goal {
-            return Err("❌ Current funding cannot exceed funding goal".to_string());
-        }
-        if current_funding < 0.0 {
-            return Err("❌ Current funding cannot be negative".to_string());
-        }
+    Ok(())
+}
+
+/// Loads the linked application and the application's other opportunities
+fn assert_opportunity_application_link(call: &SetDocCall<Opportunity>) -> Result<(), String> {
+    let opportunity = &call.proposed;
+
+    let application = load_application(&opportunity.application_id)?;
+    validate_application_link(opportunity, application.as_ref())?;
+
+    let existing: Vec<(String, Opportunity)> =
+        list_indexed::<Opportunity>("opportunities", "application", &opportunity.application_id)?
+            .into_iter()
+            .map(|(key, _, opportunity)| (key, opportunity))
+            .collect();
+    validate_single_live_opportunity(call.key(), opportunity, &existing)
+}
+
+/// The business application an opportunity is created from, if it exists
+pub fn load_application(application_id: &str) -> Result<Option<BusinessApplication>, String> {
+    match get_doc_store(id(), "business_applications".to_string(), application_id.to_string())? {
+        Some(doc) => Ok(Some(decode_doc::<BusinessApplication>("business_applications", &doc.data)?)),
+        None => Ok(None),
     }
-    
-    // 9. Verify approver information
-    if !data.contains_key("approvedBy") {
-        return Err("❌ Opportunity must include approver information".to_string());
+}
+
+/// The application must be approved, for the same contract, and for at least the funding goal
+/// Also applied to the opportunity an amendment would produce (see opportunity_amendments.rs)
+pub fn validate_application_link(
+    opportunity: &Opportunity,
+    application: Option<&BusinessApplication>,
+) -> Result<(), String> {
+    let Some(application) = application else {
+        return Err(ValidationError::new(
+            "APPLICATION_NOT_FOUND",
+            format!("❌ Business application '{}' does not exist", opportunity.application_id),
+        )
+        .field("applicationId")
+        .param("applicationId", opportunity.application_id.as_str())
+        .critical()
+        .into());
+    };
+
+    if application.status != "approved" {
+        return Err(ValidationError::new(
+            "APPLICATION_NOT_APPROVED",
+            format!(
+                "❌ Opportunities can only be created from approved applications (status: {})",
+                application.status
+            ),
+        )
+        .field("applicationId")
+        .param("status", application.status.as_str())
+        .critical()
+        .into());
     }
-    
+
+    // Older opportunities spell the same contracts differently (musharakah, ijarah)
+    if canonical_contract_type(&opportunity.contract_type) != canonical_contract_type(&application.contract_type) {
+        return Err(ValidationError::new(
+            "CONTRACT_TYPE_MISMATCH",
+            format!(
+                "❌ Contract type '{}' does not match the approved application ('{}')",
+                opportunity.contract_type, application.contract_type
+            ),
+        )
+        .field("contractType")
+        .param("expected", application.contract_type.as_str())
+        .param("actual", opportunity.contract_type.as_str())
+        .into());
+    }
+
+    let requested_amount = application.requested_amount.unwrap_or(0.0);
+    if opportunity.funding_goal > requested_amount {
+        return Err(ValidationError::new(
+            "FUNDING_GOAL_EXCEEDS_REQUEST",
+            format!(
+                "❌ Funding goal (₦{:.2}) exceeds the approved requested amount (₦{:.2})",
+                opportunity.funding_goal, requested_amount
+            ),
+        )
+        .field("fundingGoal")
+        .param("max", requested_amount)
+        .param("actual", opportunity.funding_goal)
+        .into());
+    }
+
+    Ok(())
+}
+
+/// An application can back only one active or funded opportunity at a time
+pub fn validate_single_live_opportunity(
+    key: &str,
+    opportunity: &Opportunity,
+    existing: &[(String, Opportunity)],
+) -> Result<(), String> {
+    if !LIVE_OPPORTUNITY_STATUSES.contains(&opportunity.status.as_str()) {
+        return Ok(());
+    }
+
+    let duplicate = existing.iter().find(|(other_key, other)| {
+        other_key != key
+            && other.application_id == opportunity.application_id
+            && LIVE_OPPORTUNITY_STATUSES.contains(&other.status.as_str())
+    });
+
+    if let Some((other_key, other)) = duplicate {
+        return Err(ValidationError::new(
+            "DUPLICATE_OPPORTUNITY",
+            format!(
+                "❌ Application '{}' already has a live opportunity '{}' ({})",
+                opportunity.application_id, other_key, other.status
+            ),
+        )
+        .field("applicationId")
+        .param("opportunityId", other_key.as_str())
+        .into());
+    }
+
     Ok(())
 }
 
@@ -133,19 +315,11 @@ pub fn assert_investment_opportunity_creation(
 #[allow(dead_code)]
 pub fn validate_investment_transaction(
     investment_amount: f64,
-    opportunity_data: &Value,
+    opportunity: &Opportunity,
 ) -> Result<(), String> {
-    let min_investment = opportunity_data.get("minimumInvestment")
-        .and_then(|v| v.as_f64())
-        .unwrap_or(10_000.0);
-    
-    let funding_goal = opportunity_data.get("fundingGoal")
-        .and_then(|v| v.as_f64())
-        .unwrap_or(0.0);
-    
-    let current_funding = opportunity_data.get("currentFunding")
-        .and_then(|v| v.as_f64())
-        .unwrap_or(0.0);
+    let min_investment = opportunity.minimum_investment;
+    let funding_goal = opportunity.funding_goal;
+    let current_funding = opportunity.current_funding;
     
     // Check minimum investment
     if investment_amount < min_investment {
@@ -156,12 +330,8 @@ pub fn validate_investment_transaction(
     }
     
     // Check if opportunity is still open
-    let status = opportunity_data.get("status")
-        .and_then(|v| v.as_str())
-        .unwrap_or("closed");
-    
-    if status != "active" {
-        return Err(format!("Opportunity is not active (status: {})", status));
+    if opportunity.status != "active" {
+        return Err(format!("Opportunity is not active (status: {})", opportunity.status));
     }
     
     // Check if investment would exceed funding goal
@@ -200,32 +370,84 @@ pub fn calculate_profit_share(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::json;
+    use crate::business_financing::models::decode_doc;
+    use serde_json::{json, Value};
+    
+    fn opportunity(data: Value) -> Opportunity {
+        let mut base = json!({
+            "applicationId": "app_1",
+            "contractType": "musharaka",
+            "expectedReturnMin": 10.0,
+            "expectedReturnMax": 20.0,
+            "termMonths": 12
+        });
+        base.as_object_mut().unwrap().extend(data.as_object().unwrap().clone());
+        decode_doc("opportunities", &serde_json::to_vec(&base).unwrap()).unwrap()
+    }
     
     #[test]
     fn test_validate_investment_above_minimum() {
-        let opp_data = json!({
+        let opp_data = opportunity(json!({
             "minimumInvestment": 10000.0,
             "fundingGoal": 1000000.0,
             "currentFunding": 500000.0,
             "status": "active"
-        });
+        }));
         
         assert!(validate_investment_transaction(50000.0, &opp_data).is_ok());
     }
     
     #[test]
     fn test_validate_investment_below_minimum() {
-        let opp_data = json!({
+        let opp_data = opportunity(json!({
             "minimumInvestment": 10000.0,
             "fundingGoal": 1000000.0,
             "currentFunding": 500000.0,
             "status": "active"
-        });
+        }));
         
         assert!(validate_investment_transaction(5000.0, &opp_data).is_err());
     }
     
+    fn application(data: Value) -> BusinessApplication {
+        let mut base = json!({
+            "businessName": "Kano Agro",
+            "contractType": "musharaka",
+            "status": "approved",
+            "requestedAmount": 5000000.0
+        });
+        base.as_object_mut().unwrap().extend(data.as_object().unwrap().clone());
+        decode_doc("business_applications", &serde_json::to_vec(&base).unwrap()).unwrap()
+    }
+    
+    #[test]
+    fn test_opportunity_must_match_approved_application() {
+        let opp_data = opportunity(json!({
+            "minimumInvestment": 10000.0,
+            "fundingGoal": 5000000.0
+        }));
+        
+        assert!(validate_application_link(&opp_data, Some(&application(json!({})))).is_ok());
+        assert!(validate_application_link(&opp_data, None).is_err());
+        assert!(validate_application_link(&opp_data, Some(&application(json!({ "status": "review" })))).is_err());
+        assert!(validate_application_link(&opp_data, Some(&application(json!({ "contractType": "murabaha" })))).is_err());
+        let legacy_spelling = opportunity(json!({ "contractType": "musharakah", "minimumInvestment": 10000.0, "fundingGoal": 5000000.0 }));
+        assert!(validate_application_link(&legacy_spelling, Some(&application(json!({})))).is_ok());
+        assert!(validate_application_link(&opp_data, Some(&application(json!({ "requestedAmount": 4999999.0 })))).is_err());
+    }
+    
+    #[test]
+    fn test_one_live_opportunity_per_application() {
+        let live = opportunity(json!({ "minimumInvestment": 10000.0, "fundingGoal": 1000000.0, "status": "funded" }));
+        let cancelled = opportunity(json!({ "minimumInvestment": 10000.0, "fundingGoal": 1000000.0, "status": "cancelled" }));
+        let proposed = opportunity(json!({ "minimumInvestment": 10000.0, "fundingGoal": 1000000.0, "status": "active" }));
+        
+        assert!(validate_single_live_opportunity("opp_2", &proposed, &[("opp_1".to_string(), cancelled)]).is_ok());
+        assert!(validate_single_live_opportunity("opp_2", &proposed, &[("opp_1".to_string(), live.clone())]).is_err());
+        // Updating the live opportunity itself is fine
+        assert!(validate_single_live_opportunity("opp_1", &proposed, &[("opp_1".to_string(), live)]).is_ok());
+    }
+    
     #[test]
     fn test_calculate_profit_share() {
         let result = calculate_profit_share(100000.0, 1000000.0, 200000.0);
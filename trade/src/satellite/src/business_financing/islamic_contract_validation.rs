@@ -1,9 +1,33 @@
 use serde_json::Value;
+use super::validation_error::ValidationError;
+
+/// Contract types accepted on applications and opportunities, in their canonical spelling
+/// (mirrored by `src/utils/contract-type.ts`)
+pub const CONTRACT_TYPES: [&str; 5] = ["musharaka", "mudaraba", "murabaha", "ijara", "istisna"];
+
+/// Canonical spelling of a contract type, accepting the `-ah` spellings of older opportunities
+pub fn canonical_contract_type(value: &str) -> Option<&'static str> {
+    let lower = value.trim().to_lowercase();
+    let canonical = match lower.as_str() {
+        "musharakah" => "musharaka",
+        "mudarabah" => "mudaraba",
+        "ijarah" => "ijara",
+        "istisnaa" => "istisna",
+        other => other,
+    };
+    CONTRACT_TYPES.into_iter().find(|contract_type| *contract_type == canonical)
+}
+
+/// Rejection for a field nested in one of the revenue report's contract details objects
+fn detail_error(code: &str, details: &str, key: &str, message: impl Into<String>) -> ValidationError {
+    ValidationError::new(code, message).field(&format!("{}.{}", details, key))
+}
 
 /// Validates Murabaha (Cost-Plus Financing) contract-specific fields
 pub fn validate_murabaha_details(details: &Value) -> Result<(), String> {
-    let details_obj = details.as_object()
-        .ok_or("Invalid Murabaha details format")?;
+    let details_obj = details.as_object().ok_or_else(|| {
+        ValidationError::new("INVALID_FORMAT", "❌ Invalid Murabaha details format").field("murabahaDetails")
+    })?;
     
     // Extract values
     let asset_cost = details_obj.get("assetCost")
@@ -20,20 +44,32 @@ pub fn validate_murabaha_details(details: &Value) -> Result<(), String> {
     // Validate asset cost and markup
     if let (Some(cost), Some(markup)) = (asset_cost, markup_amount) {
         if cost <= 0.0 {
-            return Err("❌ Murabaha: Asset cost must be positive".to_string());
+            return Err(detail_error("INVALID_AMOUNT", "murabahaDetails", "assetCost", "❌ Murabaha: Asset cost must be positive")
+                .param("actual", cost)
+                .into());
         }
         
         if markup <= 0.0 {
-            return Err("❌ Murabaha: Markup amount must be positive".to_string());
+            return Err(detail_error("INVALID_AMOUNT", "murabahaDetails", "markupAmount", "❌ Murabaha: Markup amount must be positive")
+                .param("actual", markup)
+                .into());
         }
         
         // Islamic finance guideline: Markup typically shouldn't exceed 30% of cost
         let markup_percentage = (markup / cost) * 100.0;
         if markup_percentage > 30.0 {
-            return Err(format!(
-                "❌ Murabaha: Markup {:.1}% exceeds recommended Islamic limit of 30%",
-                markup_percentage
-            ));
+            return Err(detail_error(
+                "SHARIAH_NON_COMPLIANT",
+                "murabahaDetails",
+                "markupAmount",
+                format!(
+                    "❌ Murabaha: Markup {:.1}% exceeds recommended Islamic limit of 30%",
+                    markup_percentage
+                ),
+            )
+            .param("max", 30.0)
+            .param("actual", markup_percentage)
+            .into());
         }
         
         // Validate remaining balance calculation
@@ -41,7 +77,8 @@ pub fn validate_murabaha_details(details: &Value) -> Result<(), String> {
             (installments_paid, installments_remaining, remaining_balance) {
             let total_installments = paid + remaining;
             if total_installments == 0 {
-                return Err("❌ Murabaha: Total installments cannot be zero".to_string());
+                return Err(detail_error("INVALID_FIELD", "murabahaDetails", "installmentsRemaining", "❌ Murabaha: Total installments cannot be zero")
+                    .into());
             }
             
             let total_price = cost + markup;
@@ -51,10 +88,18 @@ pub fn validate_murabaha_details(details: &Value) -> Result<(), String> {
             // Allow 1% tolerance for rounding
             let tolerance = expected_balance * 0.01;
             if (balance - expected_balance).abs() > tolerance {
-                return Err(format!(
-                    "❌ Murabaha: Remaining balance ₦{:.2} doesn't match expected ₦{:.2}",
-                    balance, expected_balance
-                ));
+                return Err(detail_error(
+                    "DERIVED_FIELD",
+                    "murabahaDetails",
+                    "remainingBalance",
+                    format!(
+                        "❌ Murabaha: Remaining balance ₦{:.2} doesn't match expected ₦{:.2}",
+                        balance, expected_balance
+                    ),
+                )
+                .param("expected", expected_balance)
+                .param("actual", balance)
+                .into());
             }
         }
     }
@@ -64,8 +109,9 @@ pub fn validate_murabaha_details(details: &Value) -> Result<(), String> {
 
 /// Validates Mudaraba (Trust Financing) contract-specific fields
 pub fn validate_mudaraba_details(details: &Value) -> Result<(), String> {
-    let details_obj = details.as_object()
-        .ok_or("Invalid Mudaraba details format")?;
+    let details_obj = details.as_object().ok_or_else(|| {
+        ValidationError::new("INVALID_FORMAT", "❌ Invalid Mudaraba details format").field("mudarabaDetails")
+    })?;
     
     // Extract profit share percentages
     let investor_share = details_obj.get("investorProfitShare")
@@ -75,21 +121,33 @@ pub fn validate_mudaraba_details(details: &Value) -> Result<(), String> {
     
     // Validate profit sharing ratios
     if let (Some(investor), Some(mudarib)) = (investor_share, mudarib_share) {
-        if investor < 0.0 || investor > 100.0 {
-            return Err("❌ Mudaraba: Investor profit share must be between 0-100%".to_string());
+        if !(0.0..=100.0).contains(&investor) {
+            return Err(detail_error("OUT_OF_RANGE", "mudarabaDetails", "investorProfitShare", "❌ Mudaraba: Investor profit share must be between 0-100%")
+                .param("actual", investor)
+                .into());
         }
         
-        if mudarib < 0.0 || mudarib > 100.0 {
-            return Err("❌ Mudaraba: Mudarib profit share must be between 0-100%".to_string());
+        if !(0.0..=100.0).contains(&mudarib) {
+            return Err(detail_error("OUT_OF_RANGE", "mudarabaDetails", "mudaribProfitShare", "❌ Mudaraba: Mudarib profit share must be between 0-100%")
+                .param("actual", mudarib)
+                .into());
         }
         
         // Critical Shariah requirement: Profit shares must sum to 100%
         let total = investor + mudarib;
         if (total - 100.0).abs() > 0.01 {
-            return Err(format!(
-                "❌ Mudaraba: Profit shares must sum to 100% (got {:.2}%)",
-                total
-            ));
+            return Err(detail_error(
+                "SHARIAH_NON_COMPLIANT",
+                "mudarabaDetails",
+                "investorProfitShare",
+                format!(
+                    "❌ Mudaraba: Profit shares must sum to 100% (got {:.2}%)",
+                    total
+                ),
+            )
+            .param("expected", 100.0)
+            .param("actual", total)
+            .into());
         }
         
         // Validate actual profit distribution matches ratios
@@ -107,17 +165,33 @@ pub fn validate_mudaraba_details(details: &Value) -> Result<(), String> {
                 
                 // Allow 2% tolerance for rounding
                 if (actual_inv_percentage - investor).abs() > 2.0 {
-                    return Err(format!(
-                        "❌ Mudaraba: Investor actual profit {:.1}% doesn't match agreed {:.1}%",
-                        actual_inv_percentage, investor
-                    ));
+                    return Err(detail_error(
+                        "DERIVED_FIELD",
+                        "mudarabaDetails",
+                        "actualInvestorProfit",
+                        format!(
+                            "❌ Mudaraba: Investor actual profit {:.1}% doesn't match agreed {:.1}%",
+                            actual_inv_percentage, investor
+                        ),
+                    )
+                    .param("expected", investor)
+                    .param("actual", actual_inv_percentage)
+                    .into());
                 }
                 
                 if (actual_mud_percentage - mudarib).abs() > 2.0 {
-                    return Err(format!(
-                        "❌ Mudaraba: Mudarib actual profit {:.1}% doesn't match agreed {:.1}%",
-                        actual_mud_percentage, mudarib
-                    ));
+                    return Err(detail_error(
+                        "DERIVED_FIELD",
+                        "mudarabaDetails",
+                        "actualMudaribProfit",
+                        format!(
+                            "❌ Mudaraba: Mudarib actual profit {:.1}% doesn't match agreed {:.1}%",
+                            actual_mud_percentage, mudarib
+                        ),
+                    )
+                    .param("expected", mudarib)
+                    .param("actual", actual_mud_percentage)
+                    .into());
                 }
             }
         }
@@ -126,7 +200,9 @@ pub fn validate_mudaraba_details(details: &Value) -> Result<(), String> {
     // Validate capital is positive
     if let Some(capital) = details_obj.get("capitalProvided").and_then(|v| v.as_f64()) {
         if capital <= 0.0 {
-            return Err("❌ Mudaraba: Capital provided must be positive".to_string());
+            return Err(detail_error("INVALID_AMOUNT", "mudarabaDetails", "capitalProvided", "❌ Mudaraba: Capital provided must be positive")
+                .param("actual", capital)
+                .into());
         }
     }
     
@@ -135,8 +211,9 @@ pub fn validate_mudaraba_details(details: &Value) -> Result<(), String> {
 
 /// Validates Musharaka (Partnership) contract-specific fields
 pub fn validate_musharaka_details(details: &Value) -> Result<(), String> {
-    let details_obj = details.as_object()
-        .ok_or("Invalid Musharaka details format")?;
+    let details_obj = details.as_object().ok_or_else(|| {
+        ValidationError::new("INVALID_FORMAT", "❌ Invalid Musharaka details format").field("musharakahDetails")
+    })?;
     
     // Extract capital contributions
     let party1_capital = details_obj.get("party1Capital")
@@ -147,13 +224,17 @@ pub fn validate_musharaka_details(details: &Value) -> Result<(), String> {
     // Validate capitals are positive
     if let Some(cap1) = party1_capital {
         if cap1 <= 0.0 {
-            return Err("❌ Musharaka: Partner 1 capital must be positive".to_string());
+            return Err(detail_error("INVALID_AMOUNT", "musharakahDetails", "party1Capital", "❌ Musharaka: Partner 1 capital must be positive")
+                .param("actual", cap1)
+                .into());
         }
     }
     
     if let Some(cap2) = party2_capital {
         if cap2 <= 0.0 {
-            return Err("❌ Musharaka: Partner 2 capital must be positive".to_string());
+            return Err(detail_error("INVALID_AMOUNT", "musharakahDetails", "party2Capital", "❌ Musharaka: Partner 2 capital must be positive")
+                .param("actual", cap2)
+                .into());
         }
     }
     
@@ -165,21 +246,33 @@ pub fn validate_musharaka_details(details: &Value) -> Result<(), String> {
     
     // Validate profit sharing
     if let (Some(share1), Some(share2)) = (party1_share, party2_share) {
-        if share1 < 0.0 || share1 > 100.0 {
-            return Err("❌ Musharaka: Partner 1 profit share must be between 0-100%".to_string());
+        if !(0.0..=100.0).contains(&share1) {
+            return Err(detail_error("OUT_OF_RANGE", "musharakahDetails", "party1ProfitShare", "❌ Musharaka: Partner 1 profit share must be between 0-100%")
+                .param("actual", share1)
+                .into());
         }
         
-        if share2 < 0.0 || share2 > 100.0 {
-            return Err("❌ Musharaka: Partner 2 profit share must be between 0-100%".to_string());
+        if !(0.0..=100.0).contains(&share2) {
+            return Err(detail_error("OUT_OF_RANGE", "musharakahDetails", "party2ProfitShare", "❌ Musharaka: Partner 2 profit share must be between 0-100%")
+                .param("actual", share2)
+                .into());
         }
         
         // Profit shares must sum to 100%
         let total = share1 + share2;
         if (total - 100.0).abs() > 0.01 {
-            return Err(format!(
-                "❌ Musharaka: Profit shares must sum to 100% (got {:.2}%)",
-                total
-            ));
+            return Err(detail_error(
+                "SHARIAH_NON_COMPLIANT",
+                "musharakahDetails",
+                "party1ProfitShare",
+                format!(
+                    "❌ Musharaka: Profit shares must sum to 100% (got {:.2}%)",
+                    total
+                ),
+            )
+            .param("expected", 100.0)
+            .param("actual", total)
+            .into());
         }
         
         // In traditional Musharaka, profit shares should be proportional to capital
@@ -212,17 +305,33 @@ pub fn validate_musharaka_details(details: &Value) -> Result<(), String> {
                 
                 // Allow 2% tolerance
                 if (actual_share1 - share1).abs() > 2.0 {
-                    return Err(format!(
-                        "❌ Musharaka: Partner 1 actual profit {:.1}% doesn't match agreed {:.1}%",
-                        actual_share1, share1
-                    ));
+                    return Err(detail_error(
+                        "DERIVED_FIELD",
+                        "musharakahDetails",
+                        "actualParty1Profit",
+                        format!(
+                            "❌ Musharaka: Partner 1 actual profit {:.1}% doesn't match agreed {:.1}%",
+                            actual_share1, share1
+                        ),
+                    )
+                    .param("expected", share1)
+                    .param("actual", actual_share1)
+                    .into());
                 }
                 
                 if (actual_share2 - share2).abs() > 2.0 {
-                    return Err(format!(
-                        "❌ Musharaka: Partner 2 actual profit {:.1}% doesn't match agreed {:.1}%",
-                        actual_share2, share2
-                    ));
+                    return Err(detail_error(
+                        "DERIVED_FIELD",
+                        "musharakahDetails",
+                        "actualParty2Profit",
+                        format!(
+                            "❌ Musharaka: Partner 2 actual profit {:.1}% doesn't match agreed {:.1}%",
+                            actual_share2, share2
+                        ),
+                    )
+                    .param("expected", share2)
+                    .param("actual", actual_share2)
+                    .into());
                 }
             }
         }
@@ -230,8 +339,10 @@ pub fn validate_musharaka_details(details: &Value) -> Result<(), String> {
     
     // Validate buyout progress for diminishing Musharaka
     if let Some(buyout) = details_obj.get("buyoutProgress").and_then(|v| v.as_f64()) {
-        if buyout < 0.0 || buyout > 100.0 {
-            return Err("❌ Musharaka: Buyout progress must be between 0-100%".to_string());
+        if !(0.0..=100.0).contains(&buyout) {
+            return Err(detail_error("OUT_OF_RANGE", "musharakahDetails", "buyoutProgress", "❌ Musharaka: Buyout progress must be between 0-100%")
+                .param("actual", buyout)
+                .into());
         }
     }
     
@@ -240,8 +351,9 @@ pub fn validate_musharaka_details(details: &Value) -> Result<(), String> {
 
 /// Validates Ijara (Leasing) contract-specific fields
 pub fn validate_ijara_details(details: &Value) -> Result<(), String> {
-    let details_obj = details.as_object()
-        .ok_or("Invalid Ijara details format")?;
+    let details_obj = details.as_object().ok_or_else(|| {
+        ValidationError::new("INVALID_FORMAT", "❌ Invalid Ijara details format").field("ijaraDetails")
+    })?;
     
     // Extract values
     let asset_value = details_obj.get("assetValue")
@@ -256,17 +368,24 @@ pub fn validate_ijara_details(details: &Value) -> Result<(), String> {
     // Validate asset value
     if let Some(value) = asset_value {
         if value <= 0.0 {
-            return Err("❌ Ijara: Asset value must be positive".to_string());
+            return Err(detail_error("INVALID_AMOUNT", "ijaraDetails", "assetValue", "❌ Ijara: Asset value must be positive")
+                .param("actual", value)
+                .into());
         }
         
         // Validate depreciation doesn't exceed asset value
         if let Some(depreciation) = asset_depreciation {
             if depreciation < 0.0 {
-                return Err("❌ Ijara: Depreciation cannot be negative".to_string());
+                return Err(detail_error("INVALID_AMOUNT", "ijaraDetails", "assetDepreciation", "❌ Ijara: Depreciation cannot be negative")
+                    .param("actual", depreciation)
+                    .into());
             }
             
             if depreciation > value {
-                return Err("❌ Ijara: Depreciation cannot exceed asset value".to_string());
+                return Err(detail_error("INVALID_AMOUNT", "ijaraDetails", "assetDepreciation", "❌ Ijara: Depreciation cannot exceed asset value")
+                    .param("max", value)
+                    .param("actual", depreciation)
+                    .into());
             }
         }
     }
@@ -274,7 +393,9 @@ pub fn validate_ijara_details(details: &Value) -> Result<(), String> {
     // Validate monthly rental
     if let Some(rental) = monthly_rental {
         if rental <= 0.0 {
-            return Err("❌ Ijara: Monthly rental must be positive".to_string());
+            return Err(detail_error("INVALID_AMOUNT", "ijaraDetails", "monthlyRental", "❌ Ijara: Monthly rental must be positive")
+                .param("actual", rental)
+                .into());
         }
         
         // Validate total rentals don't exceed reasonable profit margin
@@ -284,10 +405,18 @@ pub fn validate_ijara_details(details: &Value) -> Result<(), String> {
             // Islamic guideline: Total rentals shouldn't exceed asset value + 50% profit
             let max_reasonable_total = value * 1.5;
             if total_rentals_paid > max_reasonable_total {
-                return Err(format!(
-                    "❌ Ijara: Total rentals ₦{:.2} exceed reasonable limit ₦{:.2}",
-                    total_rentals_paid, max_reasonable_total
-                ));
+                return Err(detail_error(
+                    "OUT_OF_RANGE",
+                    "ijaraDetails",
+                    "rentalsPaid",
+                    format!(
+                        "❌ Ijara: Total rentals ₦{:.2} exceed reasonable limit ₦{:.2}",
+                        total_rentals_paid, max_reasonable_total
+                    ),
+                )
+                .param("max", max_reasonable_total)
+                .param("actual", total_rentals_paid)
+                .into());
             }
         }
     }
@@ -295,17 +424,27 @@ pub fn validate_ijara_details(details: &Value) -> Result<(), String> {
     // Validate maintenance costs are reasonable
     if let Some(maintenance) = details_obj.get("maintenanceCosts").and_then(|v| v.as_f64()) {
         if maintenance < 0.0 {
-            return Err("❌ Ijara: Maintenance costs cannot be negative".to_string());
+            return Err(detail_error("INVALID_AMOUNT", "ijaraDetails", "maintenanceCosts", "❌ Ijara: Maintenance costs cannot be negative")
+                .param("actual", maintenance)
+                .into());
         }
         
         // Maintenance shouldn't exceed 20% of asset value per reporting period
         if let Some(value) = asset_value {
             let max_maintenance = value * 0.2;
             if maintenance > max_maintenance {
-                return Err(format!(
-                    "❌ Ijara: Maintenance costs ₦{:.2} seem excessive (max ₦{:.2})",
-                    maintenance, max_maintenance
-                ));
+                return Err(detail_error(
+                    "OUT_OF_RANGE",
+                    "ijaraDetails",
+                    "maintenanceCosts",
+                    format!(
+                        "❌ Ijara: Maintenance costs ₦{:.2} seem excessive (max ₦{:.2})",
+                        maintenance, max_maintenance
+                    ),
+                )
+                .param("max", max_maintenance)
+                .param("actual", maintenance)
+                .into());
             }
         }
     }
@@ -315,20 +454,25 @@ pub fn validate_ijara_details(details: &Value) -> Result<(), String> {
 
 /// Validates Salam/Istisna (Forward Sales) contract-specific fields
 pub fn validate_salam_details(details: &Value) -> Result<(), String> {
-    let details_obj = details.as_object()
-        .ok_or("Invalid Salam/Istisna details format")?;
+    let details_obj = details.as_object().ok_or_else(|| {
+        ValidationError::new("INVALID_FORMAT", "❌ Invalid Salam/Istisna details format").field("salamDetails")
+    })?;
     
     // Validate advance payment (must be 100% in Salam)
     if let Some(advance) = details_obj.get("advancePaymentReceived").and_then(|v| v.as_f64()) {
         if advance <= 0.0 {
-            return Err("❌ Salam: Advance payment must be positive".to_string());
+            return Err(detail_error("INVALID_AMOUNT", "salamDetails", "advancePaymentReceived", "❌ Salam: Advance payment must be positive")
+                .param("actual", advance)
+                .into());
         }
     }
     
     // Validate production progress
     if let Some(progress) = details_obj.get("productionProgress").and_then(|v| v.as_f64()) {
-        if progress < 0.0 || progress > 100.0 {
-            return Err("❌ Salam: Production progress must be between 0-100%".to_string());
+        if !(0.0..=100.0).contains(&progress) {
+            return Err(detail_error("OUT_OF_RANGE", "salamDetails", "productionProgress", "❌ Salam: Production progress must be between 0-100%")
+                .param("actual", progress)
+                .into());
         }
     }
     
@@ -338,25 +482,39 @@ pub fn validate_salam_details(details: &Value) -> Result<(), String> {
     
     if let (Some(ordered), Some(delivered)) = (qty_ordered, qty_delivered) {
         if ordered <= 0.0 {
-            return Err("❌ Salam: Quantity ordered must be positive".to_string());
+            return Err(detail_error("INVALID_AMOUNT", "salamDetails", "quantityOrdered", "❌ Salam: Quantity ordered must be positive")
+                .param("actual", ordered)
+                .into());
         }
         
         if delivered < 0.0 {
-            return Err("❌ Salam: Quantity delivered cannot be negative".to_string());
+            return Err(detail_error("INVALID_AMOUNT", "salamDetails", "quantityDelivered", "❌ Salam: Quantity delivered cannot be negative")
+                .param("actual", delivered)
+                .into());
         }
         
         if delivered > ordered {
-            return Err(format!(
-                "❌ Salam: Quantity delivered ({:.2}) cannot exceed ordered ({:.2})",
-                delivered, ordered
-            ));
+            return Err(detail_error(
+                "OUT_OF_RANGE",
+                "salamDetails",
+                "quantityDelivered",
+                format!(
+                    "❌ Salam: Quantity delivered ({:.2}) cannot exceed ordered ({:.2})",
+                    delivered, ordered
+                ),
+            )
+            .param("max", ordered)
+            .param("actual", delivered)
+            .into());
         }
     }
     
     // Validate production costs
     if let Some(costs) = details_obj.get("productionCosts").and_then(|v| v.as_f64()) {
         if costs < 0.0 {
-            return Err("❌ Salam: Production costs cannot be negative".to_string());
+            return Err(detail_error("INVALID_AMOUNT", "salamDetails", "productionCosts", "❌ Salam: Production costs cannot be negative")
+                .param("actual", costs)
+                .into());
         }
     }
     
@@ -368,7 +526,7 @@ pub fn validate_contract_specific_details(
     contract_type: &str,
     details: &Value,
 ) -> Result<(), String> {
-    match contract_type {
+    match canonical_contract_type(contract_type).unwrap_or(contract_type) {
         "murabaha" => validate_murabaha_details(details),
         "mudaraba" => validate_mudaraba_details(details),
         "musharaka" => validate_musharaka_details(details),
@@ -383,6 +541,14 @@ mod tests {
     use super::*;
     use serde_json::json;
     
+    #[test]
+    fn test_contract_type_spellings() {
+        assert_eq!(canonical_contract_type("musharakah"), Some("musharaka"));
+        assert_eq!(canonical_contract_type("Ijarah"), Some("ijara"));
+        assert_eq!(canonical_contract_type("istisna"), Some("istisna"));
+        assert_eq!(canonical_contract_type("riba"), None);
+    }
+    
     #[test]
     fn test_murabaha_valid() {
         let details = json!({
@@ -1,4 +1,5 @@
 use serde_json::Value;
+use super::validation_error::ValidationError;
 
 /// Validates KYC status and completeness
 #[allow(dead_code)]
@@ -7,20 +8,27 @@ pub fn validate_kyc_status(profile_data: &Value) -> Result<(), String> {
         .and_then(|v| v.as_str())
         .unwrap_or("pending");
     
-    let valid_statuses = vec!["pending", "in-review", "verified", "rejected"];
+    let valid_statuses = ["pending", "in-review", "verified", "rejected"];
     
     if !valid_statuses.contains(&kyc_status) {
-        return Err(format!("❌ Invalid KYC status: {}", kyc_status));
+        return Err(ValidationError::new("INVALID_STATUS", format!("❌ Invalid KYC status: {}", kyc_status))
+            .field("kycStatus")
+            .param("actual", kyc_status)
+            .into());
     }
     
     // If status is verified, ensure verification fields are present
     if kyc_status == "verified" {
         if profile_data.get("kycVerifiedAt").is_none() {
-            return Err("❌ Verified KYC must include verification timestamp".to_string());
+            return Err(ValidationError::new("MISSING_FIELD", "❌ Verified KYC must include verification timestamp")
+                .field("kycVerifiedAt")
+                .into());
         }
         
         if profile_data.get("approvedBy").is_none() {
-            return Err("❌ Verified KYC must include approver information".to_string());
+            return Err(ValidationError::new("MISSING_FIELD", "❌ Verified KYC must include approver information")
+                .field("approvedBy")
+                .into());
         }
     }
     
@@ -32,17 +40,25 @@ pub fn validate_kyc_status(profile_data: &Value) -> Result<(), String> {
 pub fn validate_bvn(bvn: &str) -> Result<(), String> {
     // BVN must be exactly 11 digits
     if bvn.len() != 11 {
-        return Err(format!("❌ BVN must be 11 digits. Got: {}", bvn.len()));
+        return Err(ValidationError::new("INVALID_FORMAT", format!("❌ BVN must be 11 digits. Got: {}", bvn.len()))
+            .field("bvn")
+            .param("expected", 11)
+            .param("actual", bvn.len())
+            .into());
     }
     
     // BVN must contain only numbers
     if !bvn.chars().all(|c| c.is_numeric()) {
-        return Err("❌ BVN must contain only numeric characters".to_string());
+        return Err(ValidationError::new("INVALID_FORMAT", "❌ BVN must contain only numeric characters")
+            .field("bvn")
+            .into());
     }
     
     // No spaces or special characters
     if bvn.contains(char::is_whitespace) {
-        return Err("❌ BVN cannot contain spaces".to_string());
+        return Err(ValidationError::new("INVALID_FORMAT", "❌ BVN cannot contain spaces")
+            .field("bvn")
+            .into());
     }
     
     Ok(())
@@ -52,20 +68,24 @@ pub fn validate_bvn(bvn: &str) -> Result<(), String> {
 #[allow(dead_code)]
 pub fn validate_registration_number(reg_number: &str, business_type: &str) -> Result<(), String> {
     if reg_number.trim().is_empty() {
-        return Err("❌ Registration number cannot be empty".to_string());
+        return Err(ValidationError::new("MISSING_FIELD", "❌ Registration number cannot be empty")
+            .field("registrationNumber")
+            .into());
     }
     
     // For Nigerian businesses, RC numbers typically start with "RC" or "BN"
     match business_type {
-        "llc" | "corporation" => {
-            if !reg_number.starts_with("RC") && !reg_number.starts_with("rc") {
-                return Err("❌ Limited companies should have RC (Registered Company) number".to_string());
-            }
+        "llc" | "corporation" if !reg_number.starts_with("RC") && !reg_number.starts_with("rc") => {
+            return Err(ValidationError::new("INVALID_FORMAT", "❌ Limited companies should have RC (Registered Company) number")
+                .field("registrationNumber")
+                .param("expectedPrefix", "RC")
+                .into());
         }
-        "sole_proprietorship" => {
-            if !reg_number.starts_with("BN") && !reg_number.starts_with("bn") {
-                return Err("❌ Sole proprietorships should have BN (Business Name) number".to_string());
-            }
+        "sole_proprietorship" if !reg_number.starts_with("BN") && !reg_number.starts_with("bn") => {
+            return Err(ValidationError::new("INVALID_FORMAT", "❌ Sole proprietorships should have BN (Business Name) number")
+                .field("registrationNumber")
+                .param("expectedPrefix", "BN")
+                .into());
         }
         _ => {
             // Other business types may have varying formats
@@ -102,17 +122,28 @@ pub fn validate_shariah_compliance(industry: &str, business_description: &str) -
     // Check industry
     for prohibited in &prohibited_keywords {
         if industry_lower.contains(prohibited) {
-            return Err(format!(
-                "❌ Shariah Compliance: Business involves prohibited activity ({})",
-                prohibited
-            ));
+            return Err(ValidationError::new(
+                "SHARIAH_NON_COMPLIANT",
+                format!("❌ Shariah Compliance: Business involves prohibited activity ({})", prohibited),
+            )
+            .field("industry")
+            .param("keyword", *prohibited)
+            .critical()
+            .into());
         }
         
         if description_lower.contains(prohibited) {
-            return Err(format!(
-                "❌ Shariah Compliance: Business description mentions prohibited activity ({})",
-                prohibited
-            ));
+            return Err(ValidationError::new(
+                "SHARIAH_NON_COMPLIANT",
+                format!(
+                    "❌ Shariah Compliance: Business description mentions prohibited activity ({})",
+                    prohibited
+                ),
+            )
+            .field("businessDescription")
+            .param("keyword", *prohibited)
+            .critical()
+            .into());
         }
     }
     
@@ -127,10 +158,17 @@ pub fn validate_shariah_compliance(industry: &str, business_description: &str) -
     
     for prohibited_industry in prohibited_industries {
         if industry_lower == prohibited_industry {
-            return Err(format!(
-                "❌ Shariah Compliance: Industry '{}' is not permissible for Islamic financing",
-                industry
-            ));
+            return Err(ValidationError::new(
+                "SHARIAH_NON_COMPLIANT",
+                format!(
+                    "❌ Shariah Compliance: Industry '{}' is not permissible for Islamic financing",
+                    industry
+                ),
+            )
+            .field("industry")
+            .param("actual", industry)
+            .critical()
+            .into());
         }
     }
     
@@ -147,29 +185,40 @@ pub fn validate_identity_document(
         "national_id" | "nin" => {
             // Nigerian NIN is 11 digits
             if id_number.len() != 11 || !id_number.chars().all(|c| c.is_numeric()) {
-                return Err("❌ National ID (NIN) must be 11 digits".to_string());
+                return Err(ValidationError::new("INVALID_FORMAT", "❌ National ID (NIN) must be 11 digits")
+                    .field("idNumber")
+                    .into());
             }
         }
         "drivers_license" => {
             // Nigerian driver's license format varies, basic validation
             if id_number.len() < 10 {
-                return Err("❌ Driver's license number seems invalid (too short)".to_string());
+                return Err(ValidationError::new("INVALID_FORMAT", "❌ Driver's license number seems invalid (too short)")
+                    .field("idNumber")
+                    .into());
             }
         }
         "international_passport" => {
             // Nigerian passport is typically 9 characters (A12345678)
             if id_number.len() < 8 || id_number.len() > 10 {
-                return Err("❌ Passport number format invalid".to_string());
+                return Err(ValidationError::new("INVALID_FORMAT", "❌ Passport number format invalid")
+                    .field("idNumber")
+                    .into());
             }
         }
         "voters_card" => {
             // PVC numbers vary
             if id_number.len() < 10 {
-                return Err("❌ Voter's card number seems invalid".to_string());
+                return Err(ValidationError::new("INVALID_FORMAT", "❌ Voter's card number seems invalid")
+                    .field("idNumber")
+                    .into());
             }
         }
         _ => {
-            return Err(format!("❌ Unsupported ID type: {}", id_type));
+            return Err(ValidationError::new("INVALID_FIELD", format!("❌ Unsupported ID type: {}", id_type))
+                .field("idType")
+                .param("actual", id_type)
+                .into());
         }
     }
     
@@ -221,14 +270,19 @@ pub fn perform_aml_check(profile_data: &Value) -> Vec<String> {
 pub fn validate_contact_information(email: &str, phone: &str) -> Result<(), String> {
     // Email validation
     if !email.contains('@') || !email.contains('.') {
-        return Err("❌ Invalid email format".to_string());
+        return Err(ValidationError::new("INVALID_FORMAT", "❌ Invalid email format")
+            .field("email")
+            .into());
     }
     
     // Check for common typos in email domains
     let suspicious_domains = vec!["gmial.com", "yahooo.com", "hotmial.com"];
     for suspicious in suspicious_domains {
         if email.ends_with(suspicious) {
-            return Err(format!("❌ Suspicious email domain: {}. Please verify", suspicious));
+            return Err(ValidationError::new("INVALID_FORMAT", format!("❌ Suspicious email domain: {}. Please verify", suspicious))
+                .field("email")
+                .param("domain", suspicious)
+                .into());
         }
     }
     
@@ -236,13 +290,22 @@ pub fn validate_contact_information(email: &str, phone: &str) -> Result<(), Stri
     let phone_clean = phone.replace(&[' ', '-', '(', ')', '+'][..], "");
     
     if phone_clean.len() < 10 || phone_clean.len() > 14 {
-        return Err("❌ Invalid phone number length".to_string());
+        return Err(ValidationError::new("INVALID_FORMAT", "❌ Invalid phone number length")
+            .field("phone")
+            .param("min", 10)
+            .param("max", 14)
+            .param("actual", phone_clean.len())
+            .into());
     }
     
     // Check if it's mostly numeric
     let numeric_count = phone_clean.chars().filter(|c| c.is_numeric()).count();
     if numeric_count < 10 {
-        return Err("❌ Phone number must contain at least 10 digits".to_string());
+        return Err(ValidationError::new("INVALID_FORMAT", "❌ Phone number must contain at least 10 digits")
+            .field("phone")
+            .param("min", 10)
+            .param("actual", numeric_count)
+            .into());
     }
     
     Ok(())
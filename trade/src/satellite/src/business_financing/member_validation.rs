@@ -1,58 +1,60 @@
-use junobuild_satellite::AssertSetDocContext;
-use serde_json::Value;
+use chrono::{DateTime, Months, NaiveDate};
+use junobuild_satellite::get_doc;
+use super::admin_permissions::{has_sufficient_role, insufficient_role};
+use super::dispatch::SetDocCall;
+use super::doc_index::list_indexed;
+use super::models::{InvestorProfile, Investment, decode_doc};
+use super::revenue_report_validation::{ic_today, parse_report_date};
+use super::validation_error::ValidationError;
+
+/// Collections holding investor profiles (key = the member's principal)
+const INVESTOR_PROFILE_COLLECTIONS: [&str; 2] = ["individual_investor_profiles", "corporate_investor_profiles"];
 
 /// Validate member number format when approving KYC
 /// Ensures membership numbers follow the correct format: INV-YYYY-NNNN
-pub fn assert_member_number_uniqueness(context: &AssertSetDocContext) -> Result<(), String> {
-    let collection = &context.data.collection;
-
-    // Only validate for investor profile collections
-    if collection != "individual_investor_profiles" && collection != "corporate_investor_profiles" {
-        return Ok(());
-    }
-
-    // Parse the proposed data from JSON string
-    let data_str = std::str::from_utf8(&context.data.data.proposed.data)
-        .map_err(|e| format!("Failed to decode member profile data as UTF-8: {}", e))?;
-
-    let data: Value = serde_json::from_str(data_str)
-        .map_err(|e| format!("Failed to parse member profile data: {}", e))?;
-
-    let data_obj = data.as_object()
-        .ok_or("Invalid member profile data format")?;
+pub fn assert_member_number_uniqueness(call: &SetDocCall<InvestorProfile>) -> Result<(), String> {
+    let profile = &call.proposed;
 
     // Only check when KYC is being verified
-    let kyc_status = data_obj.get("kycStatus")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-
-    if kyc_status != "verified" {
+    if profile.kyc_status != "verified" {
         return Ok(());
     }
 
     // Get member number if assigned
-    let member_number = match data_obj.get("memberNumber").and_then(|v| v.as_str()) {
+    let member_number = match profile.member_number.as_deref() {
         Some(num) => num,
         None => return Ok(()), // No member number assigned yet - allow
     };
 
     // Validate member number format (INV-YYYY-NNNN)
     if !is_valid_member_number_format(member_number) {
-        return Err(format!(
-            "❌ Invalid membership number format: '{}'. Expected format: INV-YYYY-NNNN (e.g., INV-2025-0001)",
-            member_number
-        ));
+        return Err(ValidationError::new(
+            "INVALID_MEMBER_NUMBER",
+            format!(
+                "❌ Invalid membership number format: '{}'. Expected format: INV-YYYY-NNNN (e.g., INV-2025-0001)",
+                member_number
+            ),
+        )
+        .field("memberNumber")
+        .param("actual", member_number)
+        .into());
     }
 
     // Validate year is reasonable (2020-2100)
     let parts: Vec<&str> = member_number.split('-').collect();
     if parts.len() == 3 {
         if let Ok(year) = parts[1].parse::<i32>() {
-            if year < 2020 || year > 2100 {
-                return Err(format!(
-                    "❌ Invalid membership number year: {}. Year must be between 2020 and 2100",
-                    year
-                ));
+            if !(2020..=2100).contains(&year) {
+                return Err(ValidationError::new(
+                    "INVALID_MEMBER_NUMBER",
+                    format!(
+                        "❌ Invalid membership number year: {}. Year must be between 2020 and 2100",
+                        year
+                    ),
+                )
+                .field("memberNumber")
+                .param("year", year)
+                .into());
             }
         }
     }
@@ -108,61 +110,40 @@ mod tests {
     }
 }
 
-/// ============================================================================
-/// CORE MEMBER VALIDATIONS - HIGH PRIORITY
-/// ============================================================================
+// ============================================================================
+// CORE MEMBER VALIDATIONS - HIGH PRIORITY
+// ============================================================================
 
 /// 1. PROFILE UPDATE RESTRICTIONS
+///
 /// Enforce immutable fields to prevent identity fraud and maintain data integrity
-pub fn assert_immutable_fields(context: &AssertSetDocContext) -> Result<(), String> {
-    let collection = &context.data.collection;
-
-    // Only validate for investor profile collections
-    if collection != "individual_investor_profiles" && collection != "corporate_investor_profiles" {
-        return Ok(());
-    }
-
+pub fn assert_immutable_fields(call: &SetDocCall<InvestorProfile>) -> Result<(), String> {
     // Get both current (existing) and proposed (new) data
-    let current_data = match &context.data.data.current {
+    let current = match &call.current {
         Some(current) => current,
         None => return Ok(()), // New profile creation - no restrictions
     };
-
-    // Parse current data
-    let current_str = std::str::from_utf8(&current_data.data)
-        .map_err(|e| format!("Failed to decode current profile data: {}", e))?;
-    let current_obj: Value = serde_json::from_str(current_str)
-        .map_err(|e| format!("Failed to parse current profile data: {}", e))?;
-    let current_data = current_obj.as_object()
-        .ok_or("Invalid current profile format")?;
-
-    // Parse proposed data
-    let proposed_str = std::str::from_utf8(&context.data.data.proposed.data)
-        .map_err(|e| format!("Failed to decode proposed profile data: {}", e))?;
-    let proposed_obj: Value = serde_json::from_str(proposed_str)
-        .map_err(|e| format!("Failed to parse proposed profile data: {}", e))?;
-    let proposed_data = proposed_obj.as_object()
-        .ok_or("Invalid proposed profile format")?;
+    let proposed = &call.proposed;
 
     // Check investor type to determine which fields to validate
-    let investor_type = proposed_data.get("investorType")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-
-    if investor_type == "individual" {
-        // INDIVIDUAL IMMUTABLE FIELDS
-        validate_field_unchanged(current_data, proposed_data, "fullName", "Full Name")?;
-        validate_field_unchanged(current_data, proposed_data, "nationality", "Nationality")?;
-        validate_field_unchanged(current_data, proposed_data, "idType", "ID Type")?;
-        validate_field_unchanged(current_data, proposed_data, "idNumber", "ID Number")?;
-        validate_field_unchanged(current_data, proposed_data, "dateOfBirth", "Date of Birth")?;
-    } else if investor_type == "corporate" {
-        // CORPORATE IMMUTABLE FIELDS
-        validate_field_unchanged(current_data, proposed_data, "companyName", "Company Name")?;
-        validate_field_unchanged(current_data, proposed_data, "registrationNumber", "Registration Number")?;
-        validate_field_unchanged(current_data, proposed_data, "legalEntityType", "Legal Entity Type")?;
-        validate_field_unchanged(current_data, proposed_data, "incorporationDate", "Incorporation Date")?;
-        validate_field_unchanged(current_data, proposed_data, "registrationCountry", "Registration Country")?;
+    match proposed.investor_type.as_deref() {
+        Some("individual") => {
+            // INDIVIDUAL IMMUTABLE FIELDS
+            validate_field_unchanged(&current.full_name, &proposed.full_name, "fullName", "Full Name")?;
+            validate_field_unchanged(&current.nationality, &proposed.nationality, "nationality", "Nationality")?;
+            validate_field_unchanged(&current.id_type, &proposed.id_type, "idType", "ID Type")?;
+            validate_field_unchanged(&current.id_number, &proposed.id_number, "idNumber", "ID Number")?;
+            validate_field_unchanged(&current.date_of_birth, &proposed.date_of_birth, "dateOfBirth", "Date of Birth")?;
+        }
+        Some("corporate") => {
+            // CORPORATE IMMUTABLE FIELDS
+            validate_field_unchanged(&current.company_name, &proposed.company_name, "companyName", "Company Name")?;
+            validate_field_unchanged(&current.registration_number, &proposed.registration_number, "registrationNumber", "Registration Number")?;
+            validate_field_unchanged(&current.legal_entity_type, &proposed.legal_entity_type, "legalEntityType", "Legal Entity Type")?;
+            validate_field_unchanged(&current.incorporation_date, &proposed.incorporation_date, "incorporationDate", "Incorporation Date")?;
+            validate_field_unchanged(&current.registration_country, &proposed.registration_country, "registrationCountry", "Registration Country")?;
+        }
+        _ => {}
     }
 
     Ok(())
@@ -170,201 +151,579 @@ pub fn assert_immutable_fields(context: &AssertSetDocContext) -> Result<(), Stri
 
 /// Helper function to validate a field hasn't changed
 fn validate_field_unchanged(
-    current: &serde_json::Map<String, Value>,
-    proposed: &serde_json::Map<String, Value>,
+    current: &Option<String>,
+    proposed: &Option<String>,
     field: &str,
     field_name: &str,
 ) -> Result<(), String> {
-    let current_value = current.get(field);
-    let proposed_value = proposed.get(field);
-
     // If both exist and are different, reject
-    if let (Some(curr), Some(prop)) = (current_value, proposed_value) {
+    if let (Some(curr), Some(prop)) = (current, proposed) {
         if curr != prop {
-            return Err(format!(
-                "❌ SECURITY VIOLATION: {} cannot be changed after registration. This field is immutable for regulatory compliance and identity verification.",
-                field_name
-            ));
+            return Err(ValidationError::new(
+                "IMMUTABLE_FIELD",
+                format!(
+                    "❌ SECURITY VIOLATION: {} cannot be changed after registration. This field is immutable for regulatory compliance and identity verification.",
+                    field_name
+                ),
+            )
+            .field(field)
+            .critical()
+            .into());
         }
     }
 
     Ok(())
 }
 
+/// KYC statuses a member sets on their own profile: filling it in and submitting it for review
+const MEMBER_KYC_STATUSES: [&str; 2] = ["pending", "in-review"];
+
+/// Lowest admin role that may change the fields the investment gates read
+const PROFILE_REVIEW_ROLE: &str = "reviewer";
+
+/// Reviewer-only fields changed by a profile write: the KYC gate and the investment limit tier
+/// A new profile is compared with the onboarding defaults (pending KYC, active account, retail tier)
+pub fn reviewed_profile_changes(current: Option<&InvestorProfile>, proposed: &InvestorProfile) -> Vec<&'static str> {
+    let mut changed = Vec::new();
+    let current_kyc_status = current.map_or("pending", |current| current.kyc_status.as_str());
+    if proposed.kyc_status != current_kyc_status && !MEMBER_KYC_STATUSES.contains(&proposed.kyc_status.as_str()) {
+        changed.push("kycStatus");
+    }
+    let current_account_status = current.and_then(|current| current.account_status.as_deref());
+    if current_account_status.unwrap_or("active") != proposed.account_status.as_deref().unwrap_or("active") {
+        changed.push("accountStatus");
+    }
+    if current.and_then(|current| current.kyc_review_date.as_ref()) != proposed.kyc_review_date.as_ref() {
+        changed.push("kycReviewDate");
+    }
+
+    // The investment limit tier (see InvestmentLimits::for_profile)
+    if current.is_some_and(|current| current.accredited) != proposed.accredited {
+        changed.push("accredited");
+    }
+    if current.and_then(|current| current.investor_tier.as_ref()) != proposed.investor_tier.as_ref() {
+        changed.push("investorTier");
+    }
+    if current.and_then(|current| current.annual_income) != proposed.annual_income {
+        changed.push("annualIncome");
+    }
+    changed
+}
+
+/// Only reviewers (or higher) change the fields the KYC gate and the investment limits trust
+/// `caller_role` is None when the caller is not an active admin
+pub fn validate_reviewed_profile_fields(
+    current: Option<&InvestorProfile>,
+    proposed: &InvestorProfile,
+    caller_role: Option<&str>,
+) -> Result<(), String> {
+    let changed = reviewed_profile_changes(current, proposed);
+    let Some(field) = changed.first().copied() else {
+        return Ok(());
+    };
+
+    match caller_role {
+        Some(role) if has_sufficient_role(role, PROFILE_REVIEW_ROLE) => Ok(()),
+        Some(role) => Err(insufficient_role(
+            format!(
+                "❌ Access Denied: Role '{}' cannot change {}. Required: '{}' or higher",
+                role, field, PROFILE_REVIEW_ROLE
+            ),
+            role,
+            PROFILE_REVIEW_ROLE,
+        )
+        .field(field)
+        .into()),
+        None => Err(ValidationError::new(
+            "REVIEWER_ONLY_FIELD",
+            format!("❌ SECURITY VIOLATION: {} can only be changed by a KYC reviewer", field),
+        )
+        .field(field)
+        .param("fields", changed)
+        .critical()
+        .into()),
+    }
+}
+
+/// Members fill in and submit their KYC; verification, suspension, review dates and tiers are set by reviewers
+pub fn assert_reviewed_profile_fields(call: &SetDocCall<InvestorProfile>) -> Result<(), String> {
+    let caller_role = call.caller_admin_profile().ok().map(|profile| profile.role);
+    validate_reviewed_profile_fields(call.current.as_ref(), &call.proposed, caller_role.as_deref())
+}
+
 /// 2. INVESTMENT AUTHORIZATION
+///
 /// Ensure only verified members can make investments
-pub fn assert_kyc_verified_for_investment(context: &AssertSetDocContext) -> Result<(), String> {
-    let collection = &context.data.collection;
+pub fn assert_kyc_verified_for_investment(call: &SetDocCall<Investment>) -> Result<(), String> {
+    // Get investor ID to check their KYC status
+    let investment = &call.proposed;
+    if investment.investor_id.trim().is_empty() {
+        return Err(ValidationError::new("MISSING_FIELD", "❌ Investment must have an investorId")
+            .field("investorId")
+            .into());
+    }
 
-    // Only validate for investment collection
-    if collection != "investments" {
+    // Existing investments keep their investor (admins update status and returns)
+    if let Some(current) = &call.current {
+        if current.investor_id != investment.investor_id {
+            return Err(ValidationError::new(
+                "IMMUTABLE_FIELD",
+                "❌ SECURITY VIOLATION: The investor of an investment cannot be changed",
+            )
+            .field("investorId")
+            .critical()
+            .into());
+        }
+        if current.opportunity_id != investment.opportunity_id {
+            return Err(ValidationError::new(
+                "IMMUTABLE_FIELD",
+                "❌ SECURITY VIOLATION: The opportunity of an investment cannot be changed",
+            )
+            .field("opportunityId")
+            .critical()
+            .into());
+        }
         return Ok(());
     }
 
-    // Parse investment data
-    let data_str = std::str::from_utf8(&context.data.data.proposed.data)
-        .map_err(|e| format!("Failed to decode investment data: {}", e))?;
-    let data: Value = serde_json::from_str(data_str)
-        .map_err(|e| format!("Failed to parse investment data: {}", e))?;
-    let data_obj = data.as_object()
-        .ok_or("Invalid investment data format")?;
+    // Funding, concentration caps and settlement are all keyed by the opportunity
+    if investment.opportunity_id.as_deref().is_none_or(|key| key.trim().is_empty()) {
+        return Err(ValidationError::new("MISSING_FIELD", "❌ Investment must have an opportunityId")
+            .field("opportunityId")
+            .into());
+    }
 
-    // Get investor ID to check their KYC status
-    let _investor_id = data_obj.get("investorId")
-        .and_then(|v| v.as_str())
-        .ok_or("❌ Investment must have an investorId")?;
+    // Members invest for themselves only
+    if investment.investor_id != call.caller() {
+        return Err(ValidationError::new(
+            "CALLER_MISMATCH",
+            "❌ SECURITY VIOLATION: investorId must be the member making the investment",
+        )
+        .field("investorId")
+        .critical()
+        .into());
+    }
+
+    let profile = load_investor_profile(&investment.investor_id)?;
+    validate_investor_kyc(profile.as_ref(), ic_today())
+}
+
+/// Investor profile of `investor_id` (individual first, then corporate)
+pub fn load_investor_profile(investor_id: &str) -> Result<Option<InvestorProfile>, String> {
+    for collection in INVESTOR_PROFILE_COLLECTIONS {
+        if let Some(doc) = get_doc(collection.to_string(), investor_id.to_string()) {
+            return decode_doc(collection, &doc.data).map(Some);
+        }
+    }
+    Ok(None)
+}
+
+/// A profile may invest while verified, not suspended and within its KYC review date
+pub fn validate_investor_kyc(profile: Option<&InvestorProfile>, today: NaiveDate) -> Result<(), String> {
+    let Some(profile) = profile else {
+        return Err(ValidationError::new(
+            "KYC_NOT_VERIFIED",
+            "❌ KYC REQUIRED: Complete your investor profile and KYC verification before investing",
+        )
+        .field("investorId")
+        .critical()
+        .into());
+    };
+
+    if profile.kyc_status != "verified" {
+        return Err(ValidationError::new(
+            "KYC_NOT_VERIFIED",
+            format!(
+                "❌ KYC REQUIRED: Your KYC status is '{}'. Only verified members can invest",
+                profile.kyc_status
+            ),
+        )
+        .field("investorId")
+        .param("kycStatus", profile.kyc_status.as_str())
+        .critical()
+        .into());
+    }
 
-    // Note: In a full implementation, we would query the datastore here to check KYC status
-    // For now, we document the requirement - actual datastore query would need async context
-    // This serves as documentation and will be enforced by frontend + admin review
+    if profile.account_status.as_deref() == Some("suspended") {
+        return Err(ValidationError::new(
+            "ACCOUNT_SUSPENDED",
+            "❌ Your member account is suspended. Please contact support",
+        )
+        .field("investorId")
+        .critical()
+        .into());
+    }
+
+    if let Some(review_date) = profile.kyc_review_date.as_deref() {
+        if parse_report_date("kycReviewDate", review_date)? < today {
+            return Err(ValidationError::new(
+                "KYC_REVIEW_OVERDUE",
+                format!(
+                    "❌ KYC REVIEW OVERDUE: Your KYC was due for review on {}. Please update your KYC before investing",
+                    review_date
+                ),
+            )
+            .field("investorId")
+            .param("kycReviewDate", review_date)
+            .critical()
+            .into());
+        }
+    }
 
     Ok(())
 }
 
 /// 3. INVESTMENT LIMITS
-/// Enforce maximum investment amounts based on accredited status
-pub fn assert_investment_limits(context: &AssertSetDocContext) -> Result<(), String> {
-    let collection = &context.data.collection;
+///
+/// Enforce maximum investment amounts (₦) based on the investor's tier, per investment and
+/// as a rolling 12-month total, following the SEC Nigeria crowdfunding rules for retail investors
+pub fn assert_investment_limits(call: &SetDocCall<Investment>) -> Result<(), String> {
+    // Get investment amount
+    let investment = &call.proposed;
+    let amount = investment.amount;
+    if !amount.is_finite() || amount <= 0.0 {
+        return Err(ValidationError::new("INVALID_AMOUNT", "❌ Investment must have a valid amount")
+            .field("amount")
+            .into());
+    }
 
-    // Only validate for investment collection
-    if collection != "investments" {
+    // Limits are checked when the money is committed - the amount never changes afterwards
+    if let Some(current) = &call.current {
+        if current.amount != amount {
+            return Err(ValidationError::new(
+                "IMMUTABLE_FIELD",
+                "❌ SECURITY VIOLATION: The amount of an existing investment cannot be changed",
+            )
+            .field("amount")
+            .critical()
+            .into());
+        }
         return Ok(());
     }
 
-    // Parse investment data
-    let data_str = std::str::from_utf8(&context.data.data.proposed.data)
-        .map_err(|e| format!("Failed to decode investment data: {}", e))?;
-    let data: Value = serde_json::from_str(data_str)
-        .map_err(|e| format!("Failed to parse investment data: {}", e))?;
-    let data_obj = data.as_object()
-        .ok_or("Invalid investment data format")?;
+    let profile = load_investor_profile(&investment.investor_id)?;
+    let limits = InvestmentLimits::for_profile(profile.as_ref());
+
+    // Rolling 12-month window over the investor's other investments
+    let window_start = DateTime::from_timestamp_nanos(ic_cdk::api::time() as i64)
+        .checked_sub_months(Months::new(12))
+        .map(|start| start.timestamp_nanos_opt().unwrap_or(0) as u64)
+        .unwrap_or(0);
+    let invested_in_window = list_indexed::<Investment>("investments", "investor", &investment.investor_id)?
+        .into_iter()
+        .filter(|(key, doc, _)| key != call.key() && doc.created_at >= window_start)
+        .map(|(_, _, other)| other)
+        .filter(|other| !other.status.as_deref().is_some_and(|status| RELEASED_INVESTMENT_STATUSES.contains(&status)))
+        .map(|other| other.amount)
+        .sum();
+
+    validate_investment_limits(amount, invested_in_window, &limits)
+}
 
-    // Get investment amount
-    let amount = data_obj.get("amount")
-        .and_then(|v| v.as_f64())
-        .ok_or("❌ Investment must have a valid amount")?;
+/// Investment statuses whose money went back to the investor (outside the annual total)
+pub const RELEASED_INVESTMENT_STATUSES: [&str; 2] = ["cancelled", "refunded"];
+
+/// Hard limits (₦)
+const ABSOLUTE_MAX: f64 = 100_000_000.0; // ₦100M - largest opportunity the platform lists
+const NON_ACCREDITED_MAX: f64 = 2_000_000.0; // ₦2M per investment for retail investors
+const NON_ACCREDITED_ANNUAL_MAX: f64 = 10_000_000.0; // ₦10M per rolling 12 months
+const ACCREDITED_MAX: f64 = 50_000_000.0; // ₦50M per investment for sophisticated investors
+const ACCREDITED_ANNUAL_MAX: f64 = 200_000_000.0; // ₦200M per rolling 12 months
+
+/// Share of net annual income a retail investor may invest per 12 months (SEC Nigeria)
+const RETAIL_INCOME_SHARE: f64 = 0.10;
+
+/// Investment caps (₦) of one investor
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvestmentLimits {
+    pub tier: String,
+    pub per_investment: f64,
+    /// None = no aggregate cap (institutional investors)
+    pub annual: Option<f64>,
+}
 
-    // Hard limits (can be made configurable later)
-    const NON_ACCREDITED_MAX: f64 = 10000.0; // $10,000 max per investment
-    const ACCREDITED_MAX: f64 = 100000.0; // $100,000 max per investment
-    const ABSOLUTE_MAX: f64 = 1000000.0; // $1M absolute maximum
+impl InvestmentLimits {
+    /// Limits for the profile's stored tier (`investorTier`, falling back to `accredited`)
+    pub fn for_profile(profile: Option<&InvestorProfile>) -> Self {
+        let tier = profile
+            .and_then(|profile| profile.investor_tier.clone())
+            .unwrap_or_else(|| {
+                if profile.is_some_and(|profile| profile.accredited) { "sophisticated" } else { "retail" }.to_string()
+            });
+
+        match tier.as_str() {
+            "institutional" => Self { tier, per_investment: ABSOLUTE_MAX, annual: None },
+            "sophisticated" => Self { tier, per_investment: ACCREDITED_MAX, annual: Some(ACCREDITED_ANNUAL_MAX) },
+            _ => {
+                // Retail investors are further capped at 10% of their declared net annual income
+                let income_cap = profile
+                    .and_then(|profile| profile.annual_income)
+                    .filter(|income| *income > 0.0)
+                    .map(|income| income * RETAIL_INCOME_SHARE);
+                let annual = income_cap.map_or(NON_ACCREDITED_ANNUAL_MAX, |cap| cap.min(NON_ACCREDITED_ANNUAL_MAX));
+
+                Self { tier: "retail".to_string(), per_investment: NON_ACCREDITED_MAX, annual: Some(annual) }
+            }
+        }
+    }
+}
 
+/// Checks `amount` against the per-investment and rolling 12-month caps
+pub fn validate_investment_limits(amount: f64, invested_in_window: f64, limits: &InvestmentLimits) -> Result<(), String> {
     // Check absolute maximum (applies to everyone)
     if amount > ABSOLUTE_MAX {
-        return Err(format!(
-            "❌ INVESTMENT LIMIT EXCEEDED: Investment amount ${:.2} exceeds absolute maximum of ${:.2}. Please contact support for high-value investments.",
-            amount, ABSOLUTE_MAX
-        ));
+        return Err(ValidationError::new(
+            "INVESTMENT_LIMIT_EXCEEDED",
+            format!(
+                "❌ INVESTMENT LIMIT EXCEEDED: Investment amount ₦{:.2} exceeds absolute maximum of ₦{:.2}. Please contact support for high-value investments.",
+                amount, ABSOLUTE_MAX
+            ),
+        )
+        .field("amount")
+        .param("limit", ABSOLUTE_MAX)
+        .param("actual", amount)
+        .into());
+    }
+
+    if amount > limits.per_investment {
+        return Err(ValidationError::new(
+            "INVESTMENT_LIMIT_EXCEEDED",
+            format!(
+                "❌ INVESTMENT LIMIT EXCEEDED: {} investors can invest at most ₦{:.2} per investment (requested ₦{:.2})",
+                limits.tier, limits.per_investment, amount
+            ),
+        )
+        .field("amount")
+        .param("tier", limits.tier.as_str())
+        .param("limit", limits.per_investment)
+        .param("actual", amount)
+        .into());
     }
 
-    // Note: Accredited status check would require querying investor profile
-    // For now, we enforce the non-accredited limit as a safety baseline
-    // Admins can override for accredited investors during review
-    if amount > NON_ACCREDITED_MAX {
-        // Log warning but don't block (admin review will verify accredited status)
-        // In production, we'd query the investor profile here
+    if let Some(annual) = limits.annual {
+        if invested_in_window + amount > annual {
+            return Err(ValidationError::new(
+                "ANNUAL_LIMIT_EXCEEDED",
+                format!(
+                    "❌ ANNUAL LIMIT EXCEEDED: {} investors can invest at most ₦{:.2} in any 12 months. Invested so far: ₦{:.2}, remaining: ₦{:.2}",
+                    limits.tier,
+                    annual,
+                    invested_in_window,
+                    (annual - invested_in_window).max(0.0)
+                ),
+            )
+            .field("amount")
+            .param("tier", limits.tier.as_str())
+            .param("limit", annual)
+            .param("invested", invested_in_window)
+            .param("actual", amount)
+            .into());
+        }
     }
 
     Ok(())
 }
 
 /// 4. CORPORATE UBO VALIDATION
+///
 /// Ensure beneficial owners are properly disclosed (>=25% ownership)
-pub fn assert_corporate_ubo_compliance(context: &AssertSetDocContext) -> Result<(), String> {
-    let collection = &context.data.collection;
-
+pub fn assert_corporate_ubo_compliance(call: &SetDocCall<InvestorProfile>) -> Result<(), String> {
     // Only validate for corporate investor profiles
-    if collection != "corporate_investor_profiles" {
+    if call.collection() != "corporate_investor_profiles" {
         return Ok(());
     }
 
-    // Parse profile data
-    let data_str = std::str::from_utf8(&context.data.data.proposed.data)
-        .map_err(|e| format!("Failed to decode corporate profile data: {}", e))?;
-    let data: Value = serde_json::from_str(data_str)
-        .map_err(|e| format!("Failed to parse corporate profile data: {}", e))?;
-    let data_obj = data.as_object()
-        .ok_or("Invalid corporate profile format")?;
+    let profile = &call.proposed;
 
     // Only check when KYC is being submitted or verified
-    let kyc_status = data_obj.get("kycStatus")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-
-    if kyc_status != "in-review" && kyc_status != "verified" {
+    if profile.kyc_status != "in-review" && profile.kyc_status != "verified" {
         return Ok(()); // Don't enforce for pending/draft profiles
     }
 
     // Get beneficial owners array
-    let beneficial_owners = data_obj.get("beneficialOwners")
-        .and_then(|v| v.as_array())
-        .ok_or("❌ COMPLIANCE VIOLATION: Corporate profiles must disclose beneficial owners (UBO)")?;
+    let beneficial_owners = profile.beneficial_owners.as_ref()
+        .ok_or_else(|| {
+            ubo_violation("❌ COMPLIANCE VIOLATION: Corporate profiles must disclose beneficial owners (UBO)".to_string())
+        })?;
 
     // Must have at least one beneficial owner
     if beneficial_owners.is_empty() {
-        return Err(
+        return Err(ubo_violation(
             "❌ COMPLIANCE VIOLATION: At least one beneficial owner with 25% or more ownership must be disclosed for AML/KYC compliance.".to_string()
-        );
+        )
+        .into());
     }
 
     // Validate each beneficial owner has required ownership percentage
     let mut total_ownership = 0.0;
     for (idx, owner) in beneficial_owners.iter().enumerate() {
-        let owner_obj = owner.as_object()
-            .ok_or(format!("❌ Beneficial owner {} has invalid format", idx + 1))?;
-
-        let ownership = owner_obj.get("ownershipPercentage")
-            .and_then(|v| v.as_f64())
-            .ok_or(format!("❌ Beneficial owner {} must have ownershipPercentage", idx + 1))?;
+        let ownership = owner.ownership_percentage
+            .ok_or_else(|| {
+                ubo_violation(format!("❌ Beneficial owner {} must have ownershipPercentage", idx + 1))
+                    .param("owner", idx + 1)
+            })?;
 
         // Each UBO must have at least 25% ownership
         if ownership < 25.0 {
-            return Err(format!(
+            return Err(ubo_violation(format!(
                 "❌ COMPLIANCE VIOLATION: Beneficial owner {} has {:.1}% ownership. Only owners with 25% or more ownership need to be disclosed (Ultimate Beneficial Owners).",
                 idx + 1, ownership
-            ));
+            ))
+            .param("owner", idx + 1)
+            .into());
         }
 
         if ownership > 100.0 {
-            return Err(format!(
+            return Err(ubo_violation(format!(
                 "❌ COMPLIANCE VIOLATION: Beneficial owner {} has {:.1}% ownership. Ownership percentage cannot exceed 100%.",
                 idx + 1, ownership
-            ));
+            ))
+            .param("owner", idx + 1)
+            .into());
         }
 
         total_ownership += ownership;
 
         // Validate required fields
-        if !owner_obj.contains_key("fullName") || !owner_obj.contains_key("nationality") {
-            return Err(format!(
+        if owner.full_name.is_none() || owner.nationality.is_none() {
+            return Err(ubo_violation(format!(
                 "❌ COMPLIANCE VIOLATION: Beneficial owner {} must have fullName and nationality for KYC compliance.",
                 idx + 1
-            ));
+            ))
+            .param("owner", idx + 1)
+            .into());
         }
 
-        if !owner_obj.contains_key("idType") || !owner_obj.contains_key("idNumber") {
-            return Err(format!(
+        if owner.id_type.is_none() || owner.id_number.is_none() {
+            return Err(ubo_violation(format!(
                 "❌ COMPLIANCE VIOLATION: Beneficial owner {} must have valid identification (idType and idNumber) for KYC compliance.",
                 idx + 1
-            ));
+            ))
+            .param("owner", idx + 1)
+            .into());
         }
     }
 
     // Total ownership can exceed 100% (multiple majority owners possible in some structures)
     // but we warn if it's suspiciously high
     if total_ownership > 200.0 {
-        return Err(format!(
+        return Err(ubo_violation(format!(
             "❌ COMPLIANCE WARNING: Total beneficial ownership is {:.1}%. This seems unusually high. Please verify ownership structure.",
             total_ownership
-        ));
+        ))
+        .param("totalOwnership", total_ownership)
+        .into());
     }
 
     Ok(())
 }
 
+/// Rejection for an incomplete or inconsistent beneficial ownership disclosure
+fn ubo_violation(message: String) -> ValidationError {
+    ValidationError::new("UBO_DISCLOSURE_INVALID", message)
+        .field("beneficialOwners")
+        .critical()
+}
+
 #[cfg(test)]
 mod tests_core_validations {
     use super::*;
+    use serde_json::{json, Value};
+
+    fn profile(data: Value) -> InvestorProfile {
+        decode_doc("individual_investor_profiles", &serde_json::to_vec(&data).unwrap()).unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_only_verified_active_members_invest() {
+        let today = date("2026-10-17");
+
+        assert!(validate_investor_kyc(Some(&profile(json!({ "kycStatus": "verified" }))), today).is_ok());
+        assert!(validate_investor_kyc(None, today).is_err());
+        assert!(validate_investor_kyc(Some(&profile(json!({ "kycStatus": "in-review" }))), today).is_err());
+        assert!(validate_investor_kyc(
+            Some(&profile(json!({ "kycStatus": "verified", "accountStatus": "suspended" }))),
+            today
+        ).is_err());
+    }
+
+    #[test]
+    fn test_limits_follow_investor_tier() {
+        let retail = InvestmentLimits::for_profile(Some(&profile(json!({ "kycStatus": "verified" }))));
+        let accredited = InvestmentLimits::for_profile(Some(&profile(json!({ "kycStatus": "verified", "accredited": true }))));
+        let institutional = InvestmentLimits::for_profile(Some(&profile(json!({ "investorTier": "institutional" }))));
+        let low_income = InvestmentLimits::for_profile(Some(&profile(json!({ "annualIncome": 6_000_000.0 }))));
+
+        assert_eq!((retail.per_investment, retail.annual), (NON_ACCREDITED_MAX, Some(NON_ACCREDITED_ANNUAL_MAX)));
+        assert_eq!(accredited.tier, "sophisticated");
+        assert_eq!(institutional.annual, None);
+        assert_eq!(low_income.annual, Some(600_000.0));
+    }
+
+    #[test]
+    fn test_rolling_annual_total_is_enforced() {
+        let retail = InvestmentLimits::for_profile(None);
+
+        assert!(validate_investment_limits(2_000_000.0, 8_000_000.0, &retail).is_ok());
+        assert!(validate_investment_limits(2_000_001.0, 0.0, &retail).is_err());
+
+        let err = ValidationError::parse(&validate_investment_limits(1_000_000.0, 9_500_000.0, &retail).unwrap_err()).unwrap();
+        assert_eq!(err.code, "ANNUAL_LIMIT_EXCEEDED");
+    }
+
+    #[test]
+    fn test_owner_cannot_change_kyc_review_fields() {
+        let current = profile(json!({ "kycStatus": "in-review", "kycReviewDate": "2026-10-01" }));
+
+        for change in [
+            json!({ "kycStatus": "verified", "kycReviewDate": "2026-10-01" }),
+            json!({ "kycStatus": "in-review", "kycReviewDate": "2027-10-01" }),
+            json!({ "kycStatus": "in-review", "kycReviewDate": "2026-10-01", "accountStatus": "suspended" }),
+        ] {
+            let err = validate_reviewed_profile_fields(Some(&current), &profile(change), None).unwrap_err();
+            assert_eq!(ValidationError::parse(&err).unwrap().code, "REVIEWER_ONLY_FIELD");
+        }
+
+        let verified = profile(json!({ "kycStatus": "verified", "kycReviewDate": "2026-10-01" }));
+        assert!(validate_reviewed_profile_fields(Some(&current), &verified, Some("viewer")).is_err());
+        assert!(validate_reviewed_profile_fields(Some(&current), &verified, Some("reviewer")).is_ok());
+
+        // A new profile, then its KYC submission
+        let pending = profile(json!({ "kycStatus": "pending", "accountStatus": "active" }));
+        assert!(validate_reviewed_profile_fields(None, &pending, None).is_ok());
+        assert!(validate_reviewed_profile_fields(Some(&pending), &profile(json!({ "kycStatus": "in-review" })), None).is_ok());
+        assert!(validate_reviewed_profile_fields(None, &profile(json!({ "kycStatus": "verified" })), None).is_err());
+    }
+
+    #[test]
+    fn test_owner_cannot_raise_investment_tier() {
+        let current = profile(json!({ "kycStatus": "verified", "annualIncome": 6_000_000.0 }));
+
+        for change in [
+            json!({ "kycStatus": "verified", "annualIncome": 6_000_000.0, "accredited": true }),
+            json!({ "kycStatus": "verified", "annualIncome": 6_000_000.0, "investorTier": "institutional" }),
+            json!({ "kycStatus": "verified", "annualIncome": 600_000_000.0 }),
+        ] {
+            let err = validate_reviewed_profile_fields(Some(&current), &profile(change), None).unwrap_err();
+            assert_eq!(ValidationError::parse(&err).unwrap().code, "REVIEWER_ONLY_FIELD");
+        }
+
+        assert!(validate_reviewed_profile_fields(None, &profile(json!({ "investorTier": "sophisticated" })), None).is_err());
+        assert!(validate_reviewed_profile_fields(None, &profile(json!({ "accredited": false })), None).is_ok());
+    }
+
+    #[test]
+    fn test_kyc_review_date_must_not_have_passed() {
+        let today = date("2026-10-17");
+        let due = |review: &str| profile(json!({ "kycStatus": "verified", "kycReviewDate": review }));
+
+        assert!(validate_investor_kyc(Some(&due("2026-10-17")), today).is_ok());
+        assert!(validate_investor_kyc(Some(&due("2026-10-16")), today).is_err());
+    }
 
     #[test]
     fn test_valid_member_number_format() {
@@ -1,14 +1,33 @@
 // Business Financing Module
 // This module contains all serverless functions for the business financing workflow
 
+pub mod models;
+pub mod dispatch;
+pub mod doc_index;
+pub mod validation_error;
 pub mod financial_data_validation;
 pub mod business_application_validation;
+pub mod application_status_machine;
+pub mod due_diligence;
 pub mod investment_opportunity_validation;
 pub mod document_validation;
 pub mod revenue_report_validation;
+pub mod revenue_report_workflow;
+pub mod revenue_report_history;
+pub mod profit_distribution;
+pub mod opportunity_funding;
+pub mod opportunity_amendments;
+pub mod concentration_limits;
+pub mod wallet_ledger;
+pub mod investment_lifecycle;
+pub mod funding_deadline;
+pub mod revenue_anomaly;
+pub mod notifications;
+pub mod reporting_schedule;
 pub mod kyc_compliance;
 pub mod access_control;
 pub mod admin_permissions;
+pub mod audit_log;
 pub mod islamic_contract_validation;
 pub mod platform_message_validation;
 pub mod member_validation;
@@ -0,0 +1,328 @@
+// Typed document models for the collections guarded by the satellite
+//
+// Required fields are plain types and must be present with the right JSON type;
+// optional fields are `Option<_>` or carry an explicit `#[serde(default)]`.
+// Unknown fields are ignored so the frontend can keep adding display-only data.
+
+use junobuild_satellite::Doc;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use super::validation_error::ValidationError;
+
+/// Decodes a document payload into its typed model
+/// Every malformed document is rejected with the same message shape
+pub fn decode_doc<T: DeserializeOwned>(collection: &str, data: &[u8]) -> Result<T, String> {
+    serde_json::from_slice(data).map_err(|e| {
+        ValidationError::new(
+            "MALFORMED_DOCUMENT",
+            format!("❌ Malformed {} document: {}", collection, e),
+        )
+        .param("collection", collection)
+        .param("detail", e.to_string())
+        .into()
+    })
+}
+
+/// Decodes an optional stored document
+pub fn decode_stored<T: DeserializeOwned>(collection: &str, doc: Option<&Doc>) -> Result<Option<T>, String> {
+    doc.map(|doc| decode_doc(collection, &doc.data)).transpose()
+}
+
+fn default_pending() -> String {
+    "pending".to_string()
+}
+
+fn default_quarterly() -> String {
+    "quarterly".to_string()
+}
+
+fn default_draft() -> String {
+    "draft".to_string()
+}
+
+fn default_active() -> String {
+    "active".to_string()
+}
+
+fn default_viewer() -> String {
+    "viewer".to_string()
+}
+
+fn default_unknown() -> String {
+    "unknown".to_string()
+}
+
+/// `business_applications`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BusinessApplication {
+    pub business_name: String,
+    pub contract_type: String,
+    #[serde(default = "default_pending")]
+    pub status: String,
+    pub business_email: Option<String>,
+    pub business_phone: Option<String>,
+    pub business_address: Option<String>,
+    pub industry: Option<String>,
+    pub requested_amount: Option<f64>,
+    pub years_in_operation: Option<i64>,
+    pub documents_submitted: Option<bool>,
+    pub due_diligence_checklist: Option<BTreeMap<String, ChecklistEntry>>,
+    pub due_diligence_score: Option<f64>,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<String>,
+    pub approved_by: Option<String>,
+    pub secondary_approver: Option<String>,
+    pub rejection_reason: Option<String>,
+    pub rejection_allows_resubmit: Option<bool>,
+    /// Set by the business when it resubmits a rejected application
+    pub is_resubmission: Option<bool>,
+    /// Terms locked after mutual agreement
+    pub final_contract_terms: Option<Value>,
+}
+
+/// One recorded item of `BusinessApplication::due_diligence_checklist`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistEntry {
+    #[serde(default = "default_unknown")]
+    pub status: String,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// `opportunities`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Opportunity {
+    pub application_id: String,
+    pub funding_goal: f64,
+    pub minimum_investment: f64,
+    pub contract_type: String,
+    pub expected_return_min: f64,
+    pub expected_return_max: f64,
+    pub term_months: i64,
+    #[serde(default = "default_quarterly")]
+    pub reporting_period: String,
+    #[serde(default = "default_active")]
+    pub status: String,
+    #[serde(default)]
+    pub current_funding: f64,
+    #[serde(default)]
+    pub investor_count: u32,
+    pub business_id: Option<String>,
+    pub business_name: Option<String>,
+    pub campaign_deadline: Option<String>,
+    /// Funding (₦) at which an under-funded opportunity still proceeds at its deadline
+    pub minimum_viable_funding: Option<f64>,
+    pub approved_by: Option<String>,
+}
+
+/// `opportunity_amendments`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpportunityAmendment {
+    pub opportunity_id: String,
+    /// New values of the amended terms only
+    pub terms: BTreeMap<String, Value>,
+    pub reason: String,
+    #[serde(default = "default_pending")]
+    pub status: String,
+    pub requested_by: String,
+    pub decided_by: Option<String>,
+    pub decided_at: Option<String>,
+}
+
+/// `investments`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Investment {
+    pub investor_id: String,
+    pub amount: f64,
+    pub opportunity_id: Option<String>,
+    pub status: Option<String>,
+}
+
+/// `wallets` (keyed by the owner's user id)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Wallet {
+    #[serde(default)]
+    pub available_balance: f64,
+    #[serde(default = "default_active")]
+    pub status: String,
+}
+
+/// `withdrawal_requests`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalRequest {
+    pub user_id: String,
+    pub amount: f64,
+    #[serde(default = "default_pending")]
+    pub status: String,
+}
+
+/// `revenue_reports`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevenueReport {
+    pub application_id: String,
+    pub reporting_period: String,
+    pub period_start: String,
+    pub period_end: String,
+    pub total_revenue: f64,
+    pub total_expenses: f64,
+    pub gross_profit: f64,
+    pub operating_expenses: f64,
+    pub net_profit: f64,
+    pub documents: Vec<Value>,
+    #[serde(default = "default_draft")]
+    pub status: String,
+    pub business_name: Option<String>,
+    pub contract_type: Option<String>,
+    pub murabaha_details: Option<Value>,
+    pub mudaraba_details: Option<Value>,
+    pub musharakah_details: Option<Value>,
+    pub ijara_details: Option<Value>,
+    pub salam_details: Option<Value>,
+    pub revenue_breakdown: Option<BTreeMap<String, Value>>,
+    pub expense_breakdown: Option<BTreeMap<String, Value>>,
+    /// Derived by the satellite (see revenue_anomaly.rs)
+    pub anomaly_score: Option<f64>,
+    pub reviewed_by: Option<String>,
+    pub review_notes: Option<String>,
+    pub revision_notes: Option<String>,
+}
+
+/// `admin_profiles`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminProfileDoc {
+    #[serde(default = "default_viewer")]
+    pub role: String,
+    #[serde(default)]
+    pub approval_limit: f64,
+    #[serde(default)]
+    pub is_active: bool,
+    pub user_id: Option<String>,
+}
+
+/// `platform_messages`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformMessage {
+    pub message_id: String,
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    pub content: String,
+    #[serde(rename = "type")]
+    pub message_type: String,
+    pub status: String,
+    pub sent_at: String,
+    #[serde(default)]
+    pub attachments: Vec<String>,
+    pub read_at: Option<String>,
+    pub responded_at: Option<String>,
+    pub response_content: Option<String>,
+}
+
+/// `individual_investor_profiles` and `corporate_investor_profiles`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvestorProfile {
+    pub investor_type: Option<String>,
+    #[serde(default = "default_pending")]
+    pub kyc_status: String,
+    #[serde(default)]
+    pub accredited: bool,
+    /// `retail`, `sophisticated` or `institutional` (drives investment limits)
+    pub investor_tier: Option<String>,
+    /// Declared net annual income (₦) - caps retail investors at 10% per 12 months
+    pub annual_income: Option<f64>,
+    pub member_number: Option<String>,
+    /// `active` or `suspended` (suspended members cannot invest)
+    pub account_status: Option<String>,
+    /// Date (YYYY-MM-DD) by which KYC must be re-reviewed
+    pub kyc_review_date: Option<String>,
+    // Individual identity (immutable once registered)
+    pub full_name: Option<String>,
+    pub nationality: Option<String>,
+    pub id_type: Option<String>,
+    pub id_number: Option<String>,
+    pub date_of_birth: Option<String>,
+    // Corporate identity (immutable once registered)
+    pub company_name: Option<String>,
+    pub registration_number: Option<String>,
+    pub legal_entity_type: Option<String>,
+    pub incorporation_date: Option<String>,
+    pub registration_country: Option<String>,
+    pub beneficial_owners: Option<Vec<BeneficialOwner>>,
+}
+
+/// Ultimate beneficial owner disclosed on a corporate profile
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BeneficialOwner {
+    pub ownership_percentage: Option<f64>,
+    pub full_name: Option<String>,
+    pub nationality: Option<String>,
+    pub id_type: Option<String>,
+    pub id_number: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn decode<T: DeserializeOwned>(value: Value) -> Result<T, String> {
+        decode_doc("test", &serde_json::to_vec(&value).unwrap())
+    }
+
+    #[test]
+    fn test_missing_required_field_is_rejected() {
+        let result: Result<Opportunity, _> = decode(json!({
+            "applicationId": "app_1",
+            "minimumInvestment": 10000,
+            "contractType": "musharaka",
+            "expectedReturnMin": 10,
+            "expectedReturnMax": 20,
+            "termMonths": 12
+        }));
+        let err = ValidationError::parse(&result.unwrap_err()).unwrap();
+        assert_eq!(err.code, "MALFORMED_DOCUMENT");
+        assert_eq!(err.params["collection"], "test");
+        assert!(err.message.starts_with("❌ Malformed test document"));
+        assert!(err.message.contains("fundingGoal"));
+    }
+
+    #[test]
+    fn test_wrong_type_is_rejected() {
+        let result: Result<Investment, _> = decode(json!({
+            "investorId": "abc",
+            "amount": "50000"
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_defaults_and_unknown_fields() {
+        let app: BusinessApplication = decode(json!({
+            "businessName": "Acme",
+            "contractType": "murabaha",
+            "someUiField": { "nested": true }
+        }))
+        .unwrap();
+        assert_eq!(app.status, "pending");
+        assert!(app.requested_amount.is_none());
+
+        let admin: AdminProfileDoc = decode(json!({})).unwrap();
+        assert_eq!(admin.role, "viewer");
+        assert!(!admin.is_active);
+    }
+}
@@ -0,0 +1,55 @@
+// Satellite-written notifications
+//
+// Timers and on_set_doc hooks notify users by writing `notifications` documents in the
+// same shape the frontend creates (`src/schemas/notification.schema.ts`).
+
+use junobuild_satellite::{SetDoc, id, set_doc_store};
+use junobuild_utils::encode_doc_data;
+use serde_json::{json, Value};
+use std::cell::Cell;
+
+pub const NOTIFICATIONS_COLLECTION: &str = "notifications";
+
+thread_local! {
+    /// Disambiguates notification keys written within the same IC time
+    static NOTIFICATION_SEQUENCE: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Writes one unread notification for `user_id`
+pub fn write_notification(
+    user_id: &str,
+    notification_type: &str,
+    priority: &str,
+    title: &str,
+    message: &str,
+    action_url: &str,
+    metadata: &Value,
+) -> Result<(), String> {
+    let sequence = NOTIFICATION_SEQUENCE.with(|sequence| {
+        let next = sequence.get() + 1;
+        sequence.set(next);
+        next
+    });
+
+    set_doc_store(
+        id(),
+        NOTIFICATIONS_COLLECTION.to_string(),
+        format!("notif_{}_{}_{}", user_id, ic_cdk::api::time(), sequence),
+        SetDoc {
+            data: encode_doc_data(&json!({
+                "userId": user_id,
+                "type": notification_type,
+                "title": title,
+                "message": message,
+                "read": false,
+                "priority": priority,
+                "actionUrl": action_url,
+                "metadata": metadata,
+            }))?,
+            description: None,
+            version: None,
+        },
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,398 @@
+// Opportunity term freeze and amendments
+//
+// Once an opportunity has an investment its terms (returns, term, contract type,
+// minimum investment) are frozen: direct edits are rejected. A change requires an
+// opportunity_amendments record raised by an admin and approved by a manager other
+// than the requester. The approving write itself applies the new terms to the
+// opportunity and notifies every investor.
+
+use junobuild_satellite::{SetDoc, get_doc_store, id, set_doc_store};
+use junobuild_utils::encode_doc_data;
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use super::admin_permissions::{has_sufficient_role, insufficient_role, log_admin_action};
+use super::audit_log::parse_doc_json;
+use super::dispatch::SetDocCall;
+use super::doc_index::list_indexed;
+use super::investment_opportunity_validation::{load_application, validate_application_link, validate_opportunity_terms};
+use super::models::{BusinessApplication, Investment, Opportunity, OpportunityAmendment, decode_doc};
+use super::notifications::write_notification;
+use super::opportunity_funding::is_committed;
+use super::validation_error::ValidationError;
+
+/// Collection holding amendment requests for frozen opportunity terms
+pub const OPPORTUNITY_AMENDMENTS_COLLECTION: &str = "opportunity_amendments";
+
+/// Opportunity fields investors committed to
+pub const FROZEN_TERMS: [&str; 5] = [
+    "expectedReturnMin",
+    "expectedReturnMax",
+    "termMonths",
+    "contractType",
+    "minimumInvestment",
+];
+
+/// Frozen terms that differ between two versions of an opportunity
+/// Numbers are compared by value, so 12 and 12.0 are the same term
+pub fn changed_terms(before: &Value, after: &Value) -> Vec<&'static str> {
+    FROZEN_TERMS
+        .into_iter()
+        .filter(|field| !same_term(before.get(*field), after.get(*field)))
+        .collect()
+}
+
+fn same_term(before: Option<&Value>, after: Option<&Value>) -> bool {
+    match (before.and_then(Value::as_f64), after.and_then(Value::as_f64)) {
+        (Some(before), Some(after)) => before == after,
+        _ => before == after,
+    }
+}
+
+/// Rejects direct edits of the terms of an opportunity that already has investments
+pub fn assert_terms_frozen(call: &SetDocCall<Opportunity>) -> Result<(), String> {
+    let Some(current) = call.current_json()? else {
+        return Ok(());
+    };
+
+    let changed = changed_terms(current, call.proposed_json()?);
+    if changed.is_empty() || !has_investments(call.key())? {
+        return Ok(());
+    }
+
+    Err(ValidationError::new(
+        "TERMS_FROZEN",
+        format!(
+            "❌ Terms of an opportunity with investments are frozen ({}). Raise an amendment for manager approval",
+            changed.join(", ")
+        ),
+    )
+    .field(changed[0])
+    .param("fields", changed)
+    .critical()
+    .into())
+}
+
+/// Amendments are raised by admins, decided once by a manager other than the requester
+pub fn assert_opportunity_amendment(call: &SetDocCall<OpportunityAmendment>) -> Result<(), String> {
+    let proposed = &call.proposed;
+    let admin_profile = call.caller_admin_profile()?;
+    let caller = call.caller();
+
+    let Some(current) = &call.current else {
+        if proposed.status != "pending" {
+            return Err(ValidationError::new(
+                "INVALID_STATUS",
+                "❌ New amendments must be 'pending' until a manager decides on them",
+            )
+            .field("status")
+            .param("actual", proposed.status.as_str())
+            .into());
+        }
+
+        if proposed.requested_by != caller {
+            return Err(ValidationError::new(
+                "CALLER_MISMATCH",
+                "❌ requestedBy must be the admin raising the amendment",
+            )
+            .field("requestedBy")
+            .critical()
+            .into());
+        }
+
+        if proposed.reason.trim().is_empty() {
+            return Err(ValidationError::new("MISSING_FIELD", "❌ An amendment must give its reason")
+                .field("reason")
+                .into());
+        }
+
+        let opportunity = match get_doc_store(id(), "opportunities".to_string(), proposed.opportunity_id.clone())? {
+            Some(doc) => parse_doc_json(&doc.data)?,
+            None => {
+                return Err(ValidationError::new(
+                    "OPPORTUNITY_NOT_FOUND",
+                    format!("❌ Opportunity '{}' does not exist", proposed.opportunity_id),
+                )
+                .field("opportunityId")
+                .into());
+            }
+        };
+        let application = load_application(opportunity["applicationId"].as_str().unwrap_or_default())?;
+        return validate_amended_terms(&opportunity, proposed, application.as_ref());
+    };
+
+    if current.status != "pending" {
+        return Err(ValidationError::new(
+            "AMENDMENT_DECIDED",
+            format!("❌ This amendment was already {} and can no longer change", current.status),
+        )
+        .param("status", current.status.as_str())
+        .critical()
+        .into());
+    }
+
+    if proposed.opportunity_id != current.opportunity_id
+        || proposed.terms != current.terms
+        || proposed.requested_by != current.requested_by
+    {
+        return Err(ValidationError::new(
+            "AMENDMENT_IMMUTABLE",
+            "❌ The opportunity, terms and requester of an amendment cannot change",
+        )
+        .critical()
+        .into());
+    }
+
+    if proposed.status == "pending" {
+        return Ok(());
+    }
+
+    if !["approved", "rejected"].contains(&proposed.status.as_str()) {
+        return Err(ValidationError::new(
+            "INVALID_STATUS",
+            format!("❌ Invalid amendment status: {}", proposed.status),
+        )
+        .field("status")
+        .param("actual", proposed.status.as_str())
+        .into());
+    }
+
+    if !has_sufficient_role(&admin_profile.role, "manager") {
+        return Err(insufficient_role(
+            format!(
+                "❌ Access Denied: Only managers can decide on opportunity amendments. Your role: {}",
+                admin_profile.role
+            ),
+            &admin_profile.role,
+            "manager",
+        )
+        .field("status")
+        .into());
+    }
+
+    if proposed.decided_by.as_deref() != Some(caller.as_str()) {
+        return Err(ValidationError::new(
+            "CALLER_MISMATCH",
+            "❌ decidedBy must be the manager recording the decision",
+        )
+        .field("decidedBy")
+        .critical()
+        .into());
+    }
+
+    if proposed.requested_by == caller {
+        return Err(ValidationError::new(
+            "SEPARATION_OF_DUTIES",
+            "❌ Separation of duties violation: An amendment cannot be approved by its requester",
+        )
+        .field("decidedBy")
+        .critical()
+        .into());
+    }
+
+    log_admin_action(
+        &caller,
+        &format!("{}_opportunity_amendment", if proposed.status == "approved" { "approve" } else { "reject" }),
+        call.collection(),
+        call.key(),
+        call.current_json()?,
+        Some(call.proposed_json()?),
+    )?;
+
+    if proposed.status == "approved" {
+        apply_opportunity_amendment(call)?;
+    }
+
+    Ok(())
+}
+
+/// Amendments may only touch frozen terms and must leave the opportunity valid and
+/// consistent with the application it was created from
+pub fn validate_amended_terms(
+    opportunity: &Value,
+    amendment: &OpportunityAmendment,
+    application: Option<&BusinessApplication>,
+) -> Result<(), String> {
+    if amendment.terms.is_empty() {
+        return Err(ValidationError::new("MISSING_FIELD", "❌ An amendment must change at least one term")
+            .field("terms")
+            .into());
+    }
+
+    if let Some(field) = amendment.terms.keys().find(|field| !FROZEN_TERMS.contains(&field.as_str())) {
+        return Err(ValidationError::new(
+            "INVALID_FIELD",
+            format!("❌ '{}' is not an amendable opportunity term", field),
+        )
+        .field("terms")
+        .param("actual", field.as_str())
+        .into());
+    }
+
+    let amended = amended_opportunity(opportunity, amendment);
+    let opportunity: Opportunity = decode_doc("opportunities", &serde_json::to_vec(&amended).map_err(|e| e.to_string())?)?;
+    validate_opportunity_terms(&opportunity)?;
+    validate_application_link(&opportunity, application)
+}
+
+/// Opportunity document with the amendment's terms applied
+pub fn amended_opportunity(opportunity: &Value, amendment: &OpportunityAmendment) -> Value {
+    let mut amended = opportunity.clone();
+    for (field, value) in &amendment.terms {
+        amended[field.as_str()] = value.clone();
+    }
+    amended
+}
+
+/// Applies an approved amendment to its opportunity and notifies the investors
+/// Runs in the approving write's assert, so the terms change exactly when the approval is stored
+fn apply_opportunity_amendment(call: &SetDocCall<OpportunityAmendment>) -> Result<(), String> {
+    let amendment = &call.proposed;
+    let Some(doc) = get_doc_store(id(), "opportunities".to_string(), amendment.opportunity_id.clone())? else {
+        return Err(ValidationError::new(
+            "OPPORTUNITY_NOT_FOUND",
+            format!("❌ Opportunity '{}' does not exist", amendment.opportunity_id),
+        )
+        .field("opportunityId")
+        .into());
+    };
+    let current = parse_doc_json(&doc.data)?;
+
+    // The opportunity may have changed since the amendment was raised
+    let application = load_application(current["applicationId"].as_str().unwrap_or_default())?;
+    validate_amended_terms(&current, amendment, application.as_ref())?;
+
+    let mut amended = amended_opportunity(&current, amendment);
+    amended["lastAmendmentId"] = Value::from(call.key());
+    amended["amendedAt"] = Value::from(amendment.decided_at.clone());
+
+    set_doc_store(
+        id(),
+        "opportunities".to_string(),
+        amendment.opportunity_id.clone(),
+        SetDoc {
+            data: encode_doc_data(&amended)?,
+            description: doc.description.clone(),
+            version: doc.version,
+        },
+    )?;
+
+    let changes: Vec<String> = changed_terms(&current, &amended)
+        .into_iter()
+        .map(|field| format!("{}: {} → {}", field, current[field], amended[field]))
+        .collect();
+    let business_name = current["businessName"].as_str().unwrap_or("An opportunity you invested in");
+
+    for investor_id in investor_ids(&amendment.opportunity_id)? {
+        write_notification(
+            &investor_id,
+            "opportunity_amended",
+            "high",
+            "Investment terms amended",
+            &format!(
+                "{} amended its terms ({}). Reason: {}",
+                business_name,
+                changes.join("; "),
+                amendment.reason
+            ),
+            "/member/portfolio",
+            &json!({
+                "opportunityId": amendment.opportunity_id,
+                "amendmentId": call.key(),
+            }),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn has_investments(opportunity_key: &str) -> Result<bool, String> {
+    Ok(!list_indexed::<Investment>("investments", "opportunity", opportunity_key)?.is_empty())
+}
+
+/// Investors currently holding an investment in the opportunity
+fn investor_ids(opportunity_key: &str) -> Result<BTreeSet<String>, String> {
+    Ok(list_indexed::<Investment>("investments", "opportunity", opportunity_key)?
+        .into_iter()
+        .map(|(_, _, investment)| investment)
+        .filter(is_committed)
+        .map(|investment| investment.investor_id)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opportunity() -> Value {
+        json!({
+            "applicationId": "app_1",
+            "fundingGoal": 1_000_000.0,
+            "minimumInvestment": 50_000.0,
+            "contractType": "musharaka",
+            "expectedReturnMin": 10.0,
+            "expectedReturnMax": 15.0,
+            "termMonths": 12,
+            "status": "active",
+            "currentFunding": 300_000.0,
+            "businessName": "Kano Agro"
+        })
+    }
+
+    fn amendment(terms: Value) -> OpportunityAmendment {
+        decode_doc(OPPORTUNITY_AMENDMENTS_COLLECTION, &serde_json::to_vec(&json!({
+            "opportunityId": "opp_1",
+            "terms": terms,
+            "reason": "Harvest delayed by a season",
+            "requestedBy": "admin_1"
+        })).unwrap()).unwrap()
+    }
+
+    fn application(contract_type: &str) -> BusinessApplication {
+        decode_doc("business_applications", &serde_json::to_vec(&json!({
+            "businessName": "Kano Agro",
+            "contractType": contract_type,
+            "status": "approved",
+            "requestedAmount": 1_000_000.0
+        })).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_only_frozen_terms_count_as_changes() {
+        let before = opportunity();
+        let mut after = before.clone();
+        after["termMonths"] = json!(18);
+        after["description"] = json!("Updated pitch");
+        after["currentFunding"] = json!(400_000.0);
+
+        assert_eq!(changed_terms(&before, &after), vec!["termMonths"]);
+        assert!(changed_terms(&before, &before).is_empty());
+
+        let mut reformatted = before.clone();
+        reformatted["termMonths"] = json!(12.0);
+        reformatted["expectedReturnMin"] = json!(10);
+        assert!(changed_terms(&before, &reformatted).is_empty());
+    }
+
+    #[test]
+    fn test_amendment_terms_are_validated_against_the_opportunity() {
+        let opp = opportunity();
+        let app = application("musharakah");
+        let validate = |terms: Value| validate_amended_terms(&opp, &amendment(terms), Some(&app));
+
+        assert!(validate(json!({ "termMonths": 18 })).is_ok());
+        assert!(validate(json!({})).is_err());
+        assert!(validate(json!({ "fundingGoal": 2_000_000.0 })).is_err());
+        // Minimum above maximum return
+        assert!(validate(json!({ "expectedReturnMin": 20.0 })).is_err());
+    }
+
+    #[test]
+    fn test_amendment_keeps_the_application_contract() {
+        let opp = opportunity();
+        let app = application("musharakah");
+
+        let err = validate_amended_terms(&opp, &amendment(json!({ "contractType": "murabaha" })), Some(&app)).unwrap_err();
+        assert_eq!(ValidationError::parse(&err).unwrap().code, "CONTRACT_TYPE_MISMATCH");
+        assert!(validate_amended_terms(&opp, &amendment(json!({ "termMonths": 18 })), None).is_err());
+    }
+}
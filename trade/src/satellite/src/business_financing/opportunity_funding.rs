@@ -0,0 +1,372 @@
+// Server-computed opportunity funding
+//
+// currentFunding and investorCount on opportunities are derived from the committed
+// investments documents: every investment write makes the satellite recount the
+// opportunity's totals, and reaching the goal moves it from active to funded (which
+// also starts its reporting calendar). Clients can no longer write these fields, and
+// may only create active opportunities and cancel active ones.
+
+use chrono::NaiveDate;
+use junobuild_satellite::{OnSetDocContext, SetDoc, get_doc_store, id, set_doc_store};
+use junobuild_utils::encode_doc_data;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use super::audit_log::parse_doc_json;
+use super::dispatch::SetDocCall;
+use super::doc_index::list_indexed;
+use super::funding_deadline::parse_campaign_deadline;
+use super::investment_lifecycle::InvestmentStatus;
+use super::models::{Investment, Opportunity, decode_doc, decode_stored};
+use super::reporting_schedule::create_schedule;
+use super::revenue_report_validation::ic_today;
+use super::validation_error::ValidationError;
+
+/// Whether the investment's amount counts towards its opportunity's funding
+/// Follows `InvestmentStatus`, so investments stored without a status count as active
+pub fn is_committed(investment: &Investment) -> bool {
+    InvestmentStatus::parse(investment.status.as_deref()).is_ok_and(|status| status.is_committed())
+}
+
+/// Funding totals of one opportunity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FundingTotals {
+    pub current_funding: f64,
+    pub investor_count: u32,
+}
+
+/// Sums the committed investments of `opportunity_key` (investors are counted once)
+pub fn funding_totals(opportunity_key: &str, investments: &[Investment]) -> FundingTotals {
+    let committed: Vec<&Investment> = investments
+        .iter()
+        .filter(|investment| investment.opportunity_id.as_deref() == Some(opportunity_key))
+        .filter(|investment| is_committed(investment))
+        .collect();
+
+    let investors: BTreeSet<&str> = committed.iter().map(|investment| investment.investor_id.as_str()).collect();
+
+    FundingTotals {
+        current_funding: committed.iter().map(|investment| investment.amount).sum(),
+        investor_count: investors.len() as u32,
+    }
+}
+
+/// Status after applying `totals` - an active opportunity that reaches its goal is funded
+pub fn funded_status(opportunity: &Opportunity, totals: &FundingTotals) -> String {
+    if opportunity.status == "active" && totals.current_funding >= opportunity.funding_goal {
+        return "funded".to_string();
+    }
+    opportunity.status.clone()
+}
+
+/// Checks that `opportunity` still accepts an investment of `amount` on `today`
+/// `committed_funding` is the sum of its committed investments, not the stored currentFunding
+/// (which on_set_doc recounts only after the write, so it lags behind concurrent investments)
+pub fn validate_opportunity_open(
+    opportunity: &Opportunity,
+    committed_funding: f64,
+    amount: f64,
+    today: NaiveDate,
+) -> Result<(), String> {
+    if opportunity.status != "active" {
+        return Err(ValidationError::new(
+            "OPPORTUNITY_NOT_OPEN",
+            format!("❌ This opportunity is {} and no longer accepts investments", opportunity.status),
+        )
+        .field("opportunityId")
+        .param("status", opportunity.status.as_str())
+        .into());
+    }
+
+    if let Some(deadline) = opportunity.campaign_deadline.as_deref() {
+        if parse_campaign_deadline(deadline)? < today {
+            return Err(ValidationError::new(
+                "OPPORTUNITY_NOT_OPEN",
+                format!("❌ The funding campaign of this opportunity closed on {}", deadline),
+            )
+            .field("opportunityId")
+            .param("campaignDeadline", deadline)
+            .into());
+        }
+    }
+
+    if amount < opportunity.minimum_investment {
+        return Err(ValidationError::new(
+            "INVALID_AMOUNT",
+            format!("❌ The minimum investment for this opportunity is ₦{:.2}", opportunity.minimum_investment),
+        )
+        .field("amount")
+        .param("min", opportunity.minimum_investment)
+        .param("actual", amount)
+        .into());
+    }
+
+    let remaining = (opportunity.funding_goal - committed_funding).max(0.0);
+    if amount > remaining {
+        return Err(ValidationError::new(
+            "INVALID_AMOUNT",
+            format!("❌ Only ₦{:.2} of this opportunity's funding goal remains", remaining),
+        )
+        .field("amount")
+        .param("max", remaining)
+        .param("actual", amount)
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Rejects new investments into opportunities that are not open for this amount
+pub fn assert_investment_opportunity_open(call: &SetDocCall<Investment>) -> Result<(), String> {
+    if call.current.is_some() {
+        return Ok(());
+    }
+    let investment = &call.proposed;
+    let opportunity_id = investment.opportunity_id.as_deref().unwrap_or_default();
+
+    let Some(doc) = get_doc_store(id(), "opportunities".to_string(), opportunity_id.to_string())? else {
+        return Err(ValidationError::new(
+            "OPPORTUNITY_NOT_FOUND",
+            format!("❌ Opportunity '{}' does not exist", opportunity_id),
+        )
+        .field("opportunityId")
+        .into());
+    };
+    let opportunity: Opportunity = decode_doc("opportunities", &doc.data)?;
+
+    let investments: Vec<Investment> = list_indexed::<Investment>("investments", "opportunity", opportunity_id)?
+        .into_iter()
+        .map(|(_, _, investment)| investment)
+        .collect();
+    let committed = funding_totals(opportunity_id, &investments).current_funding;
+
+    validate_opportunity_open(&opportunity, committed, investment.amount, ic_today())
+}
+
+/// Whether a client may move an opportunity from `from` (None when creating it) to `to`
+/// Clients create active opportunities and cancel active ones (refunded in on_set_doc);
+/// funded, expired and every other move are the satellite's
+pub fn is_client_status_transition(from: Option<&str>, to: &str) -> bool {
+    match from {
+        None => to == "active",
+        Some(from) if from == to => true,
+        Some(from) => from == "active" && to == "cancelled",
+    }
+}
+
+/// Rejects client writes to the satellite-maintained funding fields and status
+pub fn assert_funding_fields_untouched(call: &SetDocCall<Opportunity>) -> Result<(), String> {
+    if call.is_satellite() {
+        return Ok(());
+    }
+
+    let proposed = &call.proposed;
+    let (funding, investors) = match &call.current {
+        Some(current) => (current.current_funding, current.investor_count),
+        None => (0.0, 0),
+    };
+
+    let tampered = if proposed.current_funding != funding {
+        Some("currentFunding")
+    } else if proposed.investor_count != investors {
+        Some("investorCount")
+    } else {
+        None
+    };
+
+    if let Some(field) = tampered {
+        return Err(ValidationError::new(
+            "DERIVED_FIELD",
+            format!(
+                "❌ {} is maintained by the satellite from committed investments and cannot be set by clients",
+                field
+            ),
+        )
+        .field(field)
+        .critical()
+        .into());
+    }
+
+    let from = call.current.as_ref().map(|current| current.status.as_str());
+    if !is_client_status_transition(from, &proposed.status) {
+        return Err(ValidationError::new(
+            "INVALID_STATUS_TRANSITION",
+            format!(
+                "❌ Invalid status transition: '{}' → '{}' is not allowed",
+                from.unwrap_or("new"),
+                proposed.status
+            ),
+        )
+        .field("status")
+        .param("from", from.unwrap_or("new"))
+        .param("to", proposed.status.as_str())
+        .critical()
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Recounts the funding of the opportunity an investment belongs to
+pub fn sync_opportunity_funding(context: &OnSetDocContext) -> Result<(), String> {
+    if context.data.collection != "investments" {
+        return Ok(());
+    }
+
+    let collection = &context.data.collection;
+    let after: Investment = decode_doc(collection, &context.data.data.after.data)?;
+    let before: Option<Investment> = decode_stored(collection, context.data.data.before.as_ref())?;
+
+    // An investment moved to another opportunity changes both totals
+    let mut opportunity_keys: BTreeSet<String> = after.opportunity_id.into_iter().collect();
+    opportunity_keys.extend(before.and_then(|before| before.opportunity_id));
+
+    if opportunity_keys.is_empty() {
+        return Ok(());
+    }
+
+    for opportunity_key in opportunity_keys {
+        let investments: Vec<Investment> = list_indexed::<Investment>("investments", "opportunity", &opportunity_key)?
+            .into_iter()
+            .map(|(_, _, investment)| investment)
+            .collect();
+        refresh_opportunity_funding(&opportunity_key, &investments)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the recomputed totals (and the funded transition) onto the opportunity
+pub fn refresh_opportunity_funding(opportunity_key: &str, investments: &[Investment]) -> Result<(), String> {
+    let Some(doc) = get_doc_store(id(), "opportunities".to_string(), opportunity_key.to_string())? else {
+        return Ok(());
+    };
+    let opportunity: Opportunity = decode_doc("opportunities", &doc.data)?;
+
+    let totals = funding_totals(opportunity_key, investments);
+    let status = funded_status(&opportunity, &totals);
+
+    if opportunity.current_funding == totals.current_funding
+        && opportunity.investor_count == totals.investor_count
+        && opportunity.status == status
+    {
+        return Ok(());
+    }
+
+    // Patch the raw document so fields outside the typed model are preserved
+    let mut data = parse_doc_json(&doc.data)?;
+    data["currentFunding"] = Value::from(totals.current_funding);
+    data["investorCount"] = Value::from(totals.investor_count);
+    data["status"] = Value::from(status.as_str());
+
+    set_doc_store(
+        id(),
+        "opportunities".to_string(),
+        opportunity_key.to_string(),
+        SetDoc {
+            data: encode_doc_data(&data)?,
+            description: doc.description.clone(),
+            version: doc.version,
+        },
+    )?;
+
+    // Satellite writes do not run on_set_doc, so start the reporting calendar here
+    if status == "funded" && opportunity.status != "funded" {
+        create_schedule(opportunity_key, &opportunity, ic_cdk::api::time())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn investment(investor: &str, opportunity: &str, amount: f64, status: &str) -> Investment {
+        decode_doc("investments", &serde_json::to_vec(&json!({
+            "investorId": investor,
+            "opportunityId": opportunity,
+            "amount": amount,
+            "status": status
+        })).unwrap()).unwrap()
+    }
+
+    fn opportunity(status: &str) -> Opportunity {
+        decode_doc("opportunities", &serde_json::to_vec(&json!({
+            "applicationId": "app_1",
+            "fundingGoal": 1_000_000.0,
+            "minimumInvestment": 50_000.0,
+            "contractType": "musharaka",
+            "expectedReturnMin": 10.0,
+            "expectedReturnMax": 15.0,
+            "termMonths": 12,
+            "status": status
+        })).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_totals_count_committed_investments_once_per_investor() {
+        let investments = [
+            investment("alice", "opp_1", 400_000.0, "active"),
+            investment("alice", "opp_1", 100_000.0, "active"),
+            investment("bob", "opp_1", 250_000.0, "completed"),
+            investment("carol", "opp_1", 900_000.0, "cancelled"),
+            investment("dave", "opp_2", 300_000.0, "active"),
+        ];
+
+        assert_eq!(
+            funding_totals("opp_1", &investments),
+            FundingTotals { current_funding: 750_000.0, investor_count: 2 }
+        );
+    }
+
+    #[test]
+    fn test_investments_only_into_open_opportunities() {
+        let today = NaiveDate::from_ymd_opt(2026, 9, 30).unwrap();
+        let mut open = opportunity("active");
+        open.current_funding = 900_000.0;
+        open.campaign_deadline = Some("30-09-2026".to_string());
+
+        assert!(validate_opportunity_open(&open, 900_000.0, 100_000.0, today).is_ok());
+        // Below the minimum, or more than the 100,000 still needed
+        assert!(validate_opportunity_open(&open, 900_000.0, 40_000.0, today).is_err());
+        assert!(validate_opportunity_open(&open, 900_000.0, 100_001.0, today).is_err());
+        // The campaign closes after its deadline day
+        assert!(validate_opportunity_open(&open, 900_000.0, 100_000.0, today.succ_opt().unwrap()).is_err());
+        assert!(validate_opportunity_open(&opportunity("funded"), 0.0, 50_000.0, today).is_err());
+    }
+
+    #[test]
+    fn test_remaining_goal_comes_from_committed_investments() {
+        let today = NaiveDate::from_ymd_opt(2026, 9, 30).unwrap();
+        // The stored total has not caught up with an investment committed since
+        let mut open = opportunity("active");
+        open.current_funding = 500_000.0;
+
+        assert!(validate_opportunity_open(&open, 950_000.0, 100_000.0, today).is_err());
+        assert!(validate_opportunity_open(&open, 900_000.0, 100_000.0, today).is_ok());
+    }
+
+    #[test]
+    fn test_clients_only_create_and_cancel_active_opportunities() {
+        assert!(is_client_status_transition(None, "active"));
+        assert!(is_client_status_transition(Some("active"), "cancelled"));
+        assert!(is_client_status_transition(Some("funded"), "funded"));
+
+        assert!(!is_client_status_transition(None, "funded"));
+        assert!(!is_client_status_transition(Some("funded"), "active"));
+        assert!(!is_client_status_transition(Some("funded"), "cancelled"));
+        assert!(!is_client_status_transition(Some("cancelled"), "active"));
+        assert!(!is_client_status_transition(Some("active"), "expired"));
+    }
+
+    #[test]
+    fn test_reaching_goal_moves_active_to_funded() {
+        let reached = FundingTotals { current_funding: 1_000_000.0, investor_count: 4 };
+        let short = FundingTotals { current_funding: 999_999.0, investor_count: 4 };
+
+        assert_eq!(funded_status(&opportunity("active"), &reached), "funded");
+        assert_eq!(funded_status(&opportunity("active"), &short), "active");
+        assert_eq!(funded_status(&opportunity("cancelled"), &reached), "cancelled");
+    }
+}
@@ -1,146 +1,155 @@
-use junobuild_satellite::AssertSetDocContext;
-use serde_json::Value;
+use super::dispatch::SetDocCall;
+use super::models::PlatformMessage;
+use super::validation_error::ValidationError;
 
 /// Validate platform messages before storing
 /// Ensures message integrity, proper formatting, and prevents abuse
-pub fn validate_platform_message(context: &AssertSetDocContext) -> Result<(), String> {
-    // Check if this is a platform_messages collection operation
-    let collection = &context.data.collection;
-    
-    if collection != "platform_messages" {
-        return Ok(());
-    }
-
-    // Parse message data from the context
-    let data_bytes = &context.data.data.proposed.data;
-    let data: Value = serde_json::from_slice(data_bytes)
-        .map_err(|e| format!("Failed to parse message data: {}", e))?;
-    
-    // Extract fields from the data map
-    let message_id = data.get("messageId")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing messageId")?;
-    
-    let from = data.get("from")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing from field")?;
-    
-    let to = data.get("to")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing to field")?;
-    
-    let subject = data.get("subject")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing subject")?;
-    
-    let content = data.get("content")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing content")?;
-    
-    let msg_type = data.get("type")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing type field")?;
-    
-    let status = data.get("status")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing status")?;
+pub fn validate_platform_message(call: &SetDocCall<PlatformMessage>) -> Result<(), String> {
+    let message = &call.proposed;
+    let message_id = message.message_id.as_str();
+    let from = message.from.as_str();
+    let to = message.to.as_str();
+    let subject = message.subject.as_str();
+    let content = message.content.as_str();
+    let msg_type = message.message_type.as_str();
+    let status = message.status.as_str();
 
     // Validate message ID format
     if !message_id.starts_with("msg_") {
-        return Err("Invalid messageId format. Must start with 'msg_'".to_string());
+        return Err(ValidationError::new("INVALID_FORMAT", "❌ Invalid messageId format. Must start with 'msg_'")
+            .field("messageId")
+            .param("actual", message_id)
+            .into());
     }
 
     // Validate from/to fields are not empty
     if from.trim().is_empty() {
-        return Err("From field cannot be empty".to_string());
+        return Err(ValidationError::new("MISSING_FIELD", "❌ From field cannot be empty")
+            .field("from")
+            .into());
     }
     
     if to.trim().is_empty() {
-        return Err("To field cannot be empty".to_string());
+        return Err(ValidationError::new("MISSING_FIELD", "❌ To field cannot be empty")
+            .field("to")
+            .into());
     }
 
     // Validate subject length (3-200 characters)
-    if subject.trim().len() < 3 {
-        return Err("Subject must be at least 3 characters".to_string());
-    }
-    
-    if subject.len() > 200 {
-        return Err("Subject must not exceed 200 characters".to_string());
+    if subject.trim().len() < 3 || subject.len() > 200 {
+        return Err(ValidationError::new("INVALID_LENGTH", "❌ Subject must be between 3 and 200 characters")
+            .field("subject")
+            .param("min", 3)
+            .param("max", 200)
+            .param("actual", subject.len())
+            .into());
     }
 
     // Validate content length (10-10000 characters)
-    if content.trim().len() < 10 {
-        return Err("Message content must be at least 10 characters".to_string());
-    }
-    
-    if content.len() > 10000 {
-        return Err("Message content must not exceed 10000 characters".to_string());
+    if content.trim().len() < 10 || content.len() > 10000 {
+        return Err(ValidationError::new(
+            "INVALID_LENGTH",
+            "❌ Message content must be between 10 and 10000 characters",
+        )
+        .field("content")
+        .param("min", 10)
+        .param("max", 10000)
+        .param("actual", content.len())
+        .into());
     }
 
     // Validate message type
-    match msg_type {
-        "info" | "request" | "warning" | "urgent" => {},
-        _ => return Err(format!("Invalid message type: {}. Must be one of: info, request, warning, urgent", msg_type)),
+    if !["info", "request", "warning", "urgent"].contains(&msg_type) {
+        return Err(ValidationError::new(
+            "INVALID_FIELD",
+            format!("❌ Invalid message type: {}. Must be one of: info, request, warning, urgent", msg_type),
+        )
+        .field("type")
+        .param("actual", msg_type)
+        .into());
     }
 
     // Validate status
-    match status {
-        "sent" | "read" | "responded" => {},
-        _ => return Err(format!("Invalid status: {}. Must be one of: sent, read, responded", status)),
+    if !["sent", "read", "responded"].contains(&status) {
+        return Err(ValidationError::new(
+            "INVALID_STATUS",
+            format!("❌ Invalid status: {}. Must be one of: sent, read, responded", status),
+        )
+        .field("status")
+        .param("actual", status)
+        .into());
     }
 
     // Validate attachments if present
-    if let Some(attachments_value) = data.get("attachments") {
-        if let Some(attachments_array) = attachments_value.as_array() {
-            if attachments_array.len() > 5 {
-                return Err("Cannot attach more than 5 files per message".to_string());
-            }
-            
-            // Validate each attachment URL
-            for attachment in attachments_array {
-                if let Some(url) = attachment.as_str() {
-                    if !url.starts_with("http://") && !url.starts_with("https://") {
-                        return Err("Invalid attachment URL format".to_string());
-                    }
-                } else {
-                    return Err("Attachment must be a valid URL string".to_string());
-                }
-            }
-        }
+    if message.attachments.len() > 5 {
+        return Err(ValidationError::new("TOO_MANY_ATTACHMENTS", "❌ Cannot attach more than 5 files per message")
+            .field("attachments")
+            .param("max", 5)
+            .param("actual", message.attachments.len())
+            .into());
+    }
+    
+    // Validate each attachment URL
+    if let Some(url) = message
+        .attachments
+        .iter()
+        .find(|url| !url.starts_with("http://") && !url.starts_with("https://"))
+    {
+        return Err(ValidationError::new("INVALID_FORMAT", "❌ Invalid attachment URL format")
+            .field("attachments")
+            .param("actual", url.as_str())
+            .into());
     }
 
     // Validate response content length if present (when status is "responded")
     if status == "responded" {
-        if let Some(response_content) = data.get("responseContent").and_then(|v| v.as_str()) {
-            if response_content.trim().len() < 10 {
-                return Err("Response content must be at least 10 characters".to_string());
-            }
-            
-            if response_content.len() > 10000 {
-                return Err("Response content must not exceed 10000 characters".to_string());
-            }
-        } else {
-            return Err("Response content is required when status is 'responded'".to_string());
+        let Some(response_content) = message.response_content.as_deref() else {
+            return Err(ValidationError::new(
+                "MISSING_FIELD",
+                "❌ Response content is required when status is 'responded'",
+            )
+            .field("responseContent")
+            .into());
+        };
+
+        if response_content.trim().len() < 10 || response_content.len() > 10000 {
+            return Err(ValidationError::new(
+                "INVALID_LENGTH",
+                "❌ Response content must be between 10 and 10000 characters",
+            )
+            .field("responseContent")
+            .param("min", 10)
+            .param("max", 10000)
+            .param("actual", response_content.len())
+            .into());
         }
     }
 
-    // Validate sentAt timestamp is present
-    if data.get("sentAt").is_none() {
-        return Err("sentAt timestamp is required".to_string());
+    // sentAt is required by the PlatformMessage model
+    if message.sent_at.trim().is_empty() {
+        return Err(ValidationError::new("MISSING_FIELD", "❌ sentAt timestamp is required")
+            .field("sentAt")
+            .into());
     }
 
     // If status is "read", readAt must be present
-    if status == "read" || status == "responded" {
-        if data.get("readAt").is_none() {
-            return Err("readAt timestamp is required when status is 'read' or 'responded'".to_string());
-        }
+    if (status == "read" || status == "responded") && message.read_at.is_none() {
+        return Err(ValidationError::new(
+            "MISSING_FIELD",
+            "❌ readAt timestamp is required when status is 'read' or 'responded'",
+        )
+        .field("readAt")
+        .into());
     }
 
     // If status is "responded", respondedAt must be present
-    if status == "responded" {
-        if data.get("respondedAt").is_none() {
-            return Err("respondedAt timestamp is required when status is 'responded'".to_string());
-        }
+    if status == "responded" && message.responded_at.is_none() {
+        return Err(ValidationError::new(
+            "MISSING_FIELD",
+            "❌ respondedAt timestamp is required when status is 'responded'",
+        )
+        .field("respondedAt")
+        .into());
     }
 
     Ok(())
@@ -148,6 +157,7 @@ pub fn validate_platform_message(context: &AssertSetDocContext) -> Result<(), St
 
 /// Check rate limiting for message creation
 /// Prevents spam by limiting messages per business per time period
+#[allow(dead_code)]
 pub fn check_message_rate_limit(from: &str, _existing_messages_count: usize) -> Result<(), String> {
     // Simple validation: platform can send unlimited, but businesses are tracked
     if from == "platform" {
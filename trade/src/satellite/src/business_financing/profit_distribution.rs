@@ -0,0 +1,221 @@
+// Draft profit distributions from approved revenue reports
+//
+// When a revenue report is approved the satellite writes a draft profit_distributions
+// document (key `dist_{reportKey}`) for the application's opportunity. The investors'
+// share of the reported net profit follows the application's agreed (final) contract
+// ratios, so managers only review and confirm the draft from the profit distribution page.
+
+use junobuild_satellite::{OnSetDocContext, SetDoc, get_doc_store, id, set_doc_store};
+use junobuild_utils::encode_doc_data;
+use serde_json::{json, Value};
+use super::doc_index::list_indexed;
+use super::models::{BusinessApplication, Opportunity, RevenueReport, decode_doc};
+
+/// Collection holding profit distributions (drafts and processed ones)
+pub const PROFIT_DISTRIBUTIONS_COLLECTION: &str = "profit_distributions";
+
+/// Opportunity statuses that can receive a distribution
+const DISTRIBUTING_STATUSES: [&str; 3] = ["funded", "active", "completed"];
+
+/// Investors' profit share (percent) from the application's agreed contract terms
+/// Mudarabah terms carry it as `investorProfitShare`. Musharakah terms only name two
+/// partners: the investors' side is the partner that is not the business (matched on
+/// `party{n}Id`), and terms without party ids follow the contract form, which fills in
+/// Partner 1 as the institution (components/contracts/musharakah-fields.tsx)
+pub fn investor_profit_share(terms: &Value, business_id: &str) -> Option<f64> {
+    let share = terms.get("investorProfitShare").and_then(Value::as_f64).or_else(|| {
+        let business_is_party1 = terms.get("party1Id").and_then(Value::as_str) == Some(business_id);
+        let institution = if business_is_party1 { "party2ProfitShare" } else { "party1ProfitShare" };
+        terms.get(institution).and_then(Value::as_f64)
+    });
+
+    share.filter(|share| (0.0..=100.0).contains(share))
+}
+
+/// Draft distribution for an approved report
+pub fn build_distribution_draft(
+    report_key: &str,
+    report: &RevenueReport,
+    opportunity_key: &str,
+    opportunity: &Opportunity,
+    investor_share: f64,
+    now_millis: u64,
+) -> Value {
+    let investor_profit = round_kobo(report.net_profit * investor_share / 100.0);
+    let business_profit = round_kobo(report.net_profit - investor_profit);
+
+    json!({
+        "opportunityId": opportunity_key,
+        "applicationId": report.application_id,
+        "revenueReportId": report_key,
+        "businessName": opportunity.business_name.clone().or_else(|| report.business_name.clone()).unwrap_or_default(),
+        "contractType": opportunity.contract_type,
+        "distributionPeriod": format!("{} to {}", report.period_start, report.period_end),
+        "periodStart": report.period_start,
+        "periodEnd": report.period_end,
+        "reportedNetProfit": report.net_profit,
+        "investorSharePercentage": investor_share,
+        "businessSharePercentage": 100.0 - investor_share,
+        "businessShareAmount": business_profit,
+        // Amount split between the opportunity's investors when the draft is confirmed
+        "totalProfitAmount": investor_profit,
+        "investorCount": opportunity.investor_count,
+        "totalInvestedAmount": opportunity.current_funding,
+        "distributionDate": now_millis,
+        "status": "draft",
+        "createdBy": "satellite",
+        "createdAt": now_millis,
+        "notes": format!(
+            "Generated from approved revenue report {} ({}% of ₦{:.2} net profit to investors)",
+            report_key, investor_share, report.net_profit
+        ),
+    })
+}
+
+fn round_kobo(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+/// Generates the draft distribution when a revenue report becomes approved
+pub fn draft_profit_distribution(context: &OnSetDocContext) -> Result<(), String> {
+    if context.data.collection != "revenue_reports" {
+        return Ok(());
+    }
+
+    let report: RevenueReport = decode_doc(&context.data.collection, &context.data.data.after.data)?;
+    let was_approved = match &context.data.data.before {
+        Some(doc) => decode_doc::<RevenueReport>(&context.data.collection, &doc.data)?.status == "approved",
+        None => false,
+    };
+    if report.status != "approved" || was_approved {
+        return Ok(());
+    }
+
+    // Losses are not distributed
+    if report.net_profit <= 0.0 {
+        return Ok(());
+    }
+
+    let report_key = &context.data.key;
+    let distribution_key = format!("dist_{}", report_key);
+    if get_doc_store(id(), PROFIT_DISTRIBUTIONS_COLLECTION.to_string(), distribution_key.clone())?.is_some() {
+        return Ok(());
+    }
+
+    let opportunities = list_indexed::<Opportunity>("opportunities", "application", &report.application_id)?;
+    let Some((opportunity_key, _, opportunity)) = opportunities
+        .iter()
+        .find(|(_, _, opportunity)| DISTRIBUTING_STATUSES.contains(&opportunity.status.as_str()))
+    else {
+        ic_cdk::println!("No funded opportunity for application {} - no draft distribution", report.application_id);
+        return Ok(());
+    };
+
+    // Only the terms both sides agreed count - the report's own contract details are
+    // written by the business and proposed terms are not binding
+    let Some(application_doc) = get_doc_store(id(), "business_applications".to_string(), report.application_id.clone())? else {
+        ic_cdk::println!("Application {} not found - no draft distribution", report.application_id);
+        return Ok(());
+    };
+    let application: BusinessApplication = decode_doc("business_applications", &application_doc.data)?;
+    let share = application
+        .final_contract_terms
+        .as_ref()
+        .and_then(|terms| investor_profit_share(terms, &application_doc.owner.to_text()));
+    let Some(investor_share) = share else {
+        ic_cdk::println!("No agreed profit ratio for application {} - no draft distribution", report.application_id);
+        return Ok(());
+    };
+
+    let draft = build_distribution_draft(
+        report_key,
+        &report,
+        opportunity_key,
+        opportunity,
+        investor_share,
+        ic_cdk::api::time() / 1_000_000,
+    );
+
+    set_doc_store(
+        id(),
+        PROFIT_DISTRIBUTIONS_COLLECTION.to_string(),
+        distribution_key,
+        SetDoc {
+            data: encode_doc_data(&draft)?,
+            description: None,
+            version: None,
+        },
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode<T: serde::de::DeserializeOwned>(collection: &str, value: Value) -> T {
+        decode_doc(collection, &serde_json::to_vec(&value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_ratio_from_agreed_terms() {
+        let mudarabah = json!({ "investorProfitShare": 70.0, "mudaribProfitShare": 30.0 });
+        let musharakah = json!({ "party1ProfitShare": 55.0, "party2ProfitShare": 45.0 });
+
+        assert_eq!(investor_profit_share(&mudarabah, "business"), Some(70.0));
+        assert_eq!(investor_profit_share(&musharakah, "business"), Some(55.0));
+        assert_eq!(investor_profit_share(&json!({ "profitRate": 12.0 }), "business"), None);
+        assert_eq!(investor_profit_share(&json!({ "investorProfitShare": 120.0 }), "business"), None);
+    }
+
+    #[test]
+    fn test_musharakah_investors_are_the_partner_that_is_not_the_business() {
+        let business_first = json!({
+            "party1Id": "business",
+            "party2Id": "institution",
+            "party1ProfitShare": 55.0,
+            "party2ProfitShare": 45.0
+        });
+
+        assert_eq!(investor_profit_share(&business_first, "business"), Some(45.0));
+    }
+
+    #[test]
+    fn test_draft_splits_reported_profit() {
+        let report: RevenueReport = decode("revenue_reports", json!({
+            "applicationId": "app_1",
+            "reportingPeriod": "quarterly",
+            "periodStart": "2026-01-01",
+            "periodEnd": "2026-03-31",
+            "totalRevenue": 5_000_000.0,
+            "totalExpenses": 3_999_999.99,
+            "grossProfit": 1_500_000.0,
+            "operatingExpenses": 500_000.0,
+            "netProfit": 1_000_000.01,
+            "documents": [],
+            "status": "approved"
+        }));
+        let opportunity: Opportunity = decode("opportunities", json!({
+            "applicationId": "app_1",
+            "fundingGoal": 10_000_000.0,
+            "minimumInvestment": 50_000.0,
+            "contractType": "mudarabah",
+            "expectedReturnMin": 10.0,
+            "expectedReturnMax": 15.0,
+            "termMonths": 12,
+            "status": "funded",
+            "currentFunding": 10_000_000.0,
+            "investorCount": 42,
+            "businessName": "Kano Agro"
+        }));
+
+        let draft = build_distribution_draft("report_1", &report, "opp_1", &opportunity, 60.0, 1_000);
+
+        assert_eq!(draft["status"], "draft");
+        assert_eq!(draft["totalProfitAmount"], 600_000.01);
+        assert_eq!(draft["businessShareAmount"], 400_000.0);
+        assert_eq!(draft["investorCount"], 42);
+        assert_eq!(draft["revenueReportId"], "report_1");
+    }
+}
@@ -0,0 +1,570 @@
+// Revenue reporting schedule and overdue tracking
+//
+// When an opportunity is funded the satellite writes the application's expected
+// reporting calendar (one entry per calendar month/quarter of the financing term) to
+// reporting_schedules. Submitted revenue reports tick their period off, and a canister
+// timer sweeps every schedule to mark missed periods overdue, escalate them after the
+// configured grace periods and notify the business and the assigned admin.
+
+use chrono::{Datelike, Days, Months, NaiveDate};
+use ic_cdk_timers::{set_timer, set_timer_interval};
+use junobuild_satellite::{
+    AssertSetDocContext, Doc, OnSetDocContext, SetDoc, get_doc_store, id, list_docs_store, set_doc_store,
+};
+use junobuild_utils::encode_doc_data;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+use super::admin_permissions::{has_sufficient_role, insufficient_role, log_admin_action};
+use super::concentration_limits::{CONCENTRATION_SETTINGS_KEY, ConcentrationLimits, validate_concentration_limits};
+use super::dispatch::SetDocCall;
+use super::doc_index::list_key_prefixed;
+use super::notifications::write_notification;
+use super::models::{Opportunity, RevenueReport, decode_doc, decode_stored};
+use super::revenue_report_validation::{expected_period_end, ic_today};
+use super::validation_error::ValidationError;
+
+/// Collection holding one reporting calendar per funded application (key = applicationId)
+pub const REPORTING_SCHEDULES_COLLECTION: &str = "reporting_schedules";
+
+/// Collection holding admin-tunable platform settings
+pub const PLATFORM_SETTINGS_COLLECTION: &str = "platform_settings";
+
+/// Key of the reporting schedule settings in platform_settings
+pub const REPORTING_SETTINGS_KEY: &str = "reporting_schedule";
+
+/// How often the timer re-checks every schedule
+const SWEEP_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Report statuses that count as filed for the schedule
+const FILED_STATUSES: [&str; 3] = ["submitted", "under_review", "approved"];
+
+/// Grace periods applied to every schedule (`platform_settings/reporting_schedule`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportingSettings {
+    /// Days after the period end before the report is due
+    #[serde(default = "default_due_days")]
+    pub due_days: u32,
+    /// Days past the due date at which each escalation level is reached (ascending)
+    #[serde(default = "default_escalation_days")]
+    pub escalation_days: Vec<u32>,
+}
+
+fn default_due_days() -> u32 {
+    15
+}
+
+fn default_escalation_days() -> Vec<u32> {
+    vec![7, 30]
+}
+
+impl Default for ReportingSettings {
+    fn default() -> Self {
+        Self {
+            due_days: default_due_days(),
+            escalation_days: default_escalation_days(),
+        }
+    }
+}
+
+/// Expected reporting calendar of one funded application
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportingSchedule {
+    pub application_id: String,
+    pub opportunity_id: String,
+    pub business_id: String,
+    pub assigned_admin: Option<String>,
+    pub reporting_period: String,
+    pub periods: Vec<ScheduledPeriod>,
+}
+
+/// One expected report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledPeriod {
+    pub period_start: String,
+    pub period_end: String,
+    pub due_date: String,
+    /// upcoming | overdue | escalated | submitted
+    pub status: String,
+    #[serde(default)]
+    pub escalation_level: u32,
+    pub report_key: Option<String>,
+}
+
+/// Status change of a scheduled period that has to be notified
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScheduleEvent {
+    Overdue,
+    Escalated(u32),
+}
+
+/// Builds the reporting calendar of a financing funded on `funded_on`
+/// Periods are whole calendar months/quarters, starting with the first one that begins
+/// on or after the funding date, and cover `term_months`
+pub fn build_reporting_calendar(
+    reporting_period: &str,
+    funded_on: NaiveDate,
+    term_months: i64,
+    settings: &ReportingSettings,
+) -> Result<Vec<ScheduledPeriod>, String> {
+    let months_per_period = match reporting_period {
+        "monthly" => 1,
+        "quarterly" => 3,
+        _ => {
+            return Err(ValidationError::new(
+                "INVALID_REPORTING_PERIOD",
+                format!("❌ Reporting period must be either 'monthly' or 'quarterly', got '{}'", reporting_period),
+            )
+            .field("reportingPeriod")
+            .into())
+        }
+    };
+
+    // First day of the period containing the funding date
+    let month_of_period = funded_on.month0() - funded_on.month0() % months_per_period;
+    let mut start = NaiveDate::from_ymd_opt(funded_on.year(), month_of_period + 1, 1)
+        .ok_or("❌ Invalid funding date")?;
+    if start < funded_on {
+        start = start + Months::new(months_per_period);
+    }
+
+    let period_count = (term_months.max(0) as u32).div_ceil(months_per_period);
+    let mut periods = Vec::with_capacity(period_count as usize);
+
+    for _ in 0..period_count {
+        let end = expected_period_end(reporting_period, start).ok_or("❌ Invalid reporting period start")?;
+        periods.push(ScheduledPeriod {
+            period_start: start.to_string(),
+            period_end: end.to_string(),
+            due_date: (end + Days::new(settings.due_days as u64)).to_string(),
+            status: "upcoming".to_string(),
+            escalation_level: 0,
+            report_key: None,
+        });
+        start = start + Months::new(months_per_period);
+    }
+
+    Ok(periods)
+}
+
+/// Moves a period to overdue / the next escalation level once `today` passes its deadlines
+/// Returns the event to notify, if any
+pub fn advance_period(
+    period: &mut ScheduledPeriod,
+    today: NaiveDate,
+    settings: &ReportingSettings,
+) -> Option<ScheduleEvent> {
+    if period.status == "submitted" {
+        return None;
+    }
+
+    let due_date = NaiveDate::parse_from_str(&period.due_date, "%Y-%m-%d").ok()?;
+    if today <= due_date {
+        return None;
+    }
+
+    let days_late = (today - due_date).num_days();
+    let level = settings
+        .escalation_days
+        .iter()
+        .filter(|days| days_late >= **days as i64)
+        .count() as u32;
+
+    if level > period.escalation_level {
+        period.escalation_level = level;
+        period.status = "escalated".to_string();
+        return Some(ScheduleEvent::Escalated(level));
+    }
+
+    if period.status == "upcoming" {
+        period.status = "overdue".to_string();
+        return Some(ScheduleEvent::Overdue);
+    }
+
+    None
+}
+
+/// Only the satellite writes reporting schedules
+pub fn assert_reporting_schedule_write(context: &AssertSetDocContext) -> Result<(), String> {
+    if context.caller != id() {
+        return Err(ValidationError::new(
+            "SATELLITE_ONLY",
+            "❌ Access Denied: Reporting schedules are maintained by the satellite only",
+        )
+        .param("collection", REPORTING_SCHEDULES_COLLECTION)
+        .critical()
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Only managers change platform settings; reporting and concentration settings must be well-formed
+pub fn assert_platform_settings_update(call: &SetDocCall<Value>) -> Result<(), String> {
+    let admin_profile = call.caller_admin_profile()?;
+
+    if !has_sufficient_role(&admin_profile.role, "manager") {
+        return Err(insufficient_role(
+            format!("❌ Access Denied: Only managers can change platform settings. Your role: {}", admin_profile.role),
+            &admin_profile.role,
+            "manager",
+        )
+        .into());
+    }
+
+    if call.key() == REPORTING_SETTINGS_KEY {
+        let settings: ReportingSettings = decode_doc(call.collection(), &call.context.data.data.proposed.data)?;
+
+        if settings.due_days > 90 {
+            return Err(ValidationError::new("INVALID_SETTING", "❌ dueDays cannot exceed 90 days")
+                .field("dueDays")
+                .param("max", 90)
+                .param("actual", settings.due_days)
+                .into());
+        }
+
+        if settings.escalation_days.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(ValidationError::new(
+                "INVALID_SETTING",
+                "❌ escalationDays must be strictly ascending",
+            )
+            .field("escalationDays")
+            .into());
+        }
+    }
+
+    if call.key() == CONCENTRATION_SETTINGS_KEY {
+        let limits: ConcentrationLimits = decode_doc(call.collection(), &call.context.data.data.proposed.data)?;
+        validate_concentration_limits(&limits)?;
+    }
+
+    log_admin_action(
+        &call.caller(),
+        "update_platform_settings",
+        call.collection(),
+        call.key(),
+        call.current_json()?,
+        Some(call.proposed_json()?),
+    )
+}
+
+/// Keeps reporting_schedules in step with opportunities and revenue reports
+/// - an opportunity moving to funded gets its reporting calendar
+/// - a filed revenue report ticks off its scheduled period
+pub fn sync_reporting_schedule(context: &OnSetDocContext) -> Result<(), String> {
+    let collection = context.data.collection.as_str();
+    let upsert = &context.data.data;
+
+    match collection {
+        "opportunities" => {
+            let opportunity: Opportunity = decode_doc(collection, &upsert.after.data)?;
+            let before: Option<Opportunity> = decode_stored(collection, upsert.before.as_ref())?;
+
+            let became_funded = opportunity.status == "funded"
+                && before.is_none_or(|before| before.status != "funded");
+            if !became_funded {
+                return Ok(());
+            }
+
+            create_schedule(&context.data.key, &opportunity, upsert.after.updated_at)
+        }
+        "revenue_reports" => {
+            let report: RevenueReport = decode_doc(collection, &upsert.after.data)?;
+
+            if !FILED_STATUSES.contains(&report.status.as_str()) {
+                return Ok(());
+            }
+
+            mark_period_submitted(&report, &context.data.key)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Arms the sweep timer (timers do not survive upgrades, so this runs on init and post-upgrade)
+pub fn start_reporting_timer() {
+    // Sweep once on a zero-delay timer, so a failing sweep cannot trap init or upgrade
+    set_timer(Duration::ZERO, async {
+        run_sweep();
+    });
+
+    set_timer_interval(SWEEP_INTERVAL, || async {
+        run_sweep();
+    });
+}
+
+fn run_sweep() {
+    if let Err(error) = sweep_reporting_schedules() {
+        ic_cdk::println!("Reporting schedule sweep failed: {}", error);
+    }
+}
+
+/// Marks every missed period overdue/escalated and notifies the parties involved
+pub fn sweep_reporting_schedules() -> Result<(), String> {
+    let today = ic_today();
+    let settings = load_reporting_settings()?;
+    let docs = list_docs_store(id(), REPORTING_SCHEDULES_COLLECTION.to_string(), &Default::default())?;
+
+    // One failing schedule must not hold back the others
+    for (key, doc) in &docs.items {
+        if let Err(error) = advance_schedule(key, doc, today, &settings) {
+            ic_cdk::println!("Could not check reporting schedule {}: {}", key, error);
+        }
+    }
+
+    Ok(())
+}
+
+/// Advances the missed periods of one schedule
+/// A period's new status is only saved once its event was delivered, so a failed
+/// notification is retried by the next sweep instead of being lost
+fn advance_schedule(key: &str, doc: &Doc, today: NaiveDate, settings: &ReportingSettings) -> Result<(), String> {
+    let mut schedule = decode_doc::<ReportingSchedule>(REPORTING_SCHEDULES_COLLECTION, &doc.data)?;
+
+    let mut delivered = false;
+    for index in 0..schedule.periods.len() {
+        let mut period = schedule.periods[index].clone();
+        let Some(event) = advance_period(&mut period, today, settings) else {
+            continue;
+        };
+
+        if let Err(error) = notify_period_event(&schedule, &period, event) {
+            ic_cdk::println!("Could not notify period {} of reporting schedule {}: {}", period.due_date, key, error);
+            continue;
+        }
+        schedule.periods[index] = period;
+        delivered = true;
+    }
+
+    if !delivered {
+        return Ok(());
+    }
+
+    set_doc_store(
+        id(),
+        REPORTING_SCHEDULES_COLLECTION.to_string(),
+        key.to_string(),
+        SetDoc {
+            data: encode_doc_data(&schedule)?,
+            description: doc.description.clone(),
+            version: doc.version,
+        },
+    )?;
+
+    Ok(())
+}
+
+fn load_reporting_settings() -> Result<ReportingSettings, String> {
+    let doc = get_doc_store(id(), PLATFORM_SETTINGS_COLLECTION.to_string(), REPORTING_SETTINGS_KEY.to_string())?;
+
+    Ok(decode_stored(PLATFORM_SETTINGS_COLLECTION, doc.as_ref())?.unwrap_or_default())
+}
+
+/// Writes the reporting calendar of a newly funded opportunity (once per application)
+pub fn create_schedule(opportunity_key: &str, opportunity: &Opportunity, funded_at: u64) -> Result<(), String> {
+    let application_id = &opportunity.application_id;
+
+    // One calendar per application - never reset an existing one
+    if get_doc_store(id(), REPORTING_SCHEDULES_COLLECTION.to_string(), application_id.clone())?.is_some() {
+        return Ok(());
+    }
+
+    let application = get_doc_store(id(), "business_applications".to_string(), application_id.clone())?;
+    let business_id = match (&opportunity.business_id, &application) {
+        (Some(business_id), _) => business_id.clone(),
+        (None, Some(application)) => application.owner.to_text(),
+        (None, None) => return Ok(()),
+    };
+
+    let funded_on = chrono::DateTime::from_timestamp_nanos(funded_at as i64).date_naive();
+    let schedule = ReportingSchedule {
+        application_id: application_id.clone(),
+        opportunity_id: opportunity_key.to_string(),
+        business_id,
+        assigned_admin: find_assigned_admin(application_id)?.or_else(|| opportunity.approved_by.clone()),
+        reporting_period: opportunity.reporting_period.clone(),
+        periods: build_reporting_calendar(
+            &opportunity.reporting_period,
+            funded_on,
+            opportunity.term_months,
+            &load_reporting_settings()?,
+        )?,
+    };
+
+    set_doc_store(
+        id(),
+        REPORTING_SCHEDULES_COLLECTION.to_string(),
+        application_id.clone(),
+        SetDoc {
+            data: encode_doc_data(&schedule)?,
+            description: None,
+            version: None,
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Admin currently assigned to review the application, if any
+fn find_assigned_admin(application_id: &str) -> Result<Option<String>, String> {
+    // Keys are `assignment_{applicationId}_{timestamp}`; the prefix can also match longer ids
+    let assignments = list_key_prefixed::<Value>("assignments", &format!("assignment_{}_", application_id))?;
+
+    Ok(assignments
+        .into_iter()
+        .map(|(_, _, assignment)| assignment)
+        .filter(|assignment| {
+            assignment["applicationId"] == application_id && assignment["status"] != "reassigned"
+        })
+        .max_by_key(|assignment| assignment["assignedAt"].as_u64().unwrap_or(0))
+        .and_then(|assignment| assignment["assignedTo"].as_str().map(str::to_string)))
+}
+
+fn mark_period_submitted(report: &RevenueReport, report_key: &str) -> Result<(), String> {
+    let Some(doc) = get_doc_store(id(), REPORTING_SCHEDULES_COLLECTION.to_string(), report.application_id.clone())? else {
+        return Ok(());
+    };
+    let mut schedule: ReportingSchedule = decode_doc(REPORTING_SCHEDULES_COLLECTION, &doc.data)?;
+
+    let Some(period) = schedule
+        .periods
+        .iter_mut()
+        .find(|period| period.period_start == report.period_start && period.period_end == report.period_end)
+    else {
+        return Ok(());
+    };
+
+    if period.status == "submitted" {
+        return Ok(());
+    }
+    period.status = "submitted".to_string();
+    period.report_key = Some(report_key.to_string());
+
+    set_doc_store(
+        id(),
+        REPORTING_SCHEDULES_COLLECTION.to_string(),
+        report.application_id.clone(),
+        SetDoc {
+            data: encode_doc_data(&schedule)?,
+            description: doc.description.clone(),
+            version: doc.version,
+        },
+    )?;
+
+    Ok(())
+}
+
+fn notify_period_event(
+    schedule: &ReportingSchedule,
+    period: &ScheduledPeriod,
+    event: ScheduleEvent,
+) -> Result<(), String> {
+    let period_label = format!("{} to {}", period.period_start, period.period_end);
+    let (notification_type, priority, title, business_message) = match event {
+        ScheduleEvent::Overdue => (
+            "report_overdue",
+            "high",
+            "Revenue report overdue".to_string(),
+            format!(
+                "Your {} revenue report for {} was due on {}. Please submit it as soon as possible.",
+                schedule.reporting_period, period_label, period.due_date
+            ),
+        ),
+        ScheduleEvent::Escalated(level) => (
+            "report_escalated",
+            "urgent",
+            format!("Revenue report escalated (level {})", level),
+            format!(
+                "Your {} revenue report for {} is still missing after its {} due date and has been escalated.",
+                schedule.reporting_period, period_label, period.due_date
+            ),
+        ),
+    };
+    let metadata = json!({
+        "applicationId": schedule.application_id,
+        "opportunityId": schedule.opportunity_id,
+        "periodStart": period.period_start,
+        "periodEnd": period.period_end,
+        "dueDate": period.due_date,
+        "escalationLevel": period.escalation_level,
+    });
+
+    write_notification(
+        &schedule.business_id,
+        notification_type,
+        priority,
+        &title,
+        &business_message,
+        "/business/reporting",
+        &metadata,
+    )?;
+
+    if let Some(admin) = &schedule.assigned_admin {
+        write_notification(
+            admin,
+            notification_type,
+            priority,
+            &title,
+            &format!(
+                "Application {} has not filed its revenue report for {} (due {}).",
+                schedule.application_id, period_label, period.due_date
+            ),
+            "/admin/financial-reports",
+            &metadata,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_calendar_starts_with_first_full_period() {
+        let settings = ReportingSettings::default();
+
+        let monthly = build_reporting_calendar("monthly", date("2026-01-10"), 6, &settings).unwrap();
+        assert_eq!(monthly.len(), 6);
+        assert_eq!(monthly[0].period_start, "2026-02-01");
+        assert_eq!(monthly[0].period_end, "2026-02-28");
+        assert_eq!(monthly[0].due_date, "2026-03-15");
+        assert_eq!(monthly[5].period_end, "2026-07-31");
+
+        // Funded on the first day of a quarter: that quarter counts, 7 months need 3 quarters
+        let quarterly = build_reporting_calendar("quarterly", date("2026-04-01"), 7, &settings).unwrap();
+        assert_eq!(quarterly.len(), 3);
+        assert_eq!(quarterly[0].period_start, "2026-04-01");
+        assert_eq!(quarterly[2].period_end, "2026-12-31");
+
+        assert!(build_reporting_calendar("annual", date("2026-01-01"), 12, &settings).is_err());
+    }
+
+    #[test]
+    fn test_overdue_then_escalated() {
+        let settings = ReportingSettings::default();
+        let mut period = build_reporting_calendar("monthly", date("2026-01-01"), 1, &settings)
+            .unwrap()
+            .remove(0);
+
+        // Due 2026-02-15
+        assert_eq!(advance_period(&mut period, date("2026-02-15"), &settings), None);
+        assert_eq!(advance_period(&mut period, date("2026-02-16"), &settings), Some(ScheduleEvent::Overdue));
+        assert_eq!(advance_period(&mut period, date("2026-02-20"), &settings), None);
+        assert_eq!(advance_period(&mut period, date("2026-02-22"), &settings), Some(ScheduleEvent::Escalated(1)));
+        assert_eq!(advance_period(&mut period, date("2026-03-17"), &settings), Some(ScheduleEvent::Escalated(2)));
+        assert_eq!(period.status, "escalated");
+        assert_eq!(advance_period(&mut period, date("2026-06-01"), &settings), None);
+
+        period.status = "submitted".to_string();
+        assert_eq!(advance_period(&mut period, date("2027-01-01"), &settings), None);
+    }
+}
@@ -0,0 +1,393 @@
+// Revenue anomaly engine
+//
+// Every filed revenue report is compared with the application's approved history.
+// The resulting flags and a 0-100 score are written onto the report by the satellite
+// (anomalyFlags / anomalyScore) so reviewers see them next to the figures. The engine
+// never rejects a report - it only informs the review.
+
+use junobuild_satellite::{OnSetDocContext, SetDoc, id, set_doc_store};
+use junobuild_utils::encode_doc_data;
+use serde::Serialize;
+use serde_json::Value;
+use super::audit_log::parse_doc_json;
+use super::dispatch::SetDocCall;
+use super::models::{RevenueReport, decode_doc};
+use super::revenue_report_validation::{calculate_profit_margin, load_application_reports};
+use super::validation_error::ValidationError;
+
+/// Report statuses that are assessed
+const ASSESSED_STATUSES: [&str; 3] = ["submitted", "under_review", "approved"];
+
+/// Fields only the satellite may write
+const DERIVED_FIELDS: [&str; 2] = ["anomalyFlags", "anomalyScore"];
+
+/// Revenue above this multiple of the historical average is a spike
+const SPIKE_MULTIPLE: f64 = 2.0;
+
+/// Revenue below this share of the last approved period is a drop
+const DROP_SHARE: f64 = 0.5;
+
+/// Net margin moving more than this many percentage points from the historical average
+const MARGIN_SWING_POINTS: f64 = 20.0;
+
+/// Expense/revenue ratio moving more than this from the historical average
+const EXPENSE_RATIO_DRIFT: f64 = 0.15;
+
+/// Line items needed before the Benford test means anything
+const BENFORD_MIN_SAMPLE: usize = 20;
+
+/// Chi-square critical value, 8 degrees of freedom, p = 0.05
+const BENFORD_CHI_SQUARE_CRITICAL: f64 = 15.507;
+
+/// Line items needed before round-number bias is judged
+const ROUND_NUMBER_MIN_SAMPLE: usize = 5;
+
+/// Share of round line items (multiples of ₦1,000) considered biased
+const ROUND_NUMBER_SHARE: f64 = 0.6;
+
+/// One anomaly found on a report
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnomalyFlag {
+    pub code: &'static str,
+    pub weight: u32,
+    pub message: String,
+}
+
+/// Flags and overall score of a report (0 = nothing unusual, 100 = highly suspicious)
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct AnomalyAssessment {
+    pub flags: Vec<AnomalyFlag>,
+    pub score: u32,
+}
+
+impl AnomalyAssessment {
+    fn flag(&mut self, code: &'static str, weight: u32, message: String) {
+        self.flags.push(AnomalyFlag { code, weight, message });
+        self.score = (self.score + weight).min(100);
+    }
+}
+
+/// Flags suspicious revenue patterns
+/// `history` holds the application's approved reports for earlier periods, oldest first
+pub fn detect_suspicious_patterns(current: &RevenueReport, history: &[RevenueReport]) -> AnomalyAssessment {
+    let mut assessment = AnomalyAssessment::default();
+
+    if !history.is_empty() {
+        let avg_revenue = mean(history.iter().map(|r| r.total_revenue));
+
+        // Flag if current revenue is 200% higher than average
+        if avg_revenue > 0.0 && current.total_revenue > avg_revenue * SPIKE_MULTIPLE {
+            assessment.flag(
+                "REVENUE_SPIKE",
+                25,
+                format!(
+                    "⚠️ Revenue spike detected: Current (₦{:.2}) is over 2x the average (₦{:.2})",
+                    current.total_revenue, avg_revenue
+                ),
+            );
+        }
+
+        // Flag if current revenue drops by 50% or more
+        if let Some(last) = history.last() {
+            if last.total_revenue > 0.0 && current.total_revenue < last.total_revenue * DROP_SHARE {
+                assessment.flag(
+                    "REVENUE_DROP",
+                    20,
+                    format!(
+                        "⚠️ Significant revenue drop: Down {:.0}% from last period",
+                        ((last.total_revenue - current.total_revenue) / last.total_revenue) * 100.0
+                    ),
+                );
+            }
+        }
+
+        // Flag a net margin far from the usual one
+        let margins: Vec<f64> = history
+            .iter()
+            .filter_map(|r| calculate_profit_margin(r.total_revenue, r.net_profit).ok())
+            .collect();
+        if let (Ok(margin), false) = (
+            calculate_profit_margin(current.total_revenue, current.net_profit),
+            margins.is_empty(),
+        ) {
+            let avg_margin = mean(margins.iter().copied());
+            if (margin - avg_margin).abs() > MARGIN_SWING_POINTS {
+                assessment.flag(
+                    "MARGIN_SWING",
+                    15,
+                    format!(
+                        "⚠️ Net margin of {:.1}% differs from the usual {:.1}% by more than {:.0} points",
+                        margin, avg_margin, MARGIN_SWING_POINTS
+                    ),
+                );
+            }
+        }
+
+        // Flag expenses moving out of line with revenue
+        let ratios: Vec<f64> = history.iter().filter_map(expense_ratio).collect();
+        if let (Some(ratio), false) = (expense_ratio(current), ratios.is_empty()) {
+            let avg_ratio = mean(ratios.iter().copied());
+            if (ratio - avg_ratio).abs() > EXPENSE_RATIO_DRIFT {
+                assessment.flag(
+                    "EXPENSE_RATIO_DRIFT",
+                    15,
+                    format!(
+                        "⚠️ Expenses are {:.0}% of revenue against a usual {:.0}%",
+                        ratio * 100.0, avg_ratio * 100.0
+                    ),
+                );
+            }
+        }
+
+        // Flag consistent negative profits
+        if current.net_profit < 0.0 && history.iter().all(|r| r.net_profit < 0.0) {
+            assessment.flag(
+                "CONSISTENT_LOSSES",
+                15,
+                "⚠️ Consistent losses may indicate business viability issues".to_string(),
+            );
+        }
+    }
+
+    // Benford's law over the line items of this report and its approved history
+    let sample: Vec<f64> = std::iter::once(current)
+        .chain(history)
+        .flat_map(line_items)
+        .collect();
+    if let Some(chi_square) = benford_chi_square(&sample) {
+        if chi_square > BENFORD_CHI_SQUARE_CRITICAL {
+            assessment.flag(
+                "BENFORD_DEVIATION",
+                20,
+                format!(
+                    "⚠️ First digits of {} line items deviate from Benford's law (χ² = {:.1})",
+                    sample.len(), chi_square
+                ),
+            );
+        }
+    }
+
+    // Too many suspiciously round figures in this report
+    let items = line_items(current);
+    if items.len() >= ROUND_NUMBER_MIN_SAMPLE {
+        let round = items.iter().filter(|v| **v >= 1_000.0 && **v % 1_000.0 == 0.0).count();
+        let share = round as f64 / items.len() as f64;
+        if share >= ROUND_NUMBER_SHARE {
+            assessment.flag(
+                "ROUND_NUMBER_BIAS",
+                10,
+                format!("⚠️ {} of {} line items are round thousands", round, items.len()),
+            );
+        }
+    }
+
+    assessment
+}
+
+/// Every reported amount of a report (totals and breakdown lines), as absolute values
+fn line_items(report: &RevenueReport) -> Vec<f64> {
+    let breakdowns = report
+        .revenue_breakdown
+        .iter()
+        .chain(report.expense_breakdown.iter())
+        .flat_map(|breakdown| breakdown.values().filter_map(Value::as_f64));
+
+    [report.total_revenue, report.total_expenses, report.operating_expenses, report.gross_profit, report.net_profit]
+        .into_iter()
+        .chain(breakdowns)
+        .map(f64::abs)
+        .filter(|v| *v >= 1.0)
+        .collect()
+}
+
+fn expense_ratio(report: &RevenueReport) -> Option<f64> {
+    (report.total_revenue > 0.0).then(|| report.total_expenses / report.total_revenue)
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    if count == 0 { 0.0 } else { sum / count as f64 }
+}
+
+fn first_digit(value: f64) -> usize {
+    let mut v = value.abs();
+    while v >= 10.0 {
+        v /= 10.0;
+    }
+    v as usize
+}
+
+/// Chi-square distance between the first digits of `values` and Benford's distribution
+/// None when the sample is too small to judge
+pub fn benford_chi_square(values: &[f64]) -> Option<f64> {
+    if values.len() < BENFORD_MIN_SAMPLE {
+        return None;
+    }
+
+    let mut observed = [0usize; 10];
+    for value in values {
+        observed[first_digit(*value)] += 1;
+    }
+
+    let total = values.len() as f64;
+    Some(
+        (1..=9)
+            .map(|digit| {
+                let expected = total * (1.0 + 1.0 / digit as f64).log10();
+                (observed[digit] as f64 - expected).powi(2) / expected
+            })
+            .sum(),
+    )
+}
+
+/// Clients cannot write the derived anomaly fields
+pub fn assert_anomaly_fields_untouched(call: &SetDocCall<RevenueReport>) -> Result<(), String> {
+    if call.is_satellite() {
+        return Ok(());
+    }
+
+    let proposed = call.proposed_json()?;
+    let current = call.current_json()?;
+
+    for field in DERIVED_FIELDS {
+        let Some(value) = proposed.get(field) else {
+            continue;
+        };
+        if current.and_then(|c| c.get(field)) != Some(value) {
+            return Err(ValidationError::new(
+                "DERIVED_FIELD",
+                format!("❌ Security violation: {} is computed by the platform and cannot be set", field),
+            )
+            .field(field)
+            .critical()
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Keeps anomalyFlags / anomalyScore of filed reports up to date
+pub fn sync_revenue_anomalies(context: &OnSetDocContext) -> Result<(), String> {
+    if context.data.collection != "revenue_reports" {
+        return Ok(());
+    }
+
+    let doc = &context.data.data.after;
+    let report: RevenueReport = decode_doc(&context.data.collection, &doc.data)?;
+
+    if !ASSESSED_STATUSES.contains(&report.status.as_str()) {
+        return Ok(());
+    }
+
+    let mut history: Vec<RevenueReport> = load_application_reports(&report.application_id, &context.data.key)?
+        .into_iter()
+        .map(|(_, other)| other)
+        .filter(|other| other.status == "approved" && other.period_end < report.period_start)
+        .collect();
+    history.sort_by(|a, b| a.period_start.cmp(&b.period_start));
+
+    let assessment = detect_suspicious_patterns(&report, &history);
+
+    // Patch the raw document so fields outside the typed model are preserved
+    let mut data = parse_doc_json(&doc.data)?;
+    let flags = serde_json::to_value(&assessment.flags).map_err(|e| e.to_string())?;
+    if report.anomaly_score == Some(assessment.score as f64) && data["anomalyFlags"] == flags {
+        return Ok(());
+    }
+    data["anomalyFlags"] = flags;
+    data["anomalyScore"] = Value::from(assessment.score);
+
+    set_doc_store(
+        id(),
+        context.data.collection.clone(),
+        context.data.key.clone(),
+        SetDoc {
+            data: encode_doc_data(&data)?,
+            description: doc.description.clone(),
+            version: doc.version,
+        },
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn report(revenue: f64, expenses: f64) -> RevenueReport {
+        decode_doc(
+            "revenue_reports",
+            &serde_json::to_vec(&json!({
+                "applicationId": "app_1",
+                "reportingPeriod": "monthly",
+                "periodStart": "2026-01-01",
+                "periodEnd": "2026-01-31",
+                "totalRevenue": revenue,
+                "totalExpenses": expenses,
+                "grossProfit": revenue - expenses,
+                "operatingExpenses": expenses,
+                "netProfit": revenue - expenses,
+                "documents": []
+            }))
+            .unwrap(),
+        )
+        .unwrap()
+    }
+
+    /// Flags derived from the history (line-item tests are covered separately)
+    fn codes(assessment: &AnomalyAssessment) -> Vec<&str> {
+        assessment
+            .flags
+            .iter()
+            .map(|f| f.code)
+            .filter(|code| !["BENFORD_DEVIATION", "ROUND_NUMBER_BIAS"].contains(code))
+            .collect()
+    }
+
+    #[test]
+    fn test_revenue_spike_detection() {
+        let history = vec![report(100_000.0, 80_000.0), report(110_000.0, 88_000.0), report(105_000.0, 84_000.0)];
+        let assessment = detect_suspicious_patterns(&report(300_000.0, 240_000.0), &history);
+
+        assert_eq!(codes(&assessment), vec!["REVENUE_SPIKE"]);
+        assert_eq!(assessment.flags[0].weight, 25);
+    }
+
+    #[test]
+    fn test_drop_margin_swing_and_expense_drift() {
+        let history = vec![report(200_000.0, 150_000.0), report(210_000.0, 157_500.0)];
+        let assessment = detect_suspicious_patterns(&report(80_000.0, 79_000.0), &history);
+
+        assert_eq!(codes(&assessment), vec!["REVENUE_DROP", "MARGIN_SWING", "EXPENSE_RATIO_DRIFT"]);
+    }
+
+    #[test]
+    fn test_consistent_losses_only_when_every_period_lost_money() {
+        let profitable = vec![report(100_000.0, 80_000.0), report(100_000.0, 70_000.0)];
+        assert!(!codes(&detect_suspicious_patterns(&report(100_000.0, 75_000.0), &profitable)).contains(&"CONSISTENT_LOSSES"));
+
+        let losing = vec![report(100_000.0, 110_000.0), report(100_000.0, 105_000.0)];
+        assert!(codes(&detect_suspicious_patterns(&report(100_000.0, 108_000.0), &losing)).contains(&"CONSISTENT_LOSSES"));
+    }
+
+    #[test]
+    fn test_benford_and_round_numbers() {
+        // Naturally distributed figures follow Benford closely
+        let natural: Vec<f64> = (1..=60).map(|i| 1.2_f64.powi(i)).collect();
+        assert!(benford_chi_square(&natural).unwrap() < BENFORD_CHI_SQUARE_CRITICAL);
+
+        // Fabricated figures all starting with 5-9 do not
+        let fabricated: Vec<f64> = (0..30).map(|i| 5_000.0 + (i % 5) as f64 * 1_000.0 + 37.0).collect();
+        assert!(benford_chi_square(&fabricated).unwrap() > BENFORD_CHI_SQUARE_CRITICAL);
+        assert_eq!(benford_chi_square(&natural[..10]), None);
+
+        let round = detect_suspicious_patterns(&report(500_000.0, 300_000.0), &[]);
+        assert!(round.flags.iter().any(|f| f.code == "ROUND_NUMBER_BIAS"));
+        let uneven = detect_suspicious_patterns(&report(512_345.0, 298_771.0), &[]);
+        assert!(uneven.flags.is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+// Revenue report revision history
+//
+// Every time a report is (re)submitted the satellite snapshots it into
+// revenue_report_revisions (key `{reportKey}_v{n}`) together with a field-level diff
+// against the previous submission, so reviewers see exactly which figures changed
+// between revisions. Snapshots are written by the satellite only and never change.
+
+use junobuild_satellite::{AssertSetDocContext, OnSetDocContext, SetDoc, get_doc_store, id, set_doc_store};
+use junobuild_utils::encode_doc_data;
+use serde_json::{json, Map, Value};
+use super::audit_log::parse_doc_json;
+use super::validation_error::ValidationError;
+
+/// Collection holding one immutable snapshot per submitted report version
+pub const REVENUE_REPORT_REVISIONS_COLLECTION: &str = "revenue_report_revisions";
+
+/// Workflow and derived fields that are not part of the reported figures
+const NON_REPORTED_FIELDS: [&str; 9] = [
+    "status",
+    "submittedAt",
+    "reviewedAt",
+    "reviewedBy",
+    "reviewNotes",
+    "revisionNotes",
+    "adminNotes",
+    "anomalyFlags",
+    "anomalyScore",
+];
+
+/// Only the satellite appends snapshots, and a snapshot can never be rewritten
+pub fn assert_revision_history_write(context: &AssertSetDocContext) -> Result<(), String> {
+    if context.caller != id() {
+        return Err(ValidationError::new(
+            "SATELLITE_ONLY",
+            "❌ Access Denied: Revenue report revisions are written by the satellite only",
+        )
+        .param("collection", REVENUE_REPORT_REVISIONS_COLLECTION)
+        .critical()
+        .into());
+    }
+
+    if context.data.data.current.is_some() {
+        return Err(ValidationError::new(
+            "REVISION_IMMUTABLE",
+            format!("❌ Security violation: Revenue report revision '{}' is immutable", context.data.key),
+        )
+        .critical()
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Reported figures of a report, without workflow and derived fields
+pub fn reported_figures(report: &Value) -> Value {
+    let mut figures = report.clone();
+    if let Some(obj) = figures.as_object_mut() {
+        for field in NON_REPORTED_FIELDS {
+            obj.remove(field);
+        }
+    }
+    figures
+}
+
+/// Field-level diff of two report versions: `{ path: { before, after } }`
+/// Nested blocks (contract details, breakdowns) are compared field by field with dotted paths
+pub fn diff_report_fields(before: &Value, after: &Value) -> Value {
+    let mut changes = Map::new();
+    collect_changes("", before, after, &mut changes);
+    Value::Object(changes)
+}
+
+fn collect_changes(prefix: &str, before: &Value, after: &Value, changes: &mut Map<String, Value>) {
+    if let (Some(before_obj), Some(after_obj)) = (before.as_object(), after.as_object()) {
+        let fields = before_obj.keys().chain(after_obj.keys().filter(|k| !before_obj.contains_key(*k)));
+        for field in fields {
+            let path = if prefix.is_empty() { field.clone() } else { format!("{}.{}", prefix, field) };
+            collect_changes(
+                &path,
+                before_obj.get(field).unwrap_or(&Value::Null),
+                after_obj.get(field).unwrap_or(&Value::Null),
+                changes,
+            );
+        }
+        return;
+    }
+
+    if before != after {
+        changes.insert(prefix.to_string(), json!({ "before": before, "after": after }));
+    }
+}
+
+/// Snapshots a revenue report each time it moves to submitted
+pub fn record_revenue_report_revision(context: &OnSetDocContext) -> Result<(), String> {
+    if context.data.collection != "revenue_reports" {
+        return Ok(());
+    }
+
+    let after = parse_doc_json(&context.data.data.after.data)?;
+    let before_status = match &context.data.data.before {
+        Some(doc) => parse_doc_json(&doc.data)?["status"].clone(),
+        None => Value::Null,
+    };
+
+    if after["status"] != "submitted" || before_status == "submitted" {
+        return Ok(());
+    }
+
+    let report_key = &context.data.key;
+
+    // Find the previous snapshot of this report, if any
+    let mut revision = 1;
+    let mut previous: Option<Value> = None;
+    while let Some(doc) = get_doc_store(
+        id(),
+        REVENUE_REPORT_REVISIONS_COLLECTION.to_string(),
+        revision_key(report_key, revision),
+    )? {
+        previous = Some(parse_doc_json(&doc.data)?);
+        revision += 1;
+    }
+
+    let figures = reported_figures(&after);
+    let changes = previous
+        .as_ref()
+        .map(|previous| diff_report_fields(&previous["report"], &figures));
+
+    let snapshot = json!({
+        "reportKey": report_key,
+        "applicationId": after["applicationId"],
+        "revision": revision,
+        "previousRevisionKey": previous.as_ref().map(|_| revision_key(report_key, revision - 1)),
+        "submittedBy": context.data.data.after.owner.to_text(),
+        "submittedAt": chrono::DateTime::from_timestamp_nanos(context.data.data.after.updated_at as i64).to_rfc3339(),
+        "revisionNotes": after["revisionNotes"],
+        "report": figures,
+        "changes": changes,
+    });
+
+    set_doc_store(
+        id(),
+        REVENUE_REPORT_REVISIONS_COLLECTION.to_string(),
+        revision_key(report_key, revision),
+        SetDoc {
+            data: encode_doc_data(&snapshot)?,
+            description: None,
+            version: None,
+        },
+    )?;
+
+    Ok(())
+}
+
+fn revision_key(report_key: &str, revision: u32) -> String {
+    format!("{}_v{}", report_key, revision)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_walks_contract_detail_blocks() {
+        let before = json!({
+            "totalRevenue": 1_000_000.0,
+            "operatingExpenses": 200_000.0,
+            "murabahaDetails": { "assetCost": 500_000.0, "installmentsPaid": 3 }
+        });
+        let after = json!({
+            "totalRevenue": 1_200_000.0,
+            "operatingExpenses": 200_000.0,
+            "murabahaDetails": { "assetCost": 500_000.0, "installmentsPaid": 4 },
+            "notes": "Corrected sales figure"
+        });
+
+        let diff = diff_report_fields(&before, &after);
+
+        assert_eq!(diff.as_object().unwrap().len(), 3);
+        assert_eq!(diff["totalRevenue"], json!({ "before": 1_000_000.0, "after": 1_200_000.0 }));
+        assert_eq!(diff["murabahaDetails.installmentsPaid"], json!({ "before": 3, "after": 4 }));
+        assert_eq!(diff["notes"]["before"], Value::Null);
+    }
+
+    #[test]
+    fn test_workflow_fields_are_not_figures() {
+        let report = json!({ "totalRevenue": 10.0, "status": "submitted", "reviewNotes": "ok", "anomalyScore": 25 });
+
+        assert_eq!(reported_figures(&report), json!({ "totalRevenue": 10.0 }));
+    }
+}
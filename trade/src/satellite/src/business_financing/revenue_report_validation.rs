@@ -1,228 +1,369 @@
-use junobuild_satellite::OnSetDocContext;
-use serde_json::Value;
-use super::islamic_contract_validation::validate_contract_specific_details;
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate};
+use junobuild_satellite::{get_doc_store, id};
+use serde_json::json;
+use super::dispatch::SetDocCall;
+use super::doc_index::list_indexed;
+use super::islamic_contract_validation::{canonical_contract_type, validate_contract_specific_details};
+use super::models::RevenueReport;
+use super::validation_error::ValidationError;
+
+/// Date format of `periodStart` / `periodEnd` (HTML date input)
+const REPORT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+const REVENUE_REPORTS_COLLECTION: &str = "revenue_reports";
 
 /// Validates revenue report submission integrity
-pub async fn validate_revenue_report_submission(
-    context: &OnSetDocContext,
+/// Runs in assert_set_doc so an invalid report is rejected before it is written
+pub fn validate_revenue_report_submission(
+    call: &SetDocCall<RevenueReport>,
 ) -> Result<(), String> {
-    let collection = &context.data.collection;
-    
-    if collection != "revenue_reports" {
-        return Ok(());
-    }
-    
-    // Parse the after data from JSON string
-    let data_str = std::str::from_utf8(&context.data.data.after.data)
-        .map_err(|e| format!("Failed to decode revenue report data as UTF-8: {}", e))?;
-    
-    let data: Value = serde_json::from_str(data_str)
-        .map_err(|e| format!("Failed to parse revenue report data: {}", e))?;
-    
-    let data = data.as_object()
-        .ok_or("Invalid revenue report data format")?;
+    let report = &call.proposed;
     
     // 1. Validate reporting period type
-    let period_type = data.get("reportingPeriod")
-        .and_then(|v| v.as_str())
-        .ok_or("❌ Missing reporting period type")?;
+    let period_type = report.reporting_period.as_str();
     
     if period_type != "monthly" && period_type != "quarterly" {
-        return Err("❌ Reporting period must be either 'monthly' or 'quarterly'".to_string());
+        return Err(ValidationError::new(
+            "INVALID_REPORT_PERIOD",
+            "❌ Reporting period must be either 'monthly' or 'quarterly'",
+        )
+        .field("reportingPeriod")
+        .param("actual", period_type)
+        .into());
     }
     
     // 2. Validate document requirements based on period type
-    let documents = data.get("documents")
-        .and_then(|v| v.as_array())
-        .ok_or("❌ Missing documents array")?;
-    
     let required_doc_count = if period_type == "monthly" { 4 } else { 5 };
     
-    if documents.len() < required_doc_count {
-        return Err(format!(
-            "❌ {} reports require at least {} documents. Only {} provided",
-            period_type, required_doc_count, documents.len()
-        ));
+    if report.documents.len() < required_doc_count {
+        return Err(ValidationError::new(
+            "MISSING_DOCUMENTS",
+            format!(
+                "❌ {} reports require at least {} documents. Only {} provided",
+                period_type, required_doc_count, report.documents.len()
+            ),
+        )
+        .field("documents")
+        .param("min", required_doc_count)
+        .param("actual", report.documents.len())
+        .into());
     }
     
     // 3. Validate status
-    let status = data.get("status")
-        .and_then(|v| v.as_str())
-        .unwrap_or("draft");
+    let status = report.status.as_str();
     
-    let valid_statuses = vec!["draft", "submitted", "under_review", "approved", "revision_requested"];
+    let valid_statuses = ["draft", "submitted", "under_review", "approved", "revision_requested"];
     if !valid_statuses.contains(&status) {
-        return Err(format!("❌ Invalid status: {}", status));
+        return Err(ValidationError::new("INVALID_STATUS", format!("❌ Invalid status: {}", status))
+            .field("status")
+            .param("actual", status)
+            .into());
     }
     
-    // 4. If status is submitted, ensure all required fields are present
-    if status == "submitted" {
-        let required_fields = vec![
-            "totalRevenue",
-            "totalExpenses",
-            "netProfit",
-            "grossProfit",
-            "operatingExpenses",
-            "periodStart",
-            "periodEnd",
-            "applicationId",
-            "businessName",
-        ];
-        
-        for field in required_fields {
-            if !data.contains_key(field) || data.get(field).unwrap().is_null() {
-                return Err(format!("❌ Missing required field for submission: {}", field));
-            }
-        }
+    // 4. If status is submitted, ensure fields only required for submission are present
+    if status == "submitted" && report.business_name.as_deref().is_none_or(str::is_empty) {
+        return Err(ValidationError::new("MISSING_FIELD", "❌ Missing required field for submission: businessName")
+            .field("businessName")
+            .into());
     }
     
-    // 5. Validate that period dates are logical
-    validate_report_period(&Value::Object(data.clone()))?;
+    // 5. Validate that the period is a past calendar month/quarter
+    validate_report_period(report, ic_today())?;
     
-    // 6. Auto-validate financial calculations
-    validate_financial_calculations(&Value::Object(data.clone()))?;
+    // 6. Check the period against the application's other reports
+    let existing = load_application_reports(&report.application_id, call.key())?;
+    validate_no_period_overlap(report, &existing)?;
+    if status != "draft" {
+        validate_no_missing_periods(report, &existing)?;
+    }
+    
+    // 7. Auto-validate financial calculations
+    validate_financial_calculations(report)?;
     
-    // 7. NEW: Validate Islamic finance contract-specific details
-    if let Some(contract_type) = data.get("contractType").and_then(|v| v.as_str()) {
+    // 8. NEW: Validate Islamic finance contract-specific details
+    if let Some(contract_type) = report.contract_type.as_deref() {
         // Validate contract-specific details based on contract type
-        match contract_type {
-            "murabaha" => {
-                if let Some(details) = data.get("murabahaDetails") {
-                    validate_contract_specific_details("murabaha", details)?;
-                }
-            },
-            "mudaraba" => {
-                if let Some(details) = data.get("mudarabaDetails") {
-                    validate_contract_specific_details("mudaraba", details)?;
-                }
-            },
-            "musharaka" => {
-                if let Some(details) = data.get("musharakahDetails") {
-                    validate_contract_specific_details("musharaka", details)?;
-                }
-            },
-            "ijara" => {
-                if let Some(details) = data.get("ijaraDetails") {
-                    validate_contract_specific_details("ijara", details)?;
-                }
-            },
-            "istisna" | "salam" => {
-                if let Some(details) = data.get("salamDetails") {
-                    validate_contract_specific_details(contract_type, details)?;
-                }
-            },
-            _ => {
-                // Unknown contract type, no specific validation
-            }
+        let details = match canonical_contract_type(contract_type).unwrap_or(contract_type) {
+            "murabaha" => report.murabaha_details.as_ref(),
+            "mudaraba" => report.mudaraba_details.as_ref(),
+            "musharaka" => report.musharakah_details.as_ref(),
+            "ijara" => report.ijara_details.as_ref(),
+            "istisna" | "salam" => report.salam_details.as_ref(),
+            // Unknown contract type, no specific validation
+            _ => None,
+        };
+        if let Some(details) = details {
+            validate_contract_specific_details(contract_type, details)?;
         }
     }
     
     Ok(())
 }
 
+/// Other revenue reports the application's business filed for it
+/// Reports written by anyone else (e.g. before ownership was enforced) are ignored, so a
+/// stray draft cannot block or shape the business's own periods
+pub fn load_application_reports(
+    application_id: &str,
+    exclude_key: &str,
+) -> Result<Vec<(String, RevenueReport)>, String> {
+    let Some(application) = get_doc_store(id(), "business_applications".to_string(), application_id.to_string())? else {
+        return Ok(Vec::new());
+    };
+
+    Ok(list_indexed::<RevenueReport>(REVENUE_REPORTS_COLLECTION, "application", application_id)?
+        .into_iter()
+        .filter(|(key, doc, _)| key != exclude_key && doc.owner == application.owner)
+        .map(|(key, _, report)| (key, report))
+        .collect())
+}
+
+/// Parsed `periodStart` / `periodEnd` of a report
+fn report_dates(report: &RevenueReport) -> Result<(NaiveDate, NaiveDate), String> {
+    Ok((
+        parse_report_date("periodStart", &report.period_start)?,
+        parse_report_date("periodEnd", &report.period_end)?,
+    ))
+}
+
 /// Validates that report periods don't overlap (anti-fraud measure)
-#[allow(dead_code)]
+/// A duplicate period would double-count profit for the application
 pub fn validate_no_period_overlap(
-    new_start: &str,
-    new_end: &str,
-    existing_reports: Vec<Value>,
+    report: &RevenueReport,
+    existing: &[(String, RevenueReport)],
 ) -> Result<(), String> {
-    for report in existing_reports {
-        let existing_start = report.get("periodStart")
-            .and_then(|v| v.as_str())
-            .unwrap_or("");
-        
-        let existing_end = report.get("periodEnd")
-            .and_then(|v| v.as_str())
-            .unwrap_or("");
+    let (new_start, new_end) = report_dates(report)?;
+    
+    for (key, other) in existing {
+        let Ok((existing_start, existing_end)) = report_dates(other) else {
+            continue;
+        };
         
         // Check for any overlap
         if new_start <= existing_end && new_end >= existing_start {
-            return Err(format!(
-                "❌ Report period overlaps with existing report ({} to {})",
-                existing_start, existing_end
-            ));
+            return Err(ValidationError::new(
+                "OVERLAPPING_REPORT_PERIOD",
+                format!(
+                    "❌ Report period overlaps with existing report ({} to {})",
+                    existing_start, existing_end
+                ),
+            )
+            .field("periodStart")
+            .param("reportKey", key.as_str())
+            .param("existingStart", existing_start.to_string())
+            .param("existingEnd", existing_end.to_string())
+            .into());
         }
     }
     
     Ok(())
 }
 
-/// Validates financial calculations are correct
-fn validate_financial_calculations(data: &Value) -> Result<(), String> {
-    let total_revenue = data.get("totalRevenue")
-        .and_then(|v| v.as_f64())
-        .unwrap_or(0.0);
-    
-    let total_expenses = data.get("totalExpenses")
-        .and_then(|v| v.as_f64())
-        .unwrap_or(0.0);
-    
-    let net_profit = data.get("netProfit")
-        .and_then(|v| v.as_f64())
-        .unwrap_or(0.0);
+/// Date ranges between the last approved report and `report` that no other report covers
+/// Empty when the application has no approved report before this one
+pub fn find_missing_periods(
+    report: &RevenueReport,
+    existing: &[(String, RevenueReport)],
+) -> Result<Vec<(NaiveDate, NaiveDate)>, String> {
+    let (new_start, _) = report_dates(report)?;
+    
+    let mut covered: Vec<(NaiveDate, NaiveDate)> = existing
+        .iter()
+        .filter_map(|(_, other)| report_dates(other).ok())
+        .filter(|(_, end)| *end < new_start)
+        .collect();
+    covered.sort();
+    
+    let last_approved_end = existing
+        .iter()
+        .filter(|(_, other)| other.status == "approved")
+        .filter_map(|(_, other)| report_dates(other).ok().map(|(_, end)| end))
+        .filter(|end| *end < new_start)
+        .max();
+    
+    let Some(last_approved_end) = last_approved_end else {
+        return Ok(Vec::new());
+    };
+    
+    let mut missing = Vec::new();
+    let mut cursor = last_approved_end + Days::new(1);
+    
+    for (start, end) in covered {
+        if end < cursor {
+            continue;
+        }
+        if start > cursor {
+            missing.push((cursor, start - Days::new(1)));
+        }
+        cursor = end + Days::new(1);
+    }
     
-    let gross_profit = data.get("grossProfit")
-        .and_then(|v| v.as_f64())
-        .unwrap_or(0.0);
+    if cursor < new_start {
+        missing.push((cursor, new_start - Days::new(1)));
+    }
     
-    let operating_expenses = data.get("operatingExpenses")
-        .and_then(|v| v.as_f64())
-        .unwrap_or(0.0);
+    Ok(missing)
+}
+
+/// Rejects a report that skips periods since the last approved report
+fn validate_no_missing_periods(
+    report: &RevenueReport,
+    existing: &[(String, RevenueReport)],
+) -> Result<(), String> {
+    let missing = find_missing_periods(report, existing)?;
     
+    let Some((first_start, first_end)) = missing.first() else {
+        return Ok(());
+    };
+    
+    Err(ValidationError::new(
+        "MISSING_REPORT_PERIODS",
+        format!(
+            "❌ Reports are missing for {} period(s) since the last approved report, starting {} to {}",
+            missing.len(), first_start, first_end
+        ),
+    )
+    .field("periodStart")
+    .param(
+        "missing",
+        missing
+            .iter()
+            .map(|(start, end)| json!({ "start": start.to_string(), "end": end.to_string() }))
+            .collect::<Vec<_>>(),
+    )
+    .into())
+}
+
+/// Validates financial calculations are correct
+fn validate_financial_calculations(report: &RevenueReport) -> Result<(), String> {
     // Net Profit = Total Revenue - Total Expenses
-    let calculated_net_profit = total_revenue - total_expenses;
+    let calculated_net_profit = report.total_revenue - report.total_expenses;
     let tolerance = 0.01;
     
-    if (net_profit - calculated_net_profit).abs() > tolerance {
-        return Err(format!(
-            "❌ Net profit calculation error. Expected: {:.2}, Got: {:.2}",
-            calculated_net_profit, net_profit
-        ));
+    if (report.net_profit - calculated_net_profit).abs() > tolerance {
+        return Err(ValidationError::new(
+            "CALCULATION_MISMATCH",
+            format!(
+                "❌ Net profit calculation error. Expected: {:.2}, Got: {:.2}",
+                calculated_net_profit, report.net_profit
+            ),
+        )
+        .field("netProfit")
+        .param("expected", calculated_net_profit)
+        .param("actual", report.net_profit)
+        .into());
     }
     
     // Gross Profit validation
-    let cogs = total_expenses - operating_expenses;
-    let calculated_gross_profit = total_revenue - cogs;
-    
-    if (gross_profit - calculated_gross_profit).abs() > tolerance {
-        return Err(format!(
-            "❌ Gross profit calculation error. Expected: {:.2}, Got: {:.2}",
-            calculated_gross_profit, gross_profit
-        ));
+    let cogs = report.total_expenses - report.operating_expenses;
+    let calculated_gross_profit = report.total_revenue - cogs;
+    
+    if (report.gross_profit - calculated_gross_profit).abs() > tolerance {
+        return Err(ValidationError::new(
+            "CALCULATION_MISMATCH",
+            format!(
+                "❌ Gross profit calculation error. Expected: {:.2}, Got: {:.2}",
+                calculated_gross_profit, report.gross_profit
+            ),
+        )
+        .field("grossProfit")
+        .param("expected", calculated_gross_profit)
+        .param("actual", report.gross_profit)
+        .into());
     }
     
     Ok(())
 }
 
+/// Current UTC date from IC time
+pub fn ic_today() -> NaiveDate {
+    DateTime::from_timestamp_nanos(ic_cdk::api::time() as i64).date_naive()
+}
+
+/// Parses a `YYYY-MM-DD` report date
+pub fn parse_report_date(field: &str, value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, REPORT_DATE_FORMAT).map_err(|_| {
+        ValidationError::new(
+            "INVALID_DATE",
+            format!("❌ Invalid {}: '{}'. Expected format: YYYY-MM-DD", field, value),
+        )
+        .field(field)
+        .param("actual", value)
+        .into()
+    })
+}
+
+/// Last day of the calendar month or quarter starting on `start`
+/// Returns None when `start` is not the first day of such a period
+pub fn expected_period_end(reporting_period: &str, start: NaiveDate) -> Option<NaiveDate> {
+    let months = match reporting_period {
+        "monthly" => 1,
+        // Quarters start in January, April, July and October
+        "quarterly" if start.month0().is_multiple_of(3) => 3,
+        _ => return None,
+    };
+
+    if start.day() != 1 {
+        return None;
+    }
+
+    start.checked_add_months(Months::new(months))?.pred_opt()
+}
+
 /// Validates report period dates
-fn validate_report_period(data: &Value) -> Result<(), String> {
-    let period_start = data.get("periodStart")
-        .and_then(|v| v.as_str())
-        .ok_or("❌ Missing periodStart")?;
-    
-    let period_end = data.get("periodEnd")
-        .and_then(|v| v.as_str())
-        .ok_or("❌ Missing periodEnd")?;
+/// The period must be exactly one calendar month or quarter (matching reportingPeriod)
+/// and must not end after `today`
+fn validate_report_period(report: &RevenueReport, today: NaiveDate) -> Result<(), String> {
+    let period_start = parse_report_date("periodStart", &report.period_start)?;
+    let period_end = parse_report_date("periodEnd", &report.period_end)?;
     
     // Check that start is before end
     if period_start >= period_end {
-        return Err("❌ Period start date must be before end date".to_string());
+        return Err(ValidationError::new(
+            "INVALID_REPORT_PERIOD",
+            "❌ Period start date must be before end date",
+        )
+        .field("periodEnd")
+        .into());
     }
     
-    // Check that dates are not in the future (can't report future revenue)
-    // This is a simplified check - in production, use proper date parsing
-    let current_date = "2025-12-24"; // Would use actual current date
+    // Check that the dates cover exactly one calendar month / quarter
+    match expected_period_end(&report.reporting_period, period_start) {
+        Some(expected_end) if expected_end == period_end => {}
+        expected_end => {
+            let unit = if report.reporting_period == "quarterly" { "quarter" } else { "month" };
+            let mut error = ValidationError::new(
+                "INVALID_REPORT_PERIOD",
+                format!(
+                    "❌ A {} report must cover exactly one calendar {} ({} to {} is not)",
+                    report.reporting_period, unit, period_start, period_end
+                ),
+            )
+            .field("periodEnd")
+            .param("reportingPeriod", report.reporting_period.as_str());
+            if let Some(expected_end) = expected_end {
+                error = error.param("expectedEnd", expected_end.to_string());
+            }
+            return Err(error.into());
+        }
+    }
     
-    if period_end > current_date {
-        return Err("❌ Cannot submit report for future periods".to_string());
+    // Check that dates are not in the future (can't report future revenue)
+    if period_end > today {
+        return Err(ValidationError::new(
+            "FUTURE_REPORT_PERIOD",
+            "❌ Cannot submit report for future periods",
+        )
+        .field("periodEnd")
+        .param("periodEnd", period_end.to_string())
+        .param("today", today.to_string())
+        .into());
     }
     
     Ok(())
 }
 
 /// Calculates profit margin
-#[allow(dead_code)]
 pub fn calculate_profit_margin(revenue: f64, net_profit: f64) -> Result<f64, String> {
     if revenue <= 0.0 {
         return Err("Revenue must be positive to calculate margin".to_string());
@@ -231,50 +372,25 @@ pub fn calculate_profit_margin(revenue: f64, net_profit: f64) -> Result<f64, Str
     Ok((net_profit / revenue) * 100.0)
 }
 
-/// Flags suspicious revenue patterns
-#[allow(dead_code)]
-pub fn detect_suspicious_patterns(
-    current_revenue: f64,
-    previous_revenues: Vec<f64>,
-) -> Vec<String> {
-    let mut warnings = Vec::new();
-    
-    if previous_revenues.is_empty() {
-        return warnings;
-    }
-    
-    let avg_revenue: f64 = previous_revenues.iter().sum::<f64>() / previous_revenues.len() as f64;
-    
-    // Flag if current revenue is 200% higher than average
-    if current_revenue > avg_revenue * 2.0 {
-        warnings.push(format!(
-            "⚠️ Revenue spike detected: Current (₦{:.2}) is 2x higher than average (₦{:.2})",
-            current_revenue, avg_revenue
-        ));
-    }
-    
-    // Flag if current revenue drops by 50% or more
-    if let Some(last_revenue) = previous_revenues.last() {
-        if current_revenue < last_revenue * 0.5 {
-            warnings.push(format!(
-                "⚠️ Significant revenue drop: Down {:.0}% from last period",
-                ((last_revenue - current_revenue) / last_revenue) * 100.0
-            ));
-        }
-    }
-    
-    // Flag consistent negative profits
-    if current_revenue > 0.0 && previous_revenues.iter().all(|&r| r > 0.0) {
-        warnings.push("⚠️ Consistent losses may indicate business viability issues".to_string());
-    }
-    
-    warnings
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::json;
+    use crate::business_financing::models::decode_doc;
+    use serde_json::Value;
+    
+    fn report(financials: Value) -> RevenueReport {
+        let mut data = json!({
+            "applicationId": "app_1",
+            "reportingPeriod": "monthly",
+            "periodStart": "2025-01-01",
+            "periodEnd": "2025-01-31",
+            "documents": []
+        });
+        for (key, value) in financials.as_object().unwrap() {
+            data[key] = value.clone();
+        }
+        decode_doc("revenue_reports", &serde_json::to_vec(&data).unwrap()).unwrap()
+    }
     
     #[test]
     fn test_validate_calculations() {
@@ -286,7 +402,7 @@ mod tests {
             "operatingExpenses": 200000.0
         });
         
-        assert!(validate_financial_calculations(&data).is_ok());
+        assert!(validate_financial_calculations(&report(data)).is_ok());
     }
     
     #[test]
@@ -299,19 +415,96 @@ mod tests {
             "operatingExpenses": 200000.0
         });
         
-        assert!(validate_financial_calculations(&data).is_err());
+        assert!(validate_financial_calculations(&report(data)).is_err());
+    }
+    
+    fn date(value: &str) -> NaiveDate {
+        parse_report_date("test", value).unwrap()
+    }
+    
+    fn period(reporting_period: &str, start: &str, end: &str) -> RevenueReport {
+        let mut report = report(json!({
+            "totalRevenue": 0.0,
+            "totalExpenses": 0.0,
+            "grossProfit": 0.0,
+            "operatingExpenses": 0.0,
+            "netProfit": 0.0
+        }));
+        report.reporting_period = reporting_period.to_string();
+        report.period_start = start.to_string();
+        report.period_end = end.to_string();
+        report
     }
     
     #[test]
-    fn test_profit_margin() {
-        let margin = calculate_profit_margin(1000000.0, 300000.0).unwrap();
-        assert_eq!(margin, 30.0);
+    fn test_calendar_periods() {
+        let today = date("2026-03-15");
+        
+        assert!(validate_report_period(&period("monthly", "2026-02-01", "2026-02-28"), today).is_ok());
+        assert!(validate_report_period(&period("monthly", "2024-02-01", "2024-02-29"), today).is_ok());
+        assert!(validate_report_period(&period("quarterly", "2025-10-01", "2025-12-31"), today).is_ok());
+        
+        // Not aligned to a calendar month / quarter
+        assert!(validate_report_period(&period("monthly", "2026-01-15", "2026-02-14"), today).is_err());
+        assert!(validate_report_period(&period("monthly", "2026-01-01", "2026-01-30"), today).is_err());
+        assert!(validate_report_period(&period("quarterly", "2025-11-01", "2026-01-31"), today).is_err());
+        // Period type does not match the dates
+        assert!(validate_report_period(&period("quarterly", "2026-01-01", "2026-01-31"), today).is_err());
+        assert!(validate_report_period(&period("monthly", "2025-10-01", "2025-12-31"), today).is_err());
+    }
+    
+    #[test]
+    fn test_future_and_malformed_periods() {
+        let today = date("2026-03-15");
+        
+        let err = validate_report_period(&period("monthly", "2026-03-01", "2026-03-31"), today).unwrap_err();
+        assert_eq!(ValidationError::parse(&err).unwrap().code, "FUTURE_REPORT_PERIOD");
+        
+        let err = validate_report_period(&period("monthly", "2026-02-30", "2026-03-31"), today).unwrap_err();
+        assert_eq!(ValidationError::parse(&err).unwrap().code, "INVALID_DATE");
+    }
+    
+    fn filed(status: &str, start: &str, end: &str) -> (String, RevenueReport) {
+        let mut report = period("monthly", start, end);
+        report.status = status.to_string();
+        (format!("report_{}", start), report)
+    }
+    
+    #[test]
+    fn test_overlapping_periods_rejected() {
+        let existing = vec![filed("approved", "2026-01-01", "2026-01-31")];
+        
+        let err = validate_no_period_overlap(&period("monthly", "2026-01-01", "2026-01-31"), &existing).unwrap_err();
+        assert_eq!(ValidationError::parse(&err).unwrap().code, "OVERLAPPING_REPORT_PERIOD");
+        
+        let err = validate_no_period_overlap(&period("quarterly", "2026-01-01", "2026-03-31"), &existing).unwrap_err();
+        assert_eq!(ValidationError::parse(&err).unwrap().code, "OVERLAPPING_REPORT_PERIOD");
+        
+        assert!(validate_no_period_overlap(&period("monthly", "2026-02-01", "2026-02-28"), &existing).is_ok());
+    }
+    
+    #[test]
+    fn test_missing_periods_since_last_approved() {
+        let existing = vec![
+            filed("approved", "2025-11-01", "2025-11-30"),
+            filed("submitted", "2026-01-01", "2026-01-31"),
+        ];
+        
+        // December was never filed
+        let missing = find_missing_periods(&period("monthly", "2026-02-01", "2026-02-28"), &existing).unwrap();
+        assert_eq!(missing, vec![(date("2025-12-01"), date("2025-12-31"))]);
+        
+        let err = validate_no_missing_periods(&period("monthly", "2026-03-01", "2026-03-31"), &existing).unwrap_err();
+        assert_eq!(ValidationError::parse(&err).unwrap().code, "MISSING_REPORT_PERIODS");
+        
+        // Directly after the last approved report, or no approved report yet
+        assert!(find_missing_periods(&period("monthly", "2025-12-01", "2025-12-31"), &existing).unwrap().is_empty());
+        assert!(find_missing_periods(&period("monthly", "2026-02-01", "2026-02-28"), &existing[1..]).unwrap().is_empty());
     }
     
     #[test]
-    fn test_revenue_spike_detection() {
-        let previous = vec![100000.0, 110000.0, 105000.0];
-        let warnings = detect_suspicious_patterns(300000.0, previous);
-        assert!(!warnings.is_empty());
+    fn test_profit_margin() {
+        let margin = calculate_profit_margin(1000000.0, 300000.0).unwrap();
+        assert_eq!(margin, 30.0);
     }
 }
@@ -0,0 +1,343 @@
+use junobuild_satellite::{AssertDeleteDocContext, get_doc_store, id};
+use serde_json::Value;
+use super::admin_permissions::{has_sufficient_role, insufficient_role};
+use super::audit_log::parse_doc_json;
+use super::dispatch::SetDocCall;
+use super::models::RevenueReport;
+use super::revenue_report_history::{REVENUE_REPORT_REVISIONS_COLLECTION, diff_report_fields, reported_figures};
+use super::validation_error::ValidationError;
+
+const REVENUE_REPORTS_COLLECTION: &str = "revenue_reports";
+
+/// Revenue report status, mirroring `src/schemas/revenue.schema.ts`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportStatus {
+    Draft,
+    Submitted,
+    UnderReview,
+    Approved,
+    RevisionRequested,
+}
+
+impl ReportStatus {
+    pub fn parse(status: &str) -> Result<Self, String> {
+        match status {
+            "draft" => Ok(Self::Draft),
+            "submitted" => Ok(Self::Submitted),
+            "under_review" => Ok(Self::UnderReview),
+            "approved" => Ok(Self::Approved),
+            "revision_requested" => Ok(Self::RevisionRequested),
+            _ => Err(ValidationError::new(
+                "INVALID_STATUS",
+                format!("❌ Invalid status: {}", status),
+            )
+            .field("status")
+            .param("actual", status)
+            .into()),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Submitted => "submitted",
+            Self::UnderReview => "under_review",
+            Self::Approved => "approved",
+            Self::RevisionRequested => "revision_requested",
+        }
+    }
+
+    /// Statuses reachable from this one (approved is terminal)
+    pub fn allowed_transitions(&self) -> &'static [ReportStatus] {
+        use ReportStatus::*;
+        match self {
+            Draft => &[Submitted],
+            Submitted => &[UnderReview, Approved, RevisionRequested],
+            UnderReview => &[Approved, RevisionRequested],
+            Approved => &[],
+            RevisionRequested => &[Draft, Submitted],
+        }
+    }
+
+    /// Who may edit a report that stays in this status
+    fn editor(&self) -> ReportActor {
+        use ReportStatus::*;
+        match self {
+            Draft | RevisionRequested => ReportActor::Owner,
+            Submitted | UnderReview => ReportActor::Reviewer,
+            Approved => ReportActor::Nobody,
+        }
+    }
+}
+
+/// Who is allowed to write a report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportActor {
+    /// The business that owns the report
+    Owner,
+    /// An active admin of reviewer level or above
+    Reviewer,
+    /// Approved reports are immutable
+    Nobody,
+}
+
+/// Returns the actor required for `from -> to`, or an error if the transition is illegal
+pub fn required_actor(from: ReportStatus, to: ReportStatus) -> Result<ReportActor, String> {
+    use ReportStatus::*;
+
+    if from == to {
+        return Ok(from.editor());
+    }
+
+    if !from.allowed_transitions().contains(&to) {
+        return Err(ValidationError::new(
+            "INVALID_STATUS_TRANSITION",
+            format!(
+                "❌ Invalid status transition: '{}' → '{}' is not allowed",
+                from.as_str(),
+                to.as_str()
+            ),
+        )
+        .field("status")
+        .param("from", from.as_str())
+        .param("to", to.as_str())
+        .into());
+    }
+
+    Ok(match to {
+        Draft | Submitted => ReportActor::Owner,
+        UnderReview | Approved | RevisionRequested => ReportActor::Reviewer,
+    })
+}
+
+/// Reported figures a write changes, as dotted paths (workflow and derived fields excluded)
+pub fn changed_figures(current: &Value, proposed: &Value) -> Vec<String> {
+    match diff_report_fields(&reported_figures(current), &reported_figures(proposed)) {
+        Value::Object(changes) => changes.keys().cloned().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Reviewers record their decision; the figures stay as the business filed them
+fn assert_figures_frozen(call: &SetDocCall<RevenueReport>, current_data: &[u8]) -> Result<(), String> {
+    let changed = changed_figures(&parse_doc_json(current_data)?, &parse_doc_json(&call.context.data.data.proposed.data)?);
+    let Some(first) = changed.first() else {
+        return Ok(());
+    };
+
+    Err(ValidationError::new(
+        "REPORT_FIGURES_FROZEN",
+        format!(
+            "❌ Reviewers can only change the status and review notes of a revenue report, not {}",
+            changed.join(", ")
+        ),
+    )
+    .field(first)
+    .param("fields", changed.clone())
+    .critical()
+    .into())
+}
+
+/// New reports are filed by the business that owns the application they report on
+fn assert_application_owner(call: &SetDocCall<RevenueReport>) -> Result<(), String> {
+    let application_id = &call.proposed.application_id;
+    let Some(application) = get_doc_store(id(), "business_applications".to_string(), application_id.clone())? else {
+        return Err(ValidationError::new(
+            "APPLICATION_NOT_FOUND",
+            format!("❌ Application '{}' does not exist", application_id),
+        )
+        .field("applicationId")
+        .into());
+    };
+
+    if application.owner != call.context.caller {
+        return Err(ValidationError::new(
+            "NOT_DOCUMENT_OWNER",
+            "❌ Access Denied: Only the business that owns the application can file revenue reports for it",
+        )
+        .field("applicationId")
+        .critical()
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Enforces the revenue report review workflow
+/// Only the owning business files a report, only reviewers (or above) decide on it
+/// without touching its figures, and an approved report can no longer be changed by
+/// anyone but the satellite
+pub fn assert_revenue_report_transition(call: &SetDocCall<RevenueReport>) -> Result<(), String> {
+    let proposed = &call.proposed;
+    let to = ReportStatus::parse(&proposed.status)?;
+
+    let (Some(current), Some(current_doc)) = (&call.current, &call.context.data.data.current) else {
+        // New reports are created by the business itself (Juno makes the caller the owner)
+        if !matches!(to, ReportStatus::Draft | ReportStatus::Submitted) {
+            return Err(ValidationError::new(
+                "INVALID_STATUS_TRANSITION",
+                format!(
+                    "❌ Invalid status: new revenue reports must be 'draft' or 'submitted', not '{}'",
+                    to.as_str()
+                ),
+            )
+            .field("status")
+            .param("to", to.as_str())
+            .into());
+        }
+        return assert_application_owner(call);
+    };
+    let from = ReportStatus::parse(&current.status)?;
+
+    let actor = required_actor(from, to)?;
+
+    // Writes issued by the satellite itself (derived anomaly fields) are trusted
+    if call.is_satellite() {
+        return Ok(());
+    }
+
+    match actor {
+        ReportActor::Nobody => {
+            return Err(ValidationError::new(
+                "REPORT_IMMUTABLE",
+                "❌ Approved revenue reports are immutable",
+            )
+            .param("status", from.as_str())
+            .critical()
+            .into());
+        }
+        ReportActor::Owner => {
+            if current_doc.owner != call.context.caller {
+                return Err(ValidationError::new(
+                    "NOT_DOCUMENT_OWNER",
+                    "❌ Access Denied: Only the business that owns this report can edit or submit it",
+                )
+                .critical()
+                .into());
+            }
+        }
+        ReportActor::Reviewer => {
+            let admin_profile = call.caller_admin_profile()?;
+            if !has_sufficient_role(&admin_profile.role, "reviewer") {
+                return Err(insufficient_role(
+                    format!(
+                        "❌ Access Denied: Role '{}' cannot review revenue reports. Required: 'reviewer' or higher",
+                        admin_profile.role
+                    ),
+                    &admin_profile.role,
+                    "reviewer",
+                )
+                .field("status")
+                .into());
+            }
+            assert_figures_frozen(call, &current_doc.data)?;
+        }
+    }
+
+    if from == to || actor != ReportActor::Reviewer {
+        return Ok(());
+    }
+
+    // Review decisions are attributed to the admin making them
+    let caller = call.caller();
+    if proposed.reviewed_by.as_deref() != Some(caller.as_str()) {
+        return Err(ValidationError::new(
+            "CALLER_MISMATCH",
+            "❌ reviewedBy must be the admin recording the review decision",
+        )
+        .field("reviewedBy")
+        .critical()
+        .into());
+    }
+
+    let (notes_field, notes) = match to {
+        ReportStatus::Approved => ("reviewNotes", proposed.review_notes.as_deref()),
+        ReportStatus::RevisionRequested => ("revisionNotes", proposed.revision_notes.as_deref()),
+        _ => return Ok(()),
+    };
+    if notes.is_none_or(|notes| notes.trim().is_empty()) {
+        return Err(ValidationError::new(
+            "MISSING_FIELD",
+            format!("❌ {} is required when moving a revenue report to '{}'", notes_field, to.as_str()),
+        )
+        .field(notes_field)
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Filed reports and their revision history are the basis of profit distributions - never deleted
+pub fn assert_revenue_report_delete(context: &AssertDeleteDocContext) -> Result<(), String> {
+    let collection = context.data.collection.as_str();
+    if collection != REVENUE_REPORTS_COLLECTION && collection != REVENUE_REPORT_REVISIONS_COLLECTION {
+        return Ok(());
+    }
+
+    Err(ValidationError::new(
+        "DELETE_FORBIDDEN",
+        format!("❌ Security violation: {} '{}' cannot be deleted", collection, context.data.key),
+    )
+    .param("collection", collection)
+    .critical()
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ReportStatus::*;
+
+    #[test]
+    fn test_owner_files_and_reviewer_decides() {
+        assert_eq!(required_actor(Draft, Submitted), Ok(ReportActor::Owner));
+        assert_eq!(required_actor(RevisionRequested, Submitted), Ok(ReportActor::Owner));
+        assert_eq!(required_actor(Submitted, Approved), Ok(ReportActor::Reviewer));
+        assert_eq!(required_actor(UnderReview, RevisionRequested), Ok(ReportActor::Reviewer));
+
+        // Edits without a status change
+        assert_eq!(required_actor(Draft, Draft), Ok(ReportActor::Owner));
+        assert_eq!(required_actor(UnderReview, UnderReview), Ok(ReportActor::Reviewer));
+    }
+
+    #[test]
+    fn test_approved_reports_are_immutable() {
+        assert_eq!(required_actor(Approved, Approved), Ok(ReportActor::Nobody));
+        assert!(required_actor(Approved, RevisionRequested).is_err());
+        assert!(required_actor(Approved, Draft).is_err());
+    }
+
+    #[test]
+    fn test_reviewers_cannot_change_figures() {
+        let filed = serde_json::json!({
+            "status": "submitted",
+            "netProfit": 400_000.0,
+            "mudarabaDetails": { "profitSharingRatio": 60.0 }
+        });
+
+        let decided = serde_json::json!({
+            "status": "approved",
+            "reviewedBy": "admin",
+            "reviewNotes": "Reconciled with bank statements",
+            "netProfit": 400_000.0,
+            "mudarabaDetails": { "profitSharingRatio": 60.0 }
+        });
+        assert!(changed_figures(&filed, &decided).is_empty());
+
+        let mut inflated = decided.clone();
+        inflated["netProfit"] = serde_json::json!(900_000.0);
+        inflated["mudarabaDetails"]["profitSharingRatio"] = serde_json::json!(90.0);
+        assert_eq!(
+            changed_figures(&filed, &inflated),
+            vec!["mudarabaDetails.profitSharingRatio".to_string(), "netProfit".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_review_cannot_be_skipped() {
+        assert!(required_actor(Draft, Approved).is_err());
+        assert!(required_actor(Draft, UnderReview).is_err());
+        assert!(required_actor(RevisionRequested, Approved).is_err());
+        assert!(ReportStatus::parse("rejected").is_err());
+    }
+}
@@ -0,0 +1,138 @@
+// Structured validation errors
+//
+// Juno hooks can only reject with a string, so every rejection is serialized as a
+// JSON payload carrying a stable `code`, the offending `field`, the `params` needed
+// to render the message (limits, actual values) and a `severity`. `message` keeps the
+// English text for logs and for clients that do not localize yet.
+// The frontend counterpart is `src/utils/validation-error.ts`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// How serious a rejection is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Ordinary input error the user can fix
+    Error,
+    /// Security or compliance violation (tampering, privilege escalation, AML)
+    Critical,
+}
+
+/// Machine-readable rejection returned by the satellite hooks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidationError {
+    pub code: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    #[serde(default)]
+    pub params: Map<String, Value>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Code used for rejections that have not been given a dedicated code
+pub const GENERIC_CODE: &str = "VALIDATION_FAILED";
+
+impl ValidationError {
+    pub fn new(code: &str, message: impl Into<String>) -> Self {
+        Self {
+            code: code.to_string(),
+            field: None,
+            params: Map::new(),
+            severity: Severity::Error,
+            message: message.into(),
+        }
+    }
+
+    pub fn field(mut self, field: &str) -> Self {
+        self.field = Some(field.to_string());
+        self
+    }
+
+    pub fn param(mut self, name: &str, value: impl Into<Value>) -> Self {
+        self.params.insert(name.to_string(), value.into());
+        self
+    }
+
+    pub fn critical(mut self) -> Self {
+        self.severity = Severity::Critical;
+        self
+    }
+
+    /// Wraps a legacy prose error so it still reaches the client as JSON
+    pub fn from_message(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        let error = Self::new(GENERIC_CODE, message);
+
+        if lower.contains("security violation") || lower.contains("compliance violation") {
+            error.critical()
+        } else {
+            error
+        }
+    }
+
+    /// Parses a rejection produced by `to_json`
+    pub fn parse(rejection: &str) -> Option<Self> {
+        serde_json::from_str(rejection).ok()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| {
+            format!(r#"{{"code":"{}","params":{{}},"severity":"error","message":""}}"#, GENERIC_CODE)
+        })
+    }
+}
+
+impl From<ValidationError> for String {
+    fn from(error: ValidationError) -> Self {
+        error.to_json()
+    }
+}
+
+/// Normalizes a hook rejection into the JSON payload
+/// Structured errors pass through unchanged; prose errors are wrapped under `VALIDATION_FAILED`
+pub fn to_rejection(error: String) -> String {
+    if ValidationError::parse(&error).is_some() {
+        return error;
+    }
+
+    ValidationError::from_message(&error).to_json()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_code_field_params_and_severity() {
+        let error = ValidationError::new("APPROVAL_LIMIT_EXCEEDED", "❌ Approval Denied")
+            .field("requestedAmount")
+            .param("limit", 5_000_000.0)
+            .param("actual", 7_500_000.0);
+
+        let json: Value = serde_json::from_str(&String::from(error)).unwrap();
+        assert_eq!(json["code"], "APPROVAL_LIMIT_EXCEEDED");
+        assert_eq!(json["field"], "requestedAmount");
+        assert_eq!(json["params"]["limit"], 5_000_000.0);
+        assert_eq!(json["severity"], "error");
+    }
+
+    #[test]
+    fn test_structured_errors_pass_through() {
+        let error = ValidationError::new("INSUFFICIENT_ROLE", "❌ Access Denied").critical();
+        let rejection = to_rejection(error.clone().into());
+
+        assert_eq!(ValidationError::parse(&rejection), Some(error));
+    }
+
+    #[test]
+    fn test_prose_errors_are_wrapped() {
+        let rejection = to_rejection("❌ Security violation: tampered field".to_string());
+        let error = ValidationError::parse(&rejection).unwrap();
+
+        assert_eq!(error.code, GENERIC_CODE);
+        assert_eq!(error.severity, Severity::Critical);
+        assert_eq!(error.message, "❌ Security violation: tampered field");
+    }
+}
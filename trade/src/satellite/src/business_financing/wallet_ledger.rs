@@ -0,0 +1,420 @@
+// Satellite-side wallet movements
+//
+// The satellite settles investments: a new investment is checked against the wallet's
+// available balance minus pending withdrawals, and the same call debits the wallet and
+// records the `investment` transaction. Refunds credit the wallet back. Transactions use
+// the shape of `src/schemas/transaction.schema.ts` and are keyed by the investment
+// (`investment_{key}`, `refund_{key}`), so neither movement is ever applied twice.
+//
+// Wallets are keyed by the user id. Clients never move money themselves: balances,
+// wallet status and transactions are written by the satellite or by an approving admin
+// (deposit and withdrawal approvals, profit payouts), and members can only file
+// `pending` deposit and withdrawal requests.
+
+use junobuild_satellite::{SetDoc, get_doc_store, id, set_doc_store};
+use junobuild_utils::encode_doc_data;
+use serde_json::{json, Value};
+use super::admin_permissions::{has_sufficient_role, insufficient_role};
+use super::audit_log::parse_doc_json;
+use super::dispatch::SetDocCall;
+use super::doc_index::list_indexed;
+use super::models::{Investment, Wallet, WithdrawalRequest, decode_stored};
+use super::validation_error::ValidationError;
+
+/// Withdrawal request statuses whose amount is still held in the wallet
+pub const HOLDING_WITHDRAWAL_STATUSES: [&str; 2] = ["pending", "processing"];
+
+/// Wallet fields only the satellite or an approving admin may change
+pub const LEDGER_FIELDS: [&str; 7] = [
+    "availableBalance",
+    "pendingBalance",
+    "totalBalance",
+    "totalInvested",
+    "totalReturns",
+    "currency",
+    "status",
+];
+
+/// Lowest admin role that may move money (approve deposits and withdrawals, pay out profits)
+const LEDGER_ROLE: &str = "approver";
+
+/// Ledger fields whose value differs between the stored and proposed wallet
+/// A new wallet is compared against an empty, active NGN wallet
+pub fn changed_ledger_fields(current: Option<&Value>, proposed: &Value) -> Vec<&'static str> {
+    LEDGER_FIELDS
+        .into_iter()
+        .filter(|field| {
+            let before = current.map(|wallet| &wallet[*field]);
+            let after = &proposed[*field];
+            match *field {
+                "status" => ledger_str(before, "active") != ledger_str(Some(after), "active"),
+                "currency" => ledger_str(before, "NGN") != ledger_str(Some(after), "NGN"),
+                _ => {
+                    let amount = |value: Option<&Value>| value.and_then(Value::as_f64).unwrap_or(0.0);
+                    amount(before) != amount(Some(after))
+                }
+            }
+        })
+        .collect()
+}
+
+fn ledger_str<'a>(value: Option<&'a Value>, default: &'a str) -> &'a str {
+    value.and_then(Value::as_str).unwrap_or(default)
+}
+
+/// Whether the caller is an active admin allowed to move money
+/// Non-admins get `Ok(false)`; admins below the ledger role are rejected
+fn caller_is_ledger_admin<T>(call: &SetDocCall<T>, action: &str) -> Result<bool, String> {
+    let Ok(admin_profile) = call.caller_admin_profile() else {
+        return Ok(false);
+    };
+    if !has_sufficient_role(&admin_profile.role, LEDGER_ROLE) {
+        return Err(insufficient_role(
+            format!(
+                "❌ Access Denied: Role '{}' cannot {}. Required: '{}' or higher",
+                admin_profile.role, action, LEDGER_ROLE
+            ),
+            &admin_profile.role,
+            LEDGER_ROLE,
+        )
+        .into());
+    }
+    Ok(true)
+}
+
+fn ledger_protected(message: &str, field: Option<&str>) -> String {
+    let error = ValidationError::new("LEDGER_PROTECTED", message).critical();
+    match field {
+        Some(field) => error.field(field).into(),
+        None => error.into(),
+    }
+}
+
+/// Client writes to wallets: keyed by the owner, balances and status untouched
+pub fn assert_wallet_write(call: &SetDocCall<Value>) -> Result<(), String> {
+    let proposed = call.proposed_json()?;
+    let user_id = proposed["userId"].as_str().unwrap_or_default();
+
+    if user_id != call.key() {
+        return Err(ValidationError::new(
+            "KEY_MISMATCH",
+            "❌ Wallets must be keyed by the owner's user id",
+        )
+        .field("userId")
+        .param("key", call.key())
+        .param("actual", user_id)
+        .into());
+    }
+
+    let changed = changed_ledger_fields(call.current_json()?, proposed);
+    if let Some(field) = changed.first() {
+        if !caller_is_ledger_admin(call, "change wallet balances")? {
+            return Err(ledger_protected(
+                "❌ Access Denied: Wallet balances and status are changed by the satellite or an approving admin only",
+                Some(field),
+            ));
+        }
+        return Ok(());
+    }
+
+    if user_id != call.caller() && call.caller_admin_profile().is_err() {
+        return Err(ValidationError::new(
+            "NOT_DOCUMENT_OWNER",
+            "❌ Access Denied: You can only update your own wallet",
+        )
+        .critical()
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Transactions are the ledger itself: only the satellite and approving admins record them
+pub fn assert_transaction_write(call: &SetDocCall<Value>) -> Result<(), String> {
+    if !caller_is_ledger_admin(call, "record transactions")? {
+        return Err(ledger_protected(
+            "❌ Access Denied: Transactions are recorded by the satellite or an approving admin only",
+            None,
+        ));
+    }
+    Ok(())
+}
+
+/// Deposit and withdrawal requests: members file their own `pending` request and may
+/// cancel a pending withdrawal; every other decision belongs to an approving admin
+pub fn assert_funds_request_write<T>(call: &SetDocCall<T>) -> Result<(), String> {
+    if caller_is_ledger_admin(call, "decide deposit and withdrawal requests")? {
+        return Ok(());
+    }
+
+    let proposed = call.proposed_json()?;
+    if proposed["userId"].as_str() != Some(call.caller().as_str()) {
+        return Err(ValidationError::new(
+            "NOT_DOCUMENT_OWNER",
+            "❌ Access Denied: Requests can only be filed for your own wallet",
+        )
+        .field("userId")
+        .critical()
+        .into());
+    }
+
+    let status = ledger_str(Some(&proposed["status"]), "pending");
+    let Some(current) = call.current_json()? else {
+        if status != "pending" {
+            return Err(ledger_protected("❌ New requests must be filed as 'pending'", Some("status")));
+        }
+        return Ok(());
+    };
+
+    let before = ledger_str(Some(&current["status"]), "pending");
+    let cancelling = call.collection() == "withdrawal_requests" && before == "pending" && status == "cancelled";
+    if status != before && !cancelling {
+        return Err(ledger_protected(
+            "❌ Access Denied: Requests are approved or rejected by an admin only",
+            Some("status"),
+        ));
+    }
+    if current["amount"].as_f64() != proposed["amount"].as_f64() {
+        return Err(ledger_protected("❌ The amount of a filed request cannot be changed", Some("amount")));
+    }
+
+    Ok(())
+}
+
+/// Checks that an active wallet can fund `amount` once `held` funds are set aside
+pub fn validate_wallet_debit(wallet: Option<&Wallet>, held: f64, amount: f64) -> Result<(), String> {
+    let Some(wallet) = wallet else {
+        return Err(ValidationError::new("WALLET_NOT_FOUND", "❌ Wallet not found. Please deposit funds first")
+            .field("amount")
+            .into());
+    };
+
+    if wallet.status != "active" {
+        return Err(ValidationError::new(
+            "WALLET_INACTIVE",
+            format!("❌ Your wallet is {} and cannot fund investments", wallet.status),
+        )
+        .param("status", wallet.status.as_str())
+        .into());
+    }
+
+    let spendable = wallet.available_balance - held;
+    if amount > spendable {
+        return Err(ValidationError::new(
+            "INSUFFICIENT_BALANCE",
+            format!(
+                "❌ Insufficient balance: ₦{:.2} available after ₦{:.2} of pending withdrawals, ₦{:.2} required",
+                spendable.max(0.0),
+                held,
+                amount
+            ),
+        )
+        .field("amount")
+        .param("available", wallet.available_balance)
+        .param("held", held)
+        .param("actual", amount)
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Debits the wallet for a new investment and records its `investment` transaction
+/// Must be the last rule of the investments route: a rejected write traps and rolls
+/// these writes back, an accepted one commits them with the investment itself
+pub fn settle_investment(call: &SetDocCall<Investment>) -> Result<(), String> {
+    if call.current.is_some() {
+        return Ok(());
+    }
+    let investment = &call.proposed;
+    let investor_id = &investment.investor_id;
+
+    let wallet_doc = get_doc_store(id(), "wallets".to_string(), investor_id.clone())?;
+    let wallet: Option<Wallet> = decode_stored("wallets", wallet_doc.as_ref())?;
+
+    let held: f64 = list_indexed::<WithdrawalRequest>("withdrawal_requests", "user", investor_id)?
+        .into_iter()
+        .map(|(_, _, request)| request)
+        .filter(|request| HOLDING_WITHDRAWAL_STATUSES.contains(&request.status.as_str()))
+        .map(|request| request.amount)
+        .sum();
+
+    validate_wallet_debit(wallet.as_ref(), held, investment.amount)?;
+
+    adjust_wallet(
+        investor_id,
+        &[
+            ("availableBalance", -investment.amount),
+            ("totalBalance", -investment.amount),
+            ("totalInvested", investment.amount),
+        ],
+    )?;
+
+    let proposed = call.proposed_json()?;
+    let business_name = proposed["businessName"].as_str().unwrap_or("an opportunity");
+    let now = ic_cdk::api::time() / 1_000_000;
+
+    set_doc_store(
+        id(),
+        "transactions".to_string(),
+        format!("investment_{}", call.key()),
+        SetDoc {
+            data: encode_doc_data(&json!({
+                "userId": investor_id,
+                "type": "investment",
+                "status": "completed",
+                "amount": investment.amount,
+                "currency": "NGN",
+                "reference": call.key(),
+                "description": format!("Investment in {}", business_name),
+                "investmentId": call.key(),
+                "opportunityId": investment.opportunity_id,
+                "createdAt": now,
+                "completedAt": now,
+                "metadata": {
+                    "businessName": proposed["businessName"],
+                    "contractType": proposed["contractType"],
+                },
+            }))?,
+            description: None,
+            version: None,
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Key of the refund transaction of an investment
+pub fn refund_transaction_key(investment_key: &str) -> String {
+    format!("refund_{}", investment_key)
+}
+
+/// Credits `investment.amount` back to the investor's wallet and records the refund
+/// Returns the refund transaction key, or None when the investment was already refunded.
+/// Without a wallet the transaction is left `pending` for manual handling.
+pub fn refund_investment_to_wallet(
+    investment_key: &str,
+    investment: &Investment,
+    opportunity_key: &str,
+    description: &str,
+    metadata: Value,
+) -> Result<Option<String>, String> {
+    let investor_id = &investment.investor_id;
+    let now = ic_cdk::api::time() / 1_000_000;
+
+    let transaction_key = refund_transaction_key(investment_key);
+    if get_doc_store(id(), "transactions".to_string(), transaction_key.clone())?.is_some() {
+        return Ok(None);
+    }
+
+    let credited = adjust_wallet(
+        investor_id,
+        &[
+            ("availableBalance", investment.amount),
+            ("totalBalance", investment.amount),
+            ("totalInvested", -investment.amount),
+        ],
+    )?;
+    if !credited {
+        ic_cdk::println!("No wallet for investor {} - refund of {} left pending", investor_id, investment_key);
+    }
+
+    set_doc_store(
+        id(),
+        "transactions".to_string(),
+        transaction_key.clone(),
+        SetDoc {
+            data: encode_doc_data(&json!({
+                "userId": investor_id,
+                "type": "refund",
+                "status": if credited { "completed" } else { "pending" },
+                "amount": investment.amount,
+                "currency": "NGN",
+                "reference": investment_key,
+                "description": description,
+                "investmentId": investment_key,
+                "opportunityId": opportunity_key,
+                "createdAt": now,
+                "completedAt": if credited { Some(now) } else { None },
+                "metadata": metadata,
+            }))?,
+            description: None,
+            version: None,
+        },
+    )?;
+
+    Ok(Some(transaction_key))
+}
+
+/// Adds each delta to the wallet's balance fields; returns false when the wallet does not exist
+pub fn adjust_wallet(user_id: &str, deltas: &[(&str, f64)]) -> Result<bool, String> {
+    let Some(wallet_doc) = get_doc_store(id(), "wallets".to_string(), user_id.to_string())? else {
+        return Ok(false);
+    };
+    let now = ic_cdk::api::time() / 1_000_000;
+
+    let mut data = parse_doc_json(&wallet_doc.data)?;
+    for (field, delta) in deltas {
+        data[*field] = Value::from(data[*field].as_f64().unwrap_or(0.0) + delta);
+    }
+    data["updatedAt"] = Value::from(now);
+    data["lastTransactionAt"] = Value::from(now);
+
+    set_doc_store(
+        id(),
+        "wallets".to_string(),
+        user_id.to_string(),
+        SetDoc {
+            data: encode_doc_data(&data)?,
+            description: wallet_doc.description.clone(),
+            version: wallet_doc.version,
+        },
+    )?;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::business_financing::models::decode_doc;
+
+    fn wallet(available_balance: f64, status: &str) -> Wallet {
+        decode_doc("wallets", &serde_json::to_vec(&json!({
+            "userId": "investor_1",
+            "availableBalance": available_balance,
+            "totalBalance": available_balance,
+            "status": status
+        })).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_pending_withdrawals_are_held() {
+        let funded = wallet(500_000.0, "active");
+
+        assert!(validate_wallet_debit(Some(&funded), 0.0, 500_000.0).is_ok());
+        assert!(validate_wallet_debit(Some(&funded), 100_000.0, 450_000.0).is_err());
+        assert!(validate_wallet_debit(Some(&funded), 100_000.0, 400_000.0).is_ok());
+    }
+
+    #[test]
+    fn test_changed_ledger_fields() {
+        let stored = json!({ "userId": "investor_1", "availableBalance": 1000, "totalBalance": 1000.0 });
+
+        // Integer and float encodings of the same amount are not a change
+        assert!(changed_ledger_fields(Some(&stored), &json!({ "userId": "investor_1", "availableBalance": 1000.0, "totalBalance": 1000 })).is_empty());
+        assert_eq!(
+            changed_ledger_fields(Some(&stored), &json!({ "availableBalance": 5000.0, "totalBalance": 1000.0, "status": "active" })),
+            vec!["availableBalance"]
+        );
+        // New wallets start empty and active
+        assert!(changed_ledger_fields(None, &json!({ "availableBalance": 0, "status": "active", "currency": "NGN" })).is_empty());
+        assert_eq!(changed_ledger_fields(None, &json!({ "totalInvested": 1.0 })), vec!["totalInvested"]);
+    }
+
+    #[test]
+    fn test_missing_or_inactive_wallet_cannot_fund() {
+        assert!(validate_wallet_debit(None, 0.0, 1.0).is_err());
+        assert!(validate_wallet_debit(Some(&wallet(500_000.0, "suspended")), 0.0, 1.0).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use junobuild_macros::{
     assert_delete_asset, assert_delete_doc, assert_set_doc, assert_upload_asset, on_delete_asset,
     on_delete_doc, on_delete_filtered_assets, on_delete_filtered_docs, on_delete_many_assets,
-    on_delete_many_docs, on_set_doc, on_set_many_docs, on_upload_asset,
+    on_delete_many_docs, on_init, on_post_upgrade, on_set_doc, on_set_many_docs, on_upload_asset,
 };
 use junobuild_satellite::{
     include_satellite, AssertDeleteAssetContext, AssertDeleteDocContext, AssertSetDocContext,
@@ -14,29 +14,21 @@ use junobuild_satellite::{
 mod business_financing;
 
 use business_financing::{
-    business_application_validation::assert_business_application_approval,
+    dispatch::assert_set_doc_rules,
+    doc_index::backfill_indexes,
+    reporting_schedule::{start_reporting_timer, sync_reporting_schedule},
+    funding_deadline::{refund_cancelled_opportunity, start_funding_deadline_timer},
+    investment_lifecycle::{record_investment_cancellation, start_cooling_off_timer},
+    revenue_anomaly::sync_revenue_anomalies,
+    revenue_report_history::record_revenue_report_revision,
+    revenue_report_workflow::assert_revenue_report_delete,
+    profit_distribution::draft_profit_distribution,
+    opportunity_funding::sync_opportunity_funding,
     document_validation::assert_document_upload,
-    financial_data_validation::validate_revenue_report,
-    investment_opportunity_validation::assert_investment_opportunity_creation,
-    revenue_report_validation::validate_revenue_report_submission,
-    access_control::assert_admin_only_operation,
-    platform_message_validation::validate_platform_message,
-    member_validation::{
-        assert_member_number_uniqueness,
-        assert_immutable_fields,
-        assert_kyc_verified_for_investment,
-        assert_investment_limits,
-        assert_corporate_ubo_compliance,
-    },
-    admin_permissions::{
-        assert_admin_approval_with_limits,
-        validate_separation_of_duties,
-        assert_can_manage_admins,
-        assert_can_distribute_profits,
-        assert_can_deactivate_admin,
-        assert_can_delete_admin,
-        assert_can_update_permissions,
-    },
+    audit_log::{self, AuditChainReport, assert_audit_log_delete},
+    admin_permissions::assert_can_delete_admin,
+    concentration_limits::assert_concentration_exception_delete,
+    validation_error::to_rejection,
 };
 
 // All the available hooks and assertions for your Datastore and Storage are scaffolded by default in this `lib.rs` module.
@@ -53,15 +45,74 @@ use business_financing::{
 // With this setup, only `on_set_doc` must be implemented with custom logic,
 // and other hooks and assertions can be removed. They will not be included in your Satellite.
 
+/// A side effect of a document write, run by on_set_doc
+type SetDocEffect = fn(&OnSetDocContext) -> Result<(), String>;
+
 #[on_set_doc]
 async fn on_set_doc(context: OnSetDocContext) -> Result<(), String> {
-    // Validate revenue reports on submission
-    validate_revenue_report(&context).await?;
-    validate_revenue_report_submission(&context).await?;
+    // Side effects only - every rule that can reject a write lives in assert_set_doc
+    // Each effect runs on its own: one failing must not skip the others
+    let effects: [(&str, SetDocEffect); 7] = [
+        // Cancelled investments (refunded in assert_set_doc) record their refund and notify the investor
+        ("record_investment_cancellation", record_investment_cancellation),
+        
+        // Recount opportunity funding from committed investments (active -> funded at goal)
+        ("sync_opportunity_funding", sync_opportunity_funding),
+        
+        // Cancelled opportunities refund every committed investment to the investors' wallets
+        ("refund_cancelled_opportunity", refund_cancelled_opportunity),
+        
+        // Reporting calendar on funding, filed reports tick off their period
+        ("sync_reporting_schedule", sync_reporting_schedule),
+        
+        // Score filed revenue reports against the application's approved history
+        ("sync_revenue_anomalies", sync_revenue_anomalies),
+        
+        // Snapshot every submitted report version with a diff against the previous one
+        ("record_revenue_report_revision", record_revenue_report_revision),
+        
+        // Approved reports produce a draft profit distribution for managers to confirm
+        ("draft_profit_distribution", draft_profit_distribution),
+    ];
+    
+    for (name, effect) in effects {
+        if let Err(error) = effect(&context) {
+            ic_cdk::println!(
+                "{} failed for {} '{}': {}",
+                name,
+                context.data.collection,
+                context.data.key,
+                error
+            );
+        }
+    }
     
     Ok(())
 }
 
+// Canister timers are dropped on upgrade - re-arm them on both lifecycle events
+#[on_init]
+fn on_init() {
+    // Revenue Reporting - Overdue tracking and escalation sweep
+    start_reporting_timer();
+    
+    // Funding Deadlines - Close expired campaigns, refund cancelled ones
+    start_funding_deadline_timer();
+    
+    // Investment Cooling-off - Confirm pending investments once the window elapses
+    start_cooling_off_timer();
+}
+
+#[on_post_upgrade]
+fn on_post_upgrade() {
+    // Description Indexes - Index documents stored before their collection was indexed
+    backfill_indexes();
+    
+    start_reporting_timer();
+    start_funding_deadline_timer();
+    start_cooling_off_timer();
+}
+
 #[on_set_many_docs]
 async fn on_set_many_docs(_context: OnSetManyDocsContext) -> Result<(), String> {
     Ok(())
@@ -102,64 +153,36 @@ async fn on_delete_filtered_assets(_context: OnDeleteFilteredAssetsContext) -> R
     Ok(())
 }
 
+/// Re-walks the hash-chained admin audit trail (one day, or all days when omitted)
+/// and reports the first broken link - evidence that audit history was never rewritten
+#[ic_cdk::query]
+fn verify_audit_chain(day: Option<String>) -> Result<AuditChainReport, String> {
+    audit_log::assert_can_verify_audit_chain().map_err(to_rejection)?;
+    
+    audit_log::verify_audit_chain(day).map_err(to_rejection)
+}
+
 #[assert_set_doc]
 fn assert_set_doc(context: AssertSetDocContext) -> Result<(), String> {
-    // CRITICAL GATEKEEPERS - Enforce business rules before data is stored
-    
-    // 1. Business Application Approval - Must have 100% due diligence
-    assert_business_application_approval(&context)?;
-    
-    // 2. Admin Approval Limits - Validate amount-based authorization
-    assert_admin_approval_with_limits(&context)?;
-    
-    // 3. Separation of Duties - Reviewer cannot approve own review
-    validate_separation_of_duties(&context)?;
-    
-    // 4. Investment Opportunity Creation - Only from approved applications
-    assert_investment_opportunity_creation(&context)?;
-    
-    // 5. Platform Message Validation - Ensure message integrity and prevent abuse
-    validate_platform_message(&context)?;
-    
-    // 6. Admin-Only Operations - Prevent unauthorized privilege escalation
-    assert_admin_only_operation(&context)?;
-    
-    // 7. Admin Profile Management - Only managers can manage admins
-    assert_can_manage_admins(&context)?;
-    
-    // 8. Admin Deactivation/Reactivation - Managers can toggle active status
-    assert_can_deactivate_admin(&context)?;
-    
-    // 9. Admin Permission Updates - Enforce hierarchy when changing limits/roles
-    assert_can_update_permissions(&context)?;
-    
-    // 10. Profit Distribution - Only managers can distribute profits
-    assert_can_distribute_profits(&context)?;
-    
-    // 11. Member Number Uniqueness - Prevent duplicate membership numbers
-    assert_member_number_uniqueness(&context)?;
-    
-    // === MEMBER/INVESTOR CORE VALIDATIONS ===
-    
-    // 12. Profile Update Restrictions - Enforce immutable fields
-    assert_immutable_fields(&context)?;
-    
-    // 13. Investment Authorization - Only verified KYC can invest
-    assert_kyc_verified_for_investment(&context)?;
-    
-    // 14. Investment Limits - Accredited vs non-accredited caps
-    assert_investment_limits(&context)?;
-    
-    // 15. Corporate UBO Validation - Beneficial ownership compliance
-    assert_corporate_ubo_compliance(&context)?;
-    
-    Ok(())
+    // CRITICAL GATEKEEPERS - Routed by collection, each document decoded once
+    // (see business_financing/dispatch.rs for the rules applied to each collection)
+    // Every rejection is a JSON ValidationError payload (see validation_error.rs)
+    assert_set_doc_rules(&context).map_err(to_rejection)
 }
 
 #[assert_delete_doc]
 fn assert_delete_doc(context: AssertDeleteDocContext) -> Result<(), String> {
+    // Audit Trail - Entries can never be deleted
+    assert_audit_log_delete(&context).map_err(to_rejection)?;
+    
+    // Revenue Reports - Filed reports and their revision history are never deleted
+    assert_revenue_report_delete(&context).map_err(to_rejection)?;
+    
+    // Concentration Exceptions - Only managers revoke them, and every revocation is audited
+    assert_concentration_exception_delete(&context).map_err(to_rejection)?;
+    
     // Admin Profile Deletion - Only super_admins can permanently remove admins
-    assert_can_delete_admin(&context)?;
+    assert_can_delete_admin(&context).map_err(to_rejection)?;
     
     Ok(())
 }
@@ -167,7 +190,7 @@ fn assert_delete_doc(context: AssertDeleteDocContext) -> Result<(), String> {
 #[assert_upload_asset]
 fn assert_upload_asset(context: AssertUploadAssetContext) -> Result<(), String> {
     // Document Upload Security - File type, size, and malware protection
-    assert_document_upload(&context)?;
+    assert_document_upload(&context).map_err(to_rejection)?;
     
     Ok(())
 }
@@ -71,6 +71,7 @@ export const applicationDataSchema = z.object({
   rejectionAllowsResubmit: z.boolean().optional(), // If false, application cannot be resubmitted
   requestedAt: z.string().optional(),
   resubmittedAt: z.string().optional(),
+  isResubmission: z.boolean().optional(), // Set when resubmitting a rejected application
   
   // Contract Terms Review (Admin workflow)
   contractTermsStatus: z.enum(["pending", "approved", "counter-offered", "revision-requested"]).optional(),
@@ -117,6 +118,13 @@ export const applicationDataSchema = z.object({
       noProhibitedSectors: z.boolean(),
     }),
   }).optional(),
+  // Structured checklist - the satellite rejects a dueDiligenceScore that does not match it
+  dueDiligenceChecklist: z.record(z.string(), z.object({
+    status: z.enum(["pass", "fail", "na", "unknown"]),
+    reviewedBy: z.string().optional(),
+    reviewedAt: z.string().optional(), // ISO 8601 (RFC 3339)
+    notes: z.string().optional(),
+  })).optional(),
   dueDiligenceNotes: z.string().optional(),
   riskRating: z.enum(['low', 'moderate', 'high']).optional(),
   dueDiligenceScore: z.number().optional(),
@@ -45,8 +45,12 @@ export {
 export {
   opportunitySchema,
   investmentTransactionSchema,
+  opportunityAmendmentSchema,
+  concentrationExceptionSchema,
   type OpportunityFormData,
   type InvestmentTransaction,
+  type OpportunityAmendment,
+  type ConcentrationException,
 } from "./opportunity.schema";
 
 // Revenue and financial schemas
@@ -56,6 +60,7 @@ export {
   calculateProfitDistribution,
   calculateFinancialMetrics,
   type RevenueReport,
+  type RevenueReportRevision,
   type FinancialMetrics,
 } from "./revenue.schema";
 
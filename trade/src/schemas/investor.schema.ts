@@ -16,6 +16,10 @@ const baseInvestorFields = {
   kycStatus: z.enum(["pending", "in-review", "verified", "rejected"]).default("pending"),
   kycDocuments: z.array(z.string()).default([]),
   accredited: z.boolean().default(false),
+  investorTier: z.enum(["retail", "sophisticated", "institutional"]).optional(), // Investment limits tier (defaults from `accredited`)
+  annualIncome: z.number().nonnegative().optional(), // Net annual income (₦) - retail investors may invest 10% per 12 months
+  accountStatus: z.enum(["active", "suspended"]).default("active"), // Suspended members cannot invest
+  kycReviewDate: z.string().regex(/^\d{4}-\d{2}-\d{2}$/, "Invalid date format (YYYY-MM-DD)").optional(), // Periodic KYC re-review due date
   createdAt: z.bigint().optional(),
   updatedAt: z.bigint().optional(),
   
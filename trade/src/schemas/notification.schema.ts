@@ -28,6 +28,15 @@ export const notificationSchema = z.object({
     "application_escalated",
     "review_completed",
     "status_changed",
+    // Revenue reporting schedule (written by the satellite)
+    "report_overdue",
+    "report_escalated",
+    // Funding deadline expiry (written by the satellite)
+    "investment_refunded",
+    // Investment cooling-off (written by the satellite)
+    "investment_confirmed",
+    // Opportunity term amendments (written by the satellite)
+    "opportunity_amended",
   ]),
   title: z.string().min(1, "Title required").max(200),
   message: z.string().min(1, "Message required").max(1000),
@@ -54,6 +63,13 @@ export const notificationSchema = z.object({
     allowsResubmit: z.boolean().optional(),
     rejectedAt: z.string().optional(),
     reviewStartedAt: z.string().optional(),
+    // Reporting schedule metadata
+    periodStart: z.string().optional(),
+    periodEnd: z.string().optional(),
+    dueDate: z.string().optional(),
+    escalationLevel: z.number().optional(),
+    // Amendment metadata
+    amendmentId: z.string().optional(),
   }).optional(),
   createdAt: z.bigint().optional(),
   readAt: z.bigint().optional(),
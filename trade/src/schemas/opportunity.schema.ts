@@ -1,4 +1,5 @@
 import { z } from "zod";
+import { CONTRACT_TYPES, normalizeContractType } from "@/utils/contract-type";
 
 /**
  * Investment Opportunity Schema
@@ -39,22 +40,29 @@ export const opportunitySchema = z.object({
   
   // Funding Details
   fundingGoal: z.number().positive("Funding goal must be positive"),
-  currentFunding: z.number().min(0, "Current funding cannot be negative").default(0),
+  currentFunding: z.number().min(0, "Current funding cannot be negative").default(0), // Maintained by the satellite
   minimumInvestment: z.number().positive("Minimum investment must be positive"),
   
   // Contract Terms
-  contractType: z.enum(["musharakah", "mudarabah", "murabaha", "ijarah"]),
+  contractType: z.preprocess(normalizeContractType, z.enum(CONTRACT_TYPES)),
   expectedReturnMin: z.number().positive("Expected return must be positive"),
   expectedReturnMax: z.number().positive("Expected return must be positive"),
   termMonths: z.number().int().positive("Term must be positive"),
+  reportingPeriod: z.enum(["monthly", "quarterly"]).default("quarterly"), // Revenue reporting cadence once funded
   
   // Campaign Settings
-  campaignDeadline: z.string().regex(/^\d{2}-\d{2}-\d{4}$/, "Date must be in DD-MM-YYYY format"),
+  campaignDeadline: z.string().regex(/^\d{2}-\d{2}-\d{4}$/, "Date must be in DD-MM-YYYY format"), // End of the funding window
+  minimumViableFunding: z.number().positive().optional(), // Proceeds partially funded at the deadline if reached, otherwise cancelled and refunded
   featured: z.boolean().default(false),
   status: z.enum(["active", "funded", "expired", "cancelled"]).default("active"),
+  // Set by the satellite when the funding window closes
+  partiallyFunded: z.boolean().optional(),
+  closureReason: z.enum(["funding_deadline"]).optional(),
+  closedAt: z.string().optional(),
+  refundedAmount: z.number().optional(),
   
   // Tracking
-  investorCount: z.number().int().min(0).default(0),
+  investorCount: z.number().int().min(0).default(0), // Maintained by the satellite
   createdAt: z.string().regex(/^\d{2}-\d{2}-\d{4}$/, "Date must be in DD-MM-YYYY format"),
   approvedBy: z.string().min(1, "Admin ID is required"),
 });
@@ -78,7 +86,7 @@ export const investmentTransactionSchema = z.object({
   
   // Investment Details
   amount: z.number().positive("Investment amount must be positive"),
-  contractType: z.enum(["musharakah", "mudarabah", "murabaha", "ijarah"]),
+  contractType: z.preprocess(normalizeContractType, z.enum(CONTRACT_TYPES)),
   
   // Terms
   expectedReturnMin: z.number().positive(),
@@ -86,9 +94,13 @@ export const investmentTransactionSchema = z.object({
   termMonths: z.number().int().positive(),
   
   // Tracking
-  status: z.enum(["pending", "active", "completed", "defaulted"]).default("active"),
+  status: z.enum(["pending", "active", "cancelled", "completed", "defaulted", "refunded"]).default("pending"), // pending during the 48h cooling-off period
   transactionDate: z.string().regex(/^\d{2}-\d{2}-\d{4}$/, "Date must be in DD-MM-YYYY format"),
   
+  confirmedAt: z.string().optional(), // Set by the satellite when the cooling-off period ends
+  cancelledAt: z.string().optional(),
+  refundTransactionId: z.string().optional(),
+  
   // Performance (updated over time)
   actualReturn: z.number().optional(),
   lastDistribution: z.string().regex(/^\d{2}-\d{2}-\d{4}$/, "Date must be in DD-MM-YYYY format").optional(),
@@ -96,3 +108,45 @@ export const investmentTransactionSchema = z.object({
 });
 
 export type InvestmentTransaction = z.infer<typeof investmentTransactionSchema>;
+
+/**
+ * Opportunity Amendment Schema
+ * Terms of an opportunity with investments are frozen by the satellite; changing them
+ * requires an amendment raised by an admin and approved by a different manager.
+ * The satellite applies approved terms and notifies every investor.
+ */
+export const opportunityAmendmentSchema = z.object({
+  opportunityId: z.string().min(1, "Opportunity ID is required"),
+  terms: z.object({
+    expectedReturnMin: z.number().positive().optional(),
+    expectedReturnMax: z.number().positive().optional(),
+    termMonths: z.number().int().positive().optional(),
+    contractType: z.preprocess(normalizeContractType, z.enum(CONTRACT_TYPES)).optional(),
+    minimumInvestment: z.number().positive().optional(),
+  }),
+  reason: z.string().min(10, "Explain why the terms change").max(1000),
+  status: z.enum(["pending", "approved", "rejected"]).default("pending"),
+  requestedBy: z.string().min(1, "Requester ID is required"),
+  decidedBy: z.string().optional(), // Manager, never the requester
+  decidedAt: z.string().optional(),
+  decisionNotes: z.string().optional(),
+});
+
+export type OpportunityAmendment = z.infer<typeof opportunityAmendmentSchema>;
+
+/**
+ * Concentration Exception Schema (key = opportunityId)
+ * Lifts the per-investor caps of `platform_settings/concentration_limits`
+ * (default: 25% of an opportunity, ₦20M per issuer) for one opportunity.
+ * Granted by managers only; the satellite audits every exception.
+ */
+export const concentrationExceptionSchema = z.object({
+  opportunityId: z.string().min(1, "Opportunity ID is required"),
+  investorId: z.string().optional(), // Limit the exception to one investor
+  maxOpportunityShare: z.number().positive().max(100).optional(), // % of the funding goal
+  maxIssuerExposure: z.number().positive().optional(), // ₦ across the issuer's opportunities
+  reason: z.string().min(10, "Explain why the exception is granted").max(1000),
+  grantedBy: z.string().min(1, "Manager ID is required"),
+});
+
+export type ConcentrationException = z.infer<typeof concentrationExceptionSchema>;
@@ -1,4 +1,5 @@
 import { z } from "zod";
+import { CONTRACT_TYPES, normalizeContractType } from "@/utils/contract-type";
 
 /**
  * Profit Distribution Schema
@@ -7,8 +8,10 @@ import { z } from "zod";
 
 export const profitDistributionSchema = z.object({
   opportunityId: z.string().min(1, "Opportunity ID is required"),
+  applicationId: z.string().optional(),
+  revenueReportId: z.string().optional(), // Set on drafts generated from an approved revenue report
   businessName: z.string().min(1, "Business name is required"),
-  contractType: z.enum(["musharakah", "mudarabah", "murabaha", "ijarah"]),
+  contractType: z.preprocess(normalizeContractType, z.enum(CONTRACT_TYPES)),
   
   // Distribution details
   distributionPeriod: z.string().min(1, "Distribution period is required"), // e.g., "Q1 2024", "Jan 2024"
@@ -19,11 +22,21 @@ export const profitDistributionSchema = z.object({
   investorCount: z.number().min(1, "Must have at least one investor"),
   totalInvestedAmount: z.number().min(0, "Total invested amount must be positive"),
   
+  // Contract split (drafts generated by the satellite)
+  periodStart: z.string().optional(),
+  periodEnd: z.string().optional(),
+  reportedNetProfit: z.number().optional(),
+  investorSharePercentage: z.number().min(0).max(100).optional(),
+  businessSharePercentage: z.number().min(0).max(100).optional(),
+  businessShareAmount: z.number().optional(),
+  
   // Status
-  status: z.enum(["pending", "processing", "completed", "failed"]).default("pending"),
+  // "draft" = generated from an approved revenue report, awaiting manager confirmation
+  status: z.enum(["draft", "pending", "processing", "completed", "failed"]).default("pending"),
   
   // Metadata
   processedBy: z.string().optional(), // Admin user ID who processed
+  createdBy: z.string().optional(), // "satellite" for generated drafts
   notes: z.string().optional(),
   createdAt: z.number(),
   completedAt: z.number().optional(),
@@ -58,6 +58,14 @@ export const revenueReportSchema = z.object({
   notes: z.string().max(2000, "Notes too long").optional(),
   significantEvents: z.string().max(1000, "Description too long").optional(),
   
+  // Anomaly review (computed by the satellite on filed reports - never set by clients)
+  anomalyFlags: z.array(z.object({
+    code: z.string(),
+    weight: z.number(),
+    message: z.string(),
+  })).optional(),
+  anomalyScore: z.number().min(0).max(100).optional(),
+  
   // Contract-Specific Fields
   // Murabaha (Cost-Plus Financing)
   murabahaDetails: z.object({
@@ -117,11 +125,13 @@ export const revenueReportSchema = z.object({
   }).optional(),
   
   // Status and audit
-  status: z.enum(["draft", "submitted", "under_review", "approved", "rejected"]).default("draft"),
+  // Workflow enforced by the satellite (revenue_report_workflow.rs)
+  status: z.enum(["draft", "submitted", "under_review", "approved", "revision_requested"]).default("draft"),
   submittedAt: z.string().optional(),
   reviewedAt: z.string().optional(),
   reviewedBy: z.string().optional(),
-  reviewNotes: z.string().optional(),
+  reviewNotes: z.string().optional(), // Required on approval
+  revisionNotes: z.string().optional(), // Required when requesting revision
   
   // Verification
   certifiedBy: z.string().optional(), // Business owner name
@@ -132,6 +142,23 @@ export const revenueReportSchema = z.object({
 
 export type RevenueReport = z.infer<typeof revenueReportSchema>;
 
+/**
+ * Revenue Report Revision
+ * Immutable snapshot written by the satellite each time a report is (re)submitted.
+ * `changes` maps dotted field paths (e.g. "murabahaDetails.assetCost") to before/after values.
+ */
+export interface RevenueReportRevision {
+  reportKey: string;
+  applicationId: string;
+  revision: number;
+  previousRevisionKey: string | null;
+  submittedBy: string;
+  submittedAt: string;
+  revisionNotes?: string | null;
+  report: Partial<RevenueReport>;
+  changes: Record<string, { before: unknown; after: unknown }> | null;
+}
+
 /**
  * Profit Distribution Schema
  * Calculates profit sharing for investors based on contract type
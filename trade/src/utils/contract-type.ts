@@ -0,0 +1,32 @@
+/**
+ * Contract Type Spelling
+ * Mirrors CONTRACT_TYPES and canonical_contract_type in the satellite's islamic_contract_validation.rs,
+ * which compares an opportunity's contract type with its application's after normalisation
+ */
+
+/**
+ * Canonical spellings stored on applications, opportunities and revenue reports
+ */
+export const CONTRACT_TYPES = ["musharaka", "mudaraba", "murabaha", "ijara", "istisna"] as const;
+
+export type ContractType = (typeof CONTRACT_TYPES)[number];
+
+/**
+ * Alternative spellings found on older opportunities and distributions
+ */
+const CONTRACT_TYPE_ALIASES: Record<string, ContractType> = {
+  musharakah: "musharaka",
+  mudarabah: "mudaraba",
+  ijarah: "ijara",
+  istisnaa: "istisna",
+};
+
+/**
+ * Canonical spelling of a contract type; other values are returned unchanged so schema validation can reject them
+ */
+export function normalizeContractType(value: unknown): unknown {
+  if (typeof value !== "string") return value;
+
+  const lower = value.trim().toLowerCase();
+  return CONTRACT_TYPE_ALIASES[lower] ?? lower;
+}
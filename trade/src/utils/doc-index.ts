@@ -0,0 +1,47 @@
+/**
+ * Description Indexes
+ * Mirrors doc_index.rs in the satellite, which looks documents up by the references
+ * in their description and rejects writes whose description does not match the data
+ */
+
+/**
+ * One `name:value;` entry per reference, in the given order
+ */
+function indexDescription(entries: [name: string, value: string][]): string {
+  return entries.map(([name, value]) => `${name}:${value};`).join("");
+}
+
+/**
+ * Description of a revenue report: the application it reports on
+ */
+export function revenueReportIndex(report: { applicationId: string }): string {
+  return indexDescription([["application", report.applicationId]]);
+}
+
+/**
+ * Description of an opportunity: the application it was created from and its issuer
+ * (the business, or the application when no business is recorded)
+ */
+export function opportunityIndex(opportunity: { applicationId: string; businessId?: string }): string {
+  return indexDescription([
+    ["application", opportunity.applicationId],
+    ["issuer", opportunity.businessId || opportunity.applicationId],
+  ]);
+}
+
+/**
+ * Description of an investment: its investor and the opportunity it funds
+ */
+export function investmentIndex(investment: { investorId: string; opportunityId?: string }): string {
+  return indexDescription([
+    ["investor", investment.investorId],
+    ["opportunity", investment.opportunityId ?? ""],
+  ]);
+}
+
+/**
+ * Description of a withdrawal request: the member whose wallet it draws on
+ */
+export function withdrawalRequestIndex(request: { userId: string }): string {
+  return indexDescription([["user", request.userId]]);
+}
@@ -0,0 +1,47 @@
+/**
+ * Due Diligence Checklist Score
+ * Mirrors CHECKLIST_ITEMS and evaluate_checklist in the satellite's due_diligence.rs,
+ * which rejects any dueDiligenceScore that does not match the checklist
+ */
+
+import type { ApplicationData } from "@/schemas";
+
+type Checklist = NonNullable<ApplicationData["dueDiligenceChecklist"]>;
+
+/**
+ * Checklist items and whether approval depends on them
+ */
+const CHECKLIST_ITEMS: { key: string; mandatory: boolean }[] = [
+  { key: "siteVisit", mandatory: true },
+  { key: "bankVerification", mandatory: true },
+  { key: "cacSearch", mandatory: true },
+  { key: "references", mandatory: true },
+  { key: "financialStatements", mandatory: true },
+  { key: "directorsKyc", mandatory: true },
+  { key: "taxCompliance", mandatory: true },
+  { key: "shariahScreening", mandatory: true },
+  { key: "creditBureauCheck", mandatory: false },
+  { key: "collateralValuation", mandatory: false },
+  { key: "insuranceCoverage", mandatory: false },
+];
+
+/**
+ * Percentage of applicable checklist items that passed (0-100)
+ * Missing mandatory items count as not done; missing optional items are not applicable
+ */
+export function calculateChecklistScore(checklist: Checklist | undefined): number {
+  let applicable = 0;
+  let passed = 0;
+
+  for (const item of CHECKLIST_ITEMS) {
+    const entry = checklist?.[item.key];
+    const status = entry?.status ?? "unknown";
+
+    if (status === "na" || (!item.mandatory && !entry)) continue;
+
+    applicable++;
+    if (status === "pass") passed++;
+  }
+
+  return applicable > 0 ? Math.round((passed / applicable) * 100) : 0;
+}
@@ -1,10 +1,13 @@
 import { setDoc, getDoc, type Doc } from "@junobuild/core";
-import { investmentTransactionSchema, opportunitySchema, type InvestmentTransaction, type OpportunityFormData, type Wallet } from "@/schemas";
+import { investmentTransactionSchema, type InvestmentTransaction, type OpportunityFormData, type Wallet } from "@/schemas";
 import { validateOrThrow } from "./validation";
+import { investmentIndex } from "./doc-index";
+import { getValidationMessage } from "./validation-error";
 
 /**
  * Process an investment transaction
- * Updates opportunity funding, creates investment record, updates wallet, and creates transaction
+ * Creates the investment record; the satellite debits the wallet, records the
+ * transaction and updates the opportunity's funding totals
  */
 export async function processInvestment(params: {
   userId: string;
@@ -96,7 +99,7 @@ export async function processInvestment(params: {
       expectedReturnMin: opportunity.expectedReturnMin,
       expectedReturnMax: opportunity.expectedReturnMax,
       termMonths: opportunity.termMonths,
-      status: "active",
+      status: "pending", // Confirmed by the satellite once the cooling-off period ends
       transactionDate: formatDate(today),
     };
 
@@ -113,88 +116,72 @@ export async function processInvestment(params: {
       doc: {
         key: investmentId,
         data: validatedInvestment,
+        description: investmentIndex(validatedInvestment),
       },
     });
 
-    // 7. Update wallet balance
-    const updatedWallet = {
-      ...walletDoc.data,
-      availableBalance: walletDoc.data.availableBalance - amount,
-      totalBalance: walletDoc.data.totalBalance - amount,
-      totalInvested: walletDoc.data.totalInvested + amount,
-      updatedAt: Date.now(),
-    };
+    // 7. Wallet debit and the investment transaction are settled by the satellite
+    // in the same call that stores the investment (rejected if the balance is short)
 
-    await setDoc({
-      collection: "wallets",
-      doc: {
-        key: userId,
-        data: updatedWallet,
-        version: walletDoc.version,
-      },
-    });
+    // 8. Opportunity funding (currentFunding, investorCount, active -> funded) is
+    // recomputed by the satellite from the committed investment above
 
-    // 8. Create transaction record
-    const transactionId = `${userId}_${Date.now()}`;
-    await setDoc({
-      collection: "transactions",
-      doc: {
-        key: transactionId,
-        data: {
-          userId,
-          type: 'investment',
-          status: 'completed',
-          amount,
-          description: `Investment in ${opportunity.businessName}`,
-          reference: investmentId,
-          createdAt: Date.now(),
-          metadata: {
-            opportunityId: opportunityDoc.key,
-            businessName: opportunity.businessName,
-            contractType: opportunity.contractType,
-            expectedReturnMin: opportunity.expectedReturnMin.toString(),
-            expectedReturnMax: opportunity.expectedReturnMax.toString(),
-            termMonths: opportunity.termMonths.toString(),
-          }
-        }
-      }
-    });
-
-    // 9. Update opportunity funding
-    const updatedOpportunity: OpportunityFormData = {
-      ...opportunity,
-      currentFunding: opportunity.currentFunding + amount,
-      investorCount: opportunity.investorCount + 1,
-      status:
-        opportunity.currentFunding + amount >= opportunity.fundingGoal
-          ? "funded"
-          : "active",
+    return {
+      success: true,
+      message: `Successfully invested RM ${amount.toLocaleString()} in ${opportunity.businessName}`,
+      investmentId,
+    };
+  } catch (error) {
+    console.error("Error processing investment:", error);
+    return {
+      success: false,
+      message: error instanceof Error ? getValidationMessage(error) : "Failed to process investment",
     };
+  }
+}
 
-    const validatedOpportunity = validateOrThrow(
-      opportunitySchema,
-      updatedOpportunity,
-      "Update opportunity"
-    );
+/**
+ * Cooling-off period during which a pending investment can be cancelled
+ * (mirrors COOLING_OFF_PERIOD in the satellite, which enforces it)
+ */
+export const COOLING_OFF_MS = 48 * 60 * 60 * 1000;
+
+/**
+ * Whether an investment created at `createdAt` (nanoseconds) can still be cancelled
+ */
+export function isWithinCoolingOff(createdAt: bigint | undefined): boolean {
+  if (!createdAt) return false;
+  return Date.now() < Number(createdAt) / 1_000_000 + COOLING_OFF_MS;
+}
+
+/**
+ * Cancel a pending investment during its cooling-off period
+ * (the satellite refunds the amount to the wallet and updates the opportunity's funding)
+ */
+export async function cancelInvestment<T extends { status: string }>(
+  investment: Doc<T>
+): Promise<{ success: boolean; message: string }> {
+  if (investment.data.status !== "pending" || !isWithinCoolingOff(investment.created_at)) {
+    return { success: false, message: "The cooling-off period for this investment has ended" };
+  }
 
+  try {
     await setDoc({
-      collection: "opportunities",
+      collection: "investments",
       doc: {
-        ...opportunityDoc,
-        data: validatedOpportunity,
+        key: investment.key,
+        data: { ...investment.data, status: "cancelled" },
+        description: investment.description,
+        version: investment.version,
       },
     });
 
-    return {
-      success: true,
-      message: `Successfully invested RM ${amount.toLocaleString()} in ${opportunity.businessName}`,
-      investmentId,
-    };
+    return { success: true, message: "Investment cancelled. The amount has been refunded to your wallet." };
   } catch (error) {
-    console.error("Error processing investment:", error);
+    console.error("Error cancelling investment:", error);
     return {
       success: false,
-      message: error instanceof Error ? error.message : "Failed to process investment",
+      message: error instanceof Error ? getValidationMessage(error) : "Failed to cancel investment",
     };
   }
 }
@@ -0,0 +1,30 @@
+/**
+ * Reviewer-owned Investor Profile Fields
+ * Mirrors reviewed_profile_changes in the satellite's member_validation.rs, which rejects
+ * member writes that change these fields (kycStatus may only move to pending or in-review)
+ */
+export const REVIEWED_PROFILE_FIELDS = [
+  "accountStatus",
+  "kycReviewDate",
+  "accredited",
+  "investorTier",
+  "annualIncome",
+] as const;
+
+/**
+ * Member-written profile data with the reviewer-owned fields of the stored profile kept as they are
+ */
+export function keepReviewedFields<T extends Record<string, unknown>>(
+  data: T,
+  existing: Record<string, unknown> | undefined
+): T {
+  const kept: Record<string, unknown> = { ...data };
+  for (const field of REVIEWED_PROFILE_FIELDS) {
+    if (existing && field in existing) {
+      kept[field] = existing[field];
+    } else {
+      delete kept[field];
+    }
+  }
+  return kept as T;
+}
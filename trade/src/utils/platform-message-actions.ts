@@ -1,5 +1,6 @@
 import { setDoc, listDocs, getDoc, type Doc } from "@junobuild/core";
 import type { PlatformMessage, PlatformMessageType, MessageAttachment, SendMessageRequest, MessageResponse } from "@/schemas";
+import { getValidationMessage } from "./validation-error";
 
 /**
  * Send a message from platform to business
@@ -329,7 +330,7 @@ export async function sendBusinessToAdminMessage(
     console.error("Error sending business message:", error);
     return { 
       success: false, 
-      error: error instanceof Error ? error.message : "Failed to send message" 
+      error: error instanceof Error ? getValidationMessage(error) : "Failed to send message" 
     };
   }
 }
@@ -421,7 +422,7 @@ export async function replyToThread(
     console.error("Error replying to thread:", error);
     return { 
       success: false, 
-      error: error instanceof Error ? error.message : "Failed to send reply" 
+      error: error instanceof Error ? getValidationMessage(error) : "Failed to send reply" 
     };
   }
 }
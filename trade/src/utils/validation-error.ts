@@ -0,0 +1,66 @@
+/**
+ * Structured satellite rejections
+ * Mirrors `src/satellite/src/business_financing/validation_error.rs`
+ */
+
+export type ValidationSeverity = "error" | "critical";
+
+export interface SatelliteValidationError {
+  /** Stable, machine-readable code (e.g. "APPROVAL_LIMIT_EXCEEDED") */
+  code: string;
+  /** Offending document field, when the rule targets one */
+  field?: string;
+  /** Values needed to render the message (limit, actual, role, ...) */
+  params: Record<string, unknown>;
+  severity: ValidationSeverity;
+  /** English fallback message */
+  message: string;
+}
+
+/** Code used for rejections without a dedicated code */
+export const GENERIC_VALIDATION_CODE = "VALIDATION_FAILED";
+
+/**
+ * Extracts the satellite ValidationError from a failed datastore call
+ * The payload may be wrapped in the IC reject message, so the JSON object is located first
+ * Returns null when the error did not come from a satellite rule
+ */
+export function parseValidationError(error: unknown): SatelliteValidationError | null {
+  const text = error instanceof Error ? error.message : typeof error === "string" ? error : "";
+
+  const start = text.indexOf('{"code"');
+  const end = text.lastIndexOf("}");
+  if (start === -1 || end < start) {
+    return null;
+  }
+
+  try {
+    const parsed = JSON.parse(text.slice(start, end + 1));
+    if (typeof parsed?.code !== "string" || typeof parsed?.message !== "string") {
+      return null;
+    }
+
+    return {
+      code: parsed.code,
+      field: typeof parsed.field === "string" ? parsed.field : undefined,
+      params: parsed.params ?? {},
+      severity: parsed.severity === "critical" ? "critical" : "error",
+      message: parsed.message,
+    };
+  } catch {
+    return null;
+  }
+}
+
+/**
+ * Human-readable message for a failed datastore call
+ * Falls back to the raw error text when no structured payload is present
+ */
+export function getValidationMessage(error: unknown): string {
+  const parsed = parseValidationError(error);
+  if (parsed) {
+    return parsed.message;
+  }
+
+  return error instanceof Error ? error.message : String(error);
+}